@@ -0,0 +1,588 @@
+//! Blackjack engine: cards, a multi-deck shoe and the rules of one round.
+//! Pure game logic — no Discord, storage or wallet code lives here. The bot
+//! escrows `extra_stake` before every action and pays out `payout` at the end.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Most hands a player can hold after splitting.
+pub const MAX_HANDS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Suit {
+    Spades,
+    Hearts,
+    Diamonds,
+    Clubs,
+}
+
+impl Suit {
+    const ALL: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Suit::Spades => "♠",
+            Suit::Hearts => "♥",
+            Suit::Diamonds => "♦",
+            Suit::Clubs => "♣",
+        }
+    }
+}
+
+/// A playing card. `rank` is 1 for an ace, 2–10 for pips and 11–13 for J, Q, K.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Card {
+    pub rank: u8,
+    pub suit: Suit,
+}
+
+impl Card {
+    pub fn new(rank: u8, suit: Suit) -> Self {
+        Self { rank, suit }
+    }
+
+    /// Blackjack value; aces count as 11 here and are reduced in `hand_value`.
+    pub fn value(self) -> u8 {
+        match self.rank {
+            1 => 11,
+            11..=13 => 10,
+            r => r,
+        }
+    }
+
+    pub fn is_ace(self) -> bool {
+        self.rank == 1
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rank = match self.rank {
+            1 => "A".to_string(),
+            11 => "J".to_string(),
+            12 => "Q".to_string(),
+            13 => "K".to_string(),
+            r => r.to_string(),
+        };
+        write!(f, "{}{}", rank, self.suit.symbol())
+    }
+}
+
+/// Total of a hand and whether it is soft (an ace still counts as 11).
+pub fn hand_value(cards: &[Card]) -> (u8, bool) {
+    let mut sum: u16 = cards.iter().map(|c| c.value() as u16).sum();
+    let mut aces = cards.iter().filter(|c| c.is_ace()).count();
+    while sum > 21 && aces > 0 {
+        sum -= 10;
+        aces -= 1;
+    }
+    (sum.min(u8::MAX as u16) as u8, aces > 0)
+}
+
+pub fn format_cards(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Several shuffled decks dealt from the top.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Shoe {
+    cards: Vec<Card>,
+}
+
+impl Shoe {
+    pub fn new<R: Rng>(decks: u8, rng: &mut R) -> Self {
+        let mut cards = Vec::with_capacity(decks.max(1) as usize * 52);
+        for _ in 0..decks.max(1) {
+            for suit in Suit::ALL {
+                for rank in 1..=13 {
+                    cards.push(Card::new(rank, suit));
+                }
+            }
+        }
+        cards.shuffle(rng);
+        Self { cards }
+    }
+
+    /// A shoe that deals `cards` in the given order.
+    #[cfg(test)]
+    pub fn stacked(cards: &[Card]) -> Self {
+        Self {
+            cards: cards.iter().rev().copied().collect(),
+        }
+    }
+
+    /// One round never comes close to emptying a shoe; should it happen,
+    /// a fresh deck is added so the round can still finish.
+    pub fn draw(&mut self) -> Card {
+        if self.cards.is_empty() {
+            for suit in Suit::ALL {
+                for rank in 1..=13 {
+                    self.cards.push(Card::new(rank, suit));
+                }
+            }
+        }
+        self.cards.pop().unwrap_or(Card::new(10, Suit::Spades))
+    }
+}
+
+/// Table rules, configurable by admins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rules {
+    #[serde(default = "default_decks")]
+    pub decks: u8,
+    #[serde(default)]
+    pub dealer_hits_soft_17: bool,
+}
+
+fn default_decks() -> u8 {
+    6
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            decks: default_decks(),
+            dealer_hits_soft_17: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hand {
+    pub cards: Vec<Card>,
+    pub bet: u64,
+    #[serde(default)]
+    pub doubled: bool,
+    #[serde(default)]
+    pub done: bool,
+    #[serde(default)]
+    pub from_split: bool,
+}
+
+impl Hand {
+    fn new(bet: u64) -> Self {
+        Self {
+            cards: Vec::new(),
+            bet,
+            doubled: false,
+            done: false,
+            from_split: false,
+        }
+    }
+
+    pub fn value(&self) -> u8 {
+        hand_value(&self.cards).0
+    }
+
+    pub fn is_bust(&self) -> bool {
+        self.value() > 21
+    }
+
+    /// A natural: two-card 21 that did not come from a split.
+    pub fn is_blackjack(&self) -> bool {
+        !self.from_split && self.cards.len() == 2 && self.value() == 21
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Phase {
+    /// Dealer shows an ace; the player decides on insurance first.
+    Insurance,
+    Player,
+    Finished,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Insurance,
+    DeclineInsurance,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Blackjack,
+    Win,
+    Push,
+    Lose,
+    Bust,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Game {
+    pub rules: Rules,
+    shoe: Shoe,
+    pub hands: Vec<Hand>,
+    pub active: usize,
+    pub dealer: Vec<Card>,
+    #[serde(default)]
+    pub insurance: u64,
+    pub phase: Phase,
+}
+
+impl Game {
+    /// Shuffles a new shoe and deals the opening cards.
+    pub fn deal<R: Rng>(rules: Rules, bet: u64, rng: &mut R) -> Self {
+        Self::with_shoe(rules, bet, Shoe::new(rules.decks, rng))
+    }
+
+    pub fn with_shoe(rules: Rules, bet: u64, mut shoe: Shoe) -> Self {
+        let mut hand = Hand::new(bet);
+        hand.cards.push(shoe.draw());
+        let up = shoe.draw();
+        hand.cards.push(shoe.draw());
+        let hole = shoe.draw();
+
+        let mut game = Self {
+            rules,
+            shoe,
+            hands: vec![hand],
+            active: 0,
+            dealer: vec![up, hole],
+            insurance: 0,
+            phase: if up.is_ace() {
+                Phase::Insurance
+            } else {
+                Phase::Player
+            },
+        };
+        if game.phase == Phase::Player {
+            game.check_naturals();
+        }
+        game
+    }
+
+    pub fn dealer_blackjack(&self) -> bool {
+        self.dealer.len() == 2 && hand_value(&self.dealer).0 == 21
+    }
+
+    /// Dealer peeks for a natural; either side's natural ends the round.
+    fn check_naturals(&mut self) {
+        if self.dealer_blackjack() || self.hands[0].is_blackjack() {
+            self.phase = Phase::Finished;
+        }
+    }
+
+    /// Chips the player must add to escrow before taking `action`.
+    pub fn extra_stake(&self, action: Action) -> u64 {
+        match action {
+            Action::Double | Action::Split => self.hands[self.active].bet,
+            Action::Insurance => self.hands[0].bet / 2,
+            _ => 0,
+        }
+    }
+
+    /// Everything the player has put on the table this round.
+    pub fn total_staked(&self) -> u64 {
+        self.hands.iter().map(|h| h.bet).sum::<u64>() + self.insurance
+    }
+
+    pub fn can(&self, action: Action) -> bool {
+        match self.phase {
+            Phase::Insurance => match action {
+                Action::Insurance => self.hands[0].bet / 2 > 0,
+                Action::DeclineInsurance => true,
+                _ => false,
+            },
+            Phase::Player => {
+                let hand = &self.hands[self.active];
+                match action {
+                    Action::Hit | Action::Stand => true,
+                    Action::Double => hand.cards.len() == 2 && !hand.doubled,
+                    Action::Split => {
+                        hand.cards.len() == 2
+                            && hand.cards[0].value() == hand.cards[1].value()
+                            && self.hands.len() < MAX_HANDS
+                            // Розділені тузи повторно не розділяються
+                            && !(hand.from_split && hand.cards[0].is_ace())
+                    }
+                    _ => false,
+                }
+            }
+            Phase::Finished => false,
+        }
+    }
+
+    pub fn apply(&mut self, action: Action) -> Result<(), &'static str> {
+        if !self.can(action) {
+            return Err("Ця дія зараз недоступна");
+        }
+        match action {
+            Action::Insurance | Action::DeclineInsurance => {
+                if action == Action::Insurance {
+                    self.insurance = self.hands[0].bet / 2;
+                }
+                self.phase = Phase::Player;
+                self.check_naturals();
+            }
+            Action::Hit => {
+                let card = self.shoe.draw();
+                let hand = &mut self.hands[self.active];
+                hand.cards.push(card);
+                if hand.value() >= 21 {
+                    hand.done = true;
+                }
+                self.advance();
+            }
+            Action::Stand => {
+                self.hands[self.active].done = true;
+                self.advance();
+            }
+            Action::Double => {
+                let card = self.shoe.draw();
+                let hand = &mut self.hands[self.active];
+                hand.bet = hand.bet.saturating_mul(2);
+                hand.doubled = true;
+                hand.cards.push(card);
+                hand.done = true;
+                self.advance();
+            }
+            Action::Split => {
+                let first = self.shoe.draw();
+                let second = self.shoe.draw();
+                let hand = &mut self.hands[self.active];
+                let moved = hand.cards.pop().unwrap_or(first);
+                hand.from_split = true;
+                hand.cards.push(first);
+
+                let mut other = Hand::new(hand.bet);
+                other.from_split = true;
+                other.cards = vec![moved, second];
+
+                // На розділених тузах — лише по одній карті
+                let aces = moved.is_ace();
+                for h in [&mut *hand, &mut other] {
+                    if aces || h.value() == 21 {
+                        h.done = true;
+                    }
+                }
+                self.hands.insert(self.active + 1, other);
+                self.advance();
+            }
+        }
+        Ok(())
+    }
+
+    /// Stands on every remaining hand and declines insurance (used on timeout).
+    pub fn stand_all(&mut self) {
+        if self.phase == Phase::Insurance {
+            self.phase = Phase::Player;
+            self.check_naturals();
+        }
+        if self.phase == Phase::Player {
+            for hand in &mut self.hands {
+                hand.done = true;
+            }
+            self.advance();
+        }
+    }
+
+    fn advance(&mut self) {
+        while self.active < self.hands.len() && self.hands[self.active].done {
+            self.active += 1;
+        }
+        if self.active >= self.hands.len() {
+            self.active = self.hands.len() - 1;
+            if self.hands.iter().any(|h| !h.is_bust()) {
+                self.dealer_play();
+            }
+            self.phase = Phase::Finished;
+        }
+    }
+
+    fn dealer_play(&mut self) {
+        loop {
+            let (value, soft) = hand_value(&self.dealer);
+            let hits = value < 17 || (value == 17 && soft && self.rules.dealer_hits_soft_17);
+            if !hits {
+                break;
+            }
+            let card = self.shoe.draw();
+            self.dealer.push(card);
+        }
+    }
+
+    pub fn outcome(&self, hand: &Hand) -> Outcome {
+        let dealer_bj = self.dealer_blackjack();
+        if hand.is_bust() {
+            Outcome::Bust
+        } else if hand.is_blackjack() {
+            if dealer_bj {
+                Outcome::Push
+            } else {
+                Outcome::Blackjack
+            }
+        } else if dealer_bj {
+            Outcome::Lose
+        } else {
+            let dealer = hand_value(&self.dealer).0;
+            let player = hand.value();
+            if dealer > 21 || player > dealer {
+                Outcome::Win
+            } else if player == dealer {
+                Outcome::Push
+            } else {
+                Outcome::Lose
+            }
+        }
+    }
+
+    /// Chips returned from escrow once the round is finished: stakes plus
+    /// winnings, 3:2 for a natural and 2:1 on insurance.
+    pub fn payout(&self) -> u64 {
+        if self.phase != Phase::Finished {
+            return 0;
+        }
+        let hands: u64 = self
+            .hands
+            .iter()
+            .map(|h| match self.outcome(h) {
+                Outcome::Blackjack => h.bet.saturating_add(h.bet.saturating_mul(3) / 2),
+                Outcome::Win => h.bet.saturating_mul(2),
+                Outcome::Push => h.bet,
+                Outcome::Lose | Outcome::Bust => 0,
+            })
+            .sum();
+        let insurance = if self.dealer_blackjack() {
+            self.insurance.saturating_mul(3)
+        } else {
+            0
+        };
+        hands.saturating_add(insurance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(rank: u8) -> Card {
+        Card::new(rank, Suit::Hearts)
+    }
+
+    /// Deal order: player, dealer up, player, dealer hole, then draws.
+    fn game(cards: &[u8], rules: Rules) -> Game {
+        let cards: Vec<Card> = cards.iter().map(|r| c(*r)).collect();
+        Game::with_shoe(rules, 100, Shoe::stacked(&cards))
+    }
+
+    #[test]
+    fn cards_display_with_suits_and_faces() {
+        assert_eq!(Card::new(1, Suit::Spades).to_string(), "A♠");
+        assert_eq!(Card::new(12, Suit::Diamonds).to_string(), "Q♦");
+        assert_eq!(format_cards(&[c(10), c(13)]), "10♥ K♥");
+        assert_eq!(hand_value(&[c(1), c(6)]), (17, true));
+        assert_eq!(hand_value(&[c(1), c(6), c(10)]), (17, false));
+    }
+
+    #[test]
+    fn shoe_holds_every_deck() {
+        let mut rng = rand::thread_rng();
+        let shoe = Shoe::new(6, &mut rng);
+        assert_eq!(shoe.cards.len(), 312);
+        assert_eq!(shoe.cards.iter().filter(|c| c.is_ace()).count(), 24);
+    }
+
+    #[test]
+    fn natural_pays_three_to_two() {
+        let g = game(&[1, 9, 13, 8], Rules::default());
+        assert_eq!(g.phase, Phase::Finished);
+        assert_eq!(g.outcome(&g.hands[0]), Outcome::Blackjack);
+        assert_eq!(g.payout(), 250);
+    }
+
+    #[test]
+    fn dealer_soft_17_is_configurable() {
+        // Дилер: A + 6 = м'які 17, далі в колоді 10
+        let mut stands = game(&[10, 1, 8, 6, 10], Rules::default());
+        stands.apply(Action::DeclineInsurance).unwrap();
+        stands.apply(Action::Stand).unwrap();
+        assert_eq!(stands.dealer.len(), 2);
+        assert_eq!(stands.payout(), 200);
+
+        let rules = Rules {
+            dealer_hits_soft_17: true,
+            ..Rules::default()
+        };
+        let mut hits = game(&[10, 1, 8, 6, 10], rules);
+        hits.apply(Action::DeclineInsurance).unwrap();
+        hits.apply(Action::Stand).unwrap();
+        assert_eq!(hits.dealer.len(), 3);
+        assert_eq!(hand_value(&hits.dealer).0, 17);
+        assert_eq!(hits.outcome(&hits.hands[0]), Outcome::Win);
+    }
+
+    #[test]
+    fn double_takes_one_card_and_doubles_the_bet() {
+        let mut g = game(&[5, 10, 6, 7, 10], Rules::default());
+        assert_eq!(g.extra_stake(Action::Double), 100);
+        g.apply(Action::Double).unwrap();
+        assert_eq!(g.phase, Phase::Finished);
+        assert_eq!(g.hands[0].bet, 200);
+        assert_eq!(g.hands[0].value(), 21);
+        assert_eq!(g.payout(), 400);
+    }
+
+    #[test]
+    fn split_aces_get_one_card_each_and_no_blackjack() {
+        let mut g = game(&[1, 9, 1, 8, 13, 12], Rules::default());
+        assert!(g.can(Action::Split));
+        g.apply(Action::Split).unwrap();
+        assert_eq!(g.hands.len(), 2);
+        assert!(g.hands.iter().all(|h| h.cards.len() == 2 && h.done));
+        assert_eq!(g.phase, Phase::Finished);
+        // 21 після розділення — звичайний виграш, не блекджек
+        assert!(g.hands.iter().all(|h| g.outcome(h) == Outcome::Win));
+        assert_eq!(g.total_staked(), 200);
+        assert_eq!(g.payout(), 400);
+    }
+
+    #[test]
+    fn split_pairs_play_each_hand_in_turn() {
+        let mut g = game(&[8, 10, 8, 7, 3, 10, 10], Rules::default());
+        g.apply(Action::Split).unwrap();
+        assert_eq!(g.active, 0);
+        assert_eq!(g.hands[0].value(), 11);
+        g.apply(Action::Hit).unwrap(); // 8+3+10 = 21, рука завершена
+        assert_eq!(g.active, 1);
+        g.apply(Action::Stand).unwrap(); // 8+10 = 18
+        assert_eq!(g.phase, Phase::Finished);
+        assert_eq!(g.outcome(&g.hands[0]), Outcome::Win);
+        assert_eq!(g.outcome(&g.hands[1]), Outcome::Win);
+    }
+
+    #[test]
+    fn insurance_pays_two_to_one_on_dealer_natural() {
+        let mut g = game(&[10, 1, 9, 13], Rules::default());
+        assert_eq!(g.phase, Phase::Insurance);
+        assert!(!g.can(Action::Hit));
+        assert_eq!(g.extra_stake(Action::Insurance), 50);
+        g.apply(Action::Insurance).unwrap();
+        assert_eq!(g.phase, Phase::Finished);
+        assert_eq!(g.outcome(&g.hands[0]), Outcome::Lose);
+        assert_eq!(g.payout(), 150);
+    }
+
+    #[test]
+    fn timeout_stands_and_bust_loses() {
+        let mut g = game(&[10, 9, 6, 8], Rules::default());
+        g.stand_all();
+        assert_eq!(g.phase, Phase::Finished);
+        assert_eq!(g.outcome(&g.hands[0]), Outcome::Lose);
+
+        let mut g = game(&[10, 9, 6, 8, 10], Rules::default());
+        g.apply(Action::Hit).unwrap();
+        assert_eq!(g.outcome(&g.hands[0]), Outcome::Bust);
+        assert_eq!(g.dealer.len(), 2);
+        assert_eq!(g.payout(), 0);
+    }
+}
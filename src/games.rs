@@ -0,0 +1,230 @@
+//! Odds and payouts for the single-round casino games: European roulette,
+//! slots, dice and coinflip duels. Pure logic; the bot moves the chips.
+//!
+//! Every function returns a gross payout — the amount handed back to the
+//! player including the stake — and `apply_edge` trims the winnings part by
+//! the configured house edge.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Red pockets of a European wheel.
+pub const RED_NUMBERS: [u8; 18] = [
+    1, 3, 5, 7, 9, 12, 14, 16, 18, 19, 21, 23, 25, 27, 30, 32, 34, 36,
+];
+
+/// Keeps the house edge (in percent) from the winnings of a gross payout.
+/// Losses and pushes are returned unchanged.
+pub fn apply_edge(stake: u64, gross: u64, edge_percent: f64) -> u64 {
+    if gross <= stake {
+        return gross;
+    }
+    let keep = (100.0 - edge_percent.clamp(0.0, 100.0)) / 100.0;
+    stake + ((gross - stake) as f64 * keep).floor() as u64
+}
+
+// --- РУЛЕТКА ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouletteBet {
+    Number(u8),
+    Red,
+    Black,
+    /// 1 — 1-12, 2 — 13-24, 3 — 25-36.
+    Dozen(u8),
+}
+
+pub fn spin_roulette<R: Rng>(rng: &mut R) -> u8 {
+    rng.gen_range(0..=36)
+}
+
+pub fn roulette_is_red(pocket: u8) -> bool {
+    RED_NUMBERS.contains(&pocket)
+}
+
+pub fn roulette_emoji(pocket: u8) -> &'static str {
+    if pocket == 0 {
+        "🟢"
+    } else if roulette_is_red(pocket) {
+        "🔴"
+    } else {
+        "⚫"
+    }
+}
+
+/// Gross payout of a roulette bet: 35:1 on a number, 1:1 on a colour and
+/// 2:1 on a dozen. Zero loses every outside bet.
+pub fn roulette_payout(bet: RouletteBet, stake: u64, pocket: u8) -> u64 {
+    let multiplier = match bet {
+        RouletteBet::Number(n) if n == pocket => 36,
+        RouletteBet::Red if pocket != 0 && roulette_is_red(pocket) => 2,
+        RouletteBet::Black if pocket != 0 && !roulette_is_red(pocket) => 2,
+        RouletteBet::Dozen(d) if pocket != 0 && (pocket - 1) / 12 + 1 == d => 3,
+        _ => 0,
+    };
+    stake.saturating_mul(multiplier)
+}
+
+// --- СЛОТИ ---
+
+/// One reel symbol with its weight and the stake multipliers for three of
+/// a kind and for a matching pair on the first two reels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlotSymbol {
+    pub emoji: String,
+    pub weight: u32,
+    pub three: u64,
+    #[serde(default)]
+    pub two: u64,
+}
+
+pub fn default_slot_table() -> Vec<SlotSymbol> {
+    [
+        ("🍒", 35, 5, 2),
+        ("🍋", 25, 8, 2),
+        ("🔔", 20, 12, 2),
+        ("⭐", 12, 25, 3),
+        ("💎", 6, 60, 5),
+        ("7️⃣", 2, 250, 10),
+    ]
+    .into_iter()
+    .map(|(emoji, weight, three, two)| SlotSymbol {
+        emoji: emoji.to_string(),
+        weight,
+        three,
+        two,
+    })
+    .collect()
+}
+
+/// Spins three reels; returns indices into `table`, or `None` if the table
+/// has no symbol with a positive weight.
+pub fn spin_slots<R: Rng>(table: &[SlotSymbol], rng: &mut R) -> Option<[usize; 3]> {
+    let total: u32 = table.iter().map(|s| s.weight).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut spin = || {
+        let mut roll = rng.gen_range(0..total);
+        table
+            .iter()
+            .position(|s| {
+                if roll < s.weight {
+                    true
+                } else {
+                    roll -= s.weight;
+                    false
+                }
+            })
+            .unwrap_or(0)
+    };
+    Some([spin(), spin(), spin()])
+}
+
+pub fn slots_payout(table: &[SlotSymbol], reels: [usize; 3], stake: u64) -> u64 {
+    let multiplier = match table.get(reels[0]) {
+        Some(s) if reels[1] == reels[0] && reels[2] == reels[0] => s.three,
+        Some(s) if reels[1] == reels[0] => s.two,
+        _ => 0,
+    };
+    stake.saturating_mul(multiplier)
+}
+
+// --- КУБИКИ ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiceCall {
+    Over,
+    Under,
+}
+
+/// Chance in percent that a 1-100 roll lands over/under `target`.
+pub fn dice_chance(call: DiceCall, target: u8) -> u64 {
+    let target = target.min(100) as u64;
+    match call {
+        DiceCall::Over => 100 - target,
+        DiceCall::Under => target.saturating_sub(1),
+    }
+}
+
+pub fn roll_dice<R: Rng>(rng: &mut R) -> u8 {
+    rng.gen_range(1..=100)
+}
+
+/// Fair gross payout: the stake times 100 / chance when the roll wins.
+pub fn dice_payout(call: DiceCall, target: u8, stake: u64, roll: u8) -> u64 {
+    let chance = dice_chance(call, target);
+    let wins = match call {
+        DiceCall::Over => roll > target,
+        DiceCall::Under => roll < target,
+    };
+    if !wins || chance == 0 {
+        return 0;
+    }
+    (stake as u128 * 100 / chance as u128).min(u64::MAX as u128) as u64
+}
+
+// --- ДУЕЛІ ---
+
+/// Splits a duel pot: the winner takes it minus the house rake.
+/// Returns (prize, rake).
+pub fn duel_prize(stake: u64, edge_percent: f64) -> (u64, u64) {
+    let pot = stake.saturating_mul(2);
+    let prize = apply_edge(0, pot, edge_percent);
+    (prize, pot - prize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edge_only_trims_winnings() {
+        assert_eq!(apply_edge(100, 200, 5.0), 195);
+        assert_eq!(apply_edge(100, 100, 5.0), 100);
+        assert_eq!(apply_edge(100, 0, 5.0), 0);
+        assert_eq!(apply_edge(100, 200, 0.0), 200);
+        assert_eq!(duel_prize(100, 5.0), (190, 10));
+    }
+
+    #[test]
+    fn roulette_pays_number_colour_and_dozen() {
+        assert_eq!(roulette_payout(RouletteBet::Number(17), 10, 17), 360);
+        assert_eq!(roulette_payout(RouletteBet::Number(17), 10, 18), 0);
+        assert_eq!(roulette_payout(RouletteBet::Red, 10, 1), 20);
+        assert_eq!(roulette_payout(RouletteBet::Black, 10, 2), 20);
+        assert_eq!(roulette_payout(RouletteBet::Black, 10, 0), 0);
+        assert_eq!(roulette_payout(RouletteBet::Dozen(1), 10, 12), 30);
+        assert_eq!(roulette_payout(RouletteBet::Dozen(2), 10, 13), 30);
+        assert_eq!(roulette_payout(RouletteBet::Dozen(3), 10, 0), 0);
+        assert_eq!(roulette_emoji(0), "🟢");
+    }
+
+    #[test]
+    fn slots_follow_the_payout_table() {
+        let table = default_slot_table();
+        assert_eq!(slots_payout(&table, [5, 5, 5], 10), 2500);
+        assert_eq!(slots_payout(&table, [0, 0, 3], 10), 20);
+        assert_eq!(slots_payout(&table, [0, 1, 0], 10), 0);
+
+        let only_bells = vec![SlotSymbol {
+            emoji: "🔔".to_string(),
+            weight: 1,
+            three: 3,
+            two: 0,
+        }];
+        let reels = spin_slots(&only_bells, &mut rand::thread_rng()).unwrap();
+        assert_eq!(reels, [0, 0, 0]);
+        assert!(spin_slots(&[], &mut rand::thread_rng()).is_none());
+    }
+
+    #[test]
+    fn dice_pays_inverse_of_chance() {
+        assert_eq!(dice_chance(DiceCall::Over, 50), 50);
+        assert_eq!(dice_chance(DiceCall::Under, 26), 25);
+        assert_eq!(dice_payout(DiceCall::Over, 50, 10, 51), 20);
+        assert_eq!(dice_payout(DiceCall::Over, 50, 10, 50), 0);
+        assert_eq!(dice_payout(DiceCall::Under, 26, 10, 25), 40);
+        assert_eq!(dice_payout(DiceCall::Over, 100, 10, 100), 0);
+    }
+}
@@ -1,4 +1,8 @@
-use chrono::{Datelike, Local, NaiveDate, Timelike, Utc};
+mod blackjack;
+mod games;
+
+use chrono::{Datelike, Local, NaiveDate, TimeZone, Timelike, Utc};
+use chrono_tz::Europe::Kyiv;
 use poise::serenity_prelude as serenity;
 use rand::Rng;
 use regex::Regex;
@@ -9,6 +13,7 @@ use serenity::{
 };
 use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Duration;
@@ -124,6 +129,41 @@ const AUTO_ROLES_FILE: &str = "auto_roles.json";
 const BANNED_WORDS_FILE: &str = "banned_words.json";
 const SUGGESTIONS_CHANNELS_FILE: &str = "suggestions_channels.json";
 const SUGGESTIONS_DATA_FILE: &str = "suggestions_data.json";
+const SUGGESTIONS_CONFIG_FILE: &str = "suggestions_config.json";
+const MODMAIL_CONFIG_FILE: &str = "modmail_config.json";
+const MODMAIL_THREADS_FILE: &str = "modmail_threads.json";
+const MODMAIL_TRANSCRIPTS_DIR: &str = "modmail_transcripts";
+const TAGS_FILE: &str = "tags.json";
+const AUTORESPONDERS_FILE: &str = "autoresponders.json";
+const SCHEDULES_FILE: &str = "schedules.json";
+const REMINDERS_FILE: &str = "reminders.json";
+const POLLS_FILE: &str = "polls.json";
+const MAX_POLL_OPTIONS: usize = 10;
+const GIVEAWAYS_FILE: &str = "giveaways.json";
+const BLACKJACK_FILE: &str = "blackjack_games.json";
+const BLACKJACK_TIMEOUT_SECS: i64 = 60;
+const CASINO_CONFIG_FILE: &str = "casino_config.json";
+// Журнал гривень: один JSON-запис на рядок, файл лише дописується
+const TRANSACTIONS_FILE: &str = "transactions.jsonl";
+// Старий формат журналу (JSON-масив), переноситься при запуску
+const LEGACY_TRANSACTIONS_FILE: &str = "transactions.json";
+const LEDGER_PAGE_SIZE: usize = 15;
+const ECONOMY_CONFIG_FILE: &str = "economy_config.json";
+const TRANSFER_FLAGS_FILE: &str = "transfer_flags.json";
+// Перекази, що за тиждень замикаються в коло, позначаються для адмінів
+const PAY_CYCLE_WINDOW_SECS: i64 = 7 * 86400;
+const PAY_CYCLE_MAX_HOPS: usize = 4;
+const DUELS_FILE: &str = "duels.json";
+const SHOP_FILE: &str = "shop.json";
+const ROLE_GRANTS_FILE: &str = "role_grants.json";
+const XP_BOOSTS_FILE: &str = "xp_boosts.json";
+// За скільки до закінчення тимчасової ролі надсилати нагадування
+const ROLE_RENEW_REMINDER_SECS: i64 = 86400;
+const DUEL_TIMEOUT_SECS: i64 = 120;
+const MAX_REMINDERS_PER_USER: usize = 25;
+// Якщо бот пропустив запуск довше, ніж на стільки, подія вважається
+// пропущеною (і надсилається лише з catch_up).
+const SCHEDULE_GRACE_SECS: i64 = 300;
 const VOICE_XP_AMOUNT: u64 = 10;
 const MSG_XP_AMOUNT: u64 = 2;
 const BIRTHDAY_ROLE_NAME: &str = "誕生日 Іменинник 誕生日";
@@ -140,14 +180,37 @@ struct UserProfile {
     minutes: u64,
     #[serde(default)]
     last_daily: i64,
+    // Серія щоденних нагород поспіль і найкраща серія
+    #[serde(default)]
+    daily_streak: u32,
+    #[serde(default)]
+    best_streak: u32,
+    #[serde(default)]
+    last_weekly: i64,
+    #[serde(default)]
+    last_monthly: i64,
     #[serde(default = "default_chips")]
     chips: u64,
+    // Банк: окремий від гаманця баланс, на який нараховуються відсотки
+    #[serde(default)]
+    bank: u64,
+    #[serde(default)]
+    bank_interest_at: i64,
 
-    // Бустери XP (timestamp закінчення дії)
+    // Активні бустери XP
     #[serde(default)]
+    xp_boosters: Vec<XpBoost>,
+    // Старі фіксовані бустери, переносяться в `xp_boosters` при завантаженні
+    #[serde(default, skip_serializing)]
     xp_booster_x2_until: i64,
-    #[serde(default)]
+    #[serde(default, skip_serializing)]
     xp_booster_x5_until: i64,
+    // Куплені в магазині предмети
+    #[serde(default)]
+    inventory: Vec<InventoryItem>,
+    // Титул з магазину, показується в /rank
+    #[serde(default)]
+    title: Option<String>,
 
     // Час останнього повідомлення (мс). Зберігається, щоб /cleanup_inactive
     // працював і після перезапуску бота.
@@ -161,1569 +224,11123 @@ struct UserProfile {
     spam_block_until: i64,
 }
 
-// Авто-роль при вході
+// Активний бустер XP
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct XpBoost {
+    multiplier: u64,
+    until: i64,
+}
+
+// Що робить товар магазину при використанні
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ShopItemKind {
+    Consumable,
+    XpBooster { multiplier: u64, duration_secs: i64 },
+    TempRole { role_id: String, duration_secs: i64 },
+    Role { role_id: String },
+    // Кольорові ролі взаємовиключні: нова знімає попередню
+    ColorRole { role_id: String },
+    Title { title: String },
+}
+
+impl ShopItemKind {
+    fn label(&self) -> String {
+        match self {
+            ShopItemKind::Consumable => "🎟️ Предмет".to_string(),
+            ShopItemKind::XpBooster {
+                multiplier,
+                duration_secs,
+            } => format!(
+                "🚀 Бустер XP x{} на {}",
+                multiplier,
+                format_duration_short(*duration_secs)
+            ),
+            ShopItemKind::TempRole {
+                role_id,
+                duration_secs,
+            } => format!(
+                "⏳ Роль <@&{}> на {}",
+                role_id,
+                format_duration_short(*duration_secs)
+            ),
+            ShopItemKind::Role { role_id } => format!("🎭 Роль <@&{}>", role_id),
+            ShopItemKind::ColorRole { role_id } => format!("🎨 Колір <@&{}>", role_id),
+            ShopItemKind::Title { title } => format!("🏷️ Титул «{}»", title),
+        }
+    }
+}
+
+// Товар магазину сервера
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct ShopItem {
+    // Унікальна в межах сервера (без урахування регістру)
+    name: String,
+    #[serde(default)]
+    description: String,
+    price: u64,
+    kind: ShopItemKind,
+    // None — без обмеження кількості
+    #[serde(default)]
+    stock: Option<u64>,
+    #[serde(default)]
+    min_level: u64,
+}
+
+// Куплений товар в інвентарі; зберігає копію товару, тож його можна
+// використати навіть після зміни чи видалення з магазину
 #[derive(Debug, Serialize, Deserialize, Clone)]
-struct AutoRole {
+struct InventoryItem {
     guild_id: String,
-    role_id: String,
+    item: ShopItem,
+    quantity: u32,
 }
 
-// Структура для збереження ідей
+// Тимчасова роль, яку треба зняти після закінчення терміну
 #[derive(Debug, Serialize, Deserialize, Clone)]
-struct SuggestionData {
-    message_id: String,
-    channel_id: String,
-    author_id: String,
-    author_name: String,
-    content: String,
-    status: String, // "pending", "approved", "rejected"
-    votes_for: u32,
-    votes_against: u32,
+struct RoleGrant {
+    guild_id: String,
+    user_id: String,
+    role_id: String,
+    expires_at: i64,
+    // Товар, за яким роль продовжується з нагадування
     #[serde(default)]
-    voted_users: Vec<String>, // ID користувачів які проголосували (формат: "user_id:vote_type")
-    timestamp: i64,
+    item_name: String,
+    #[serde(default)]
+    duration_secs: i64,
+    // Нагадування про закінчення вже надіслано
+    #[serde(default)]
+    reminded: bool,
 }
 
-struct Data {
-    users: Arc<Mutex<HashMap<String, UserProfile>>>,
-    birthdays: Arc<Mutex<HashMap<String, String>>>,
-    auto_roles: Arc<Mutex<Vec<AutoRole>>>,
-    banned_words: Arc<Mutex<Vec<String>>>,
-    // Кеш скомпільованих регулярних виразів для заборонених слів, щоб не
-    // компілювати їх заново на кожне повідомлення.
-    banned_regex_cache: Arc<Mutex<HashMap<String, Regex>>>,
-    suggestions_channels: Arc<Mutex<Vec<String>>>, // ID каналів для ідей
-    suggestions_data: Arc<Mutex<HashMap<String, SuggestionData>>>, // message_id -> SuggestionData
+// Як поєднуються кілька активних множників XP
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, poise::ChoiceParameter,
+)]
+enum BoostStacking {
+    // x2 і x3 дають x4: бонуси (множник - 1) додаються
+    #[default]
+    #[name = "Додавання бонусів"]
+    Additive,
+    // x2 і x3 дають x6
+    #[name = "Множення"]
+    Multiplicative,
+    // Діє лише найбільший множник
+    #[name = "Лише найбільший"]
+    Highest,
 }
 
-type Error = Box<dyn std::error::Error + Send + Sync>;
-type Context<'a> = poise::Context<'a, Data, Error>;
+impl BoostStacking {
+    fn label(self) -> &'static str {
+        match self {
+            BoostStacking::Additive => "додавання бонусів",
+            BoostStacking::Multiplicative => "множення",
+            BoostStacking::Highest => "лише найбільший",
+        }
+    }
+}
 
-/// Result of atomically recording a vote on a suggestion.
-enum VoteOutcome {
-    AlreadyVotedSame,
-    AlreadyVotedOther,
-    Gone,
-    Recorded(SuggestionData),
+// Бустер XP для всього сервера (наприклад, подвійний XP на вихідних)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ServerBoost {
+    name: String,
+    multiplier: f64,
+    until: i64,
+    started_by: String,
 }
 
-// --- КОНФІГУРАЦІЯ РОЛЕЙ ---
-fn get_roles_config() -> Vec<(u64, &'static str, u32)> {
-    vec![
-        (0, "草 Дачник 草", 0x78B159),
-        (5, "目 Сусід через паркан 目", 0x4E7F38),
-        (10, "力 Тракторист 力", 0x3498DB),
-        (15, "土 Агроном 土", 0x1ABC9C),
-        (20, "牛 Зоотехнік 牛", 0xE67E22),
-        (25, "蜂 Бджоляр 蜂", 0xF1C40F),
-        (30, "長 Голова колгоспу 長", 0x9B59B6),
-        (35, "金 Олігарх місцевий 金", 0xE91E63),
-        (40, "城 Депутат райради 城", 0x2C3E50),
-        (45, "仙 Мольфар 仙", 0x11806A),
-        (50, "神 Дід Панас 神", 0xFFD700),
-    ]
+fn default_max_xp_multiplier() -> f64 {
+    10.0
 }
 
-// --- ДОПОМІЖНІ ФУНКЦІЇ ---
+// Налаштування множників XP сервера
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct XpBoostSettings {
+    #[serde(default)]
+    stacking: BoostStacking,
+    #[serde(default = "default_max_xp_multiplier")]
+    max_multiplier: f64,
+    // role_id -> множник для учасників з цією роллю
+    #[serde(default)]
+    role_multipliers: HashMap<String, f64>,
+    #[serde(default)]
+    server_boosts: Vec<ServerBoost>,
+}
 
-/// Safely lock a mutex, recovering from poisoning instead of panicking.
-fn safe_lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
-    match mutex.lock() {
-        Ok(guard) => guard,
-        Err(poisoned) => {
-            warn!("⚠️ Mutex was poisoned, recovering...");
-            poisoned.into_inner()
+impl Default for XpBoostSettings {
+    fn default() -> Self {
+        Self {
+            stacking: BoostStacking::default(),
+            max_multiplier: default_max_xp_multiplier(),
+            role_multipliers: HashMap::new(),
+            server_boosts: Vec::new(),
         }
     }
 }
 
-fn load_json<T: for<'a> Deserialize<'a> + Default>(path: &str) -> T {
-    match fs::read_to_string(path) {
-        Ok(data) => match serde_json::from_str(&data) {
-            Ok(parsed) => {
-                info!("✅ Завантажено файл: {}", path);
-                parsed
+impl UserProfile {
+    /// Activates a personal booster. Buying a multiplier that is already
+    /// running extends it instead of restarting the timer. Returns the
+    /// booster's new expiry.
+    fn add_xp_boost(&mut self, multiplier: u64, duration_secs: i64, now: i64) -> i64 {
+        self.xp_boosters.retain(|b| b.until > now);
+        match self
+            .xp_boosters
+            .iter_mut()
+            .find(|b| b.multiplier == multiplier)
+        {
+            Some(boost) => {
+                boost.until += duration_secs;
+                boost.until
             }
-            Err(e) => {
-                warn!(
-                    "⚠️ Помилка парсингу {}: {}. Використовую значення за замовчуванням.",
-                    path, e
-                );
-                T::default()
+            None => {
+                self.xp_boosters.push(XpBoost {
+                    multiplier,
+                    until: now + duration_secs,
+                });
+                now + duration_secs
             }
-        },
-        Err(e) => {
-            info!("ℹ️ Файл {} не знайдено ({}), створюю новий.", path, e);
-            T::default()
         }
     }
-}
 
-fn save_json<T: Serialize>(path: &str, data: &T) {
-    let json = match serde_json::to_string_pretty(data) {
-        Ok(json) => json,
-        Err(e) => {
-            error!("❌ Помилка серіалізації {}: {}", path, e);
-            return;
+    fn migrate_legacy_boosters(&mut self) {
+        for (multiplier, until) in [(2, self.xp_booster_x2_until), (5, self.xp_booster_x5_until)] {
+            if until > 0 {
+                self.xp_boosters.push(XpBoost { multiplier, until });
+            }
         }
-    };
+        self.xp_booster_x2_until = 0;
+        self.xp_booster_x5_until = 0;
+    }
 
-    // Write atomically: serialize to a temp file in the same directory, then
-    // rename over the target. A crash mid-write can never truncate/corrupt the
-    // real file (which would otherwise wipe all data on the next load).
-    let tmp_path = format!("{}.tmp", path);
-    if let Err(e) = fs::write(&tmp_path, &json) {
-        error!("❌ Не вдалося записати тимчасовий файл {}: {}", tmp_path, e);
-        return;
+    fn add_to_inventory(&mut self, guild_id: &str, item: &ShopItem) {
+        match self
+            .inventory
+            .iter_mut()
+            .find(|i| i.guild_id == guild_id && i.item.name.eq_ignore_ascii_case(&item.name))
+        {
+            Some(entry) => entry.quantity += 1,
+            None => self.inventory.push(InventoryItem {
+                guild_id: guild_id.to_string(),
+                item: item.clone(),
+                quantity: 1,
+            }),
+        }
     }
-    if let Err(e) = fs::rename(&tmp_path, path) {
-        error!("❌ Не вдалося зберегти {}: {}", path, e);
-        let _ = fs::remove_file(&tmp_path);
-        return;
+
+    /// Removes one unit of an item from the inventory and returns it.
+    fn take_from_inventory(&mut self, guild_id: &str, name: &str) -> Option<ShopItem> {
+        let name = name.trim().to_lowercase();
+        let idx = self
+            .inventory
+            .iter()
+            .position(|i| i.guild_id == guild_id && i.item.name.to_lowercase() == name)?;
+        let entry = &mut self.inventory[idx];
+        let item = entry.item.clone();
+        entry.quantity -= 1;
+        if entry.quantity == 0 {
+            self.inventory.remove(idx);
+        }
+        Some(item)
     }
-    info!("💾 Збережено: {}", path);
 }
 
-fn get_xp_needed(level: u64) -> u64 {
-    // Power-logarithmic curve: combines exponential growth with log scaling.
-    // Formula: 100 * (level + 1)^1.3 * ln(level + 2) + 100
-    // Lv0→1: ~169 | Lv5→6: ~2098 | Lv10→11: ~5957 | Lv20→21: ~16822 | Lv37→38: ~41055
-    let level_f = level as f64;
-    let needed = 100.0 * (level_f + 1.0).powf(1.3) * (level_f + 2.0).ln() + 100.0;
-    needed as u64
+// Авто-роль при вході
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct AutoRole {
+    guild_id: String,
+    role_id: String,
 }
 
-fn try_levelup(profile: &mut UserProfile) -> Option<u64> {
-    let mut leveled_up = None;
-    loop {
-        let needed = get_xp_needed(profile.level);
-        if profile.xp >= needed {
-            profile.xp -= needed;
-            profile.level += 1;
-            leveled_up = Some(profile.level);
-        } else {
-            break;
-        }
-    }
-    leveled_up
+// Структура для збереження ідей
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct SuggestionData {
+    message_id: String,
+    channel_id: String,
+    author_id: String,
+    author_name: String,
+    content: String,
+    #[serde(default)]
+    status: SuggestionStatus,
+    #[serde(default)]
+    staff_response: Option<String>, // Відповідь персоналу, показується в embed-і
+    #[serde(default)]
+    duplicate_of: Option<String>, // Посилання на оригінал для дублікатів
+    #[serde(default)]
+    status_history: Vec<StatusChange>,
+    #[serde(default)]
+    votes: HashMap<String, IdeaVote>, // user_id -> голос
+    // Старий формат ("user_id:like"), переноситься у `votes` при завантаженні
+    #[serde(default, skip_serializing)]
+    voted_users: Vec<String>,
+    timestamp: i64,
+    #[serde(default)]
+    flagged: bool, // Персонал уже отримав позначку про поріг голосів
+    #[serde(default)]
+    stale_reminded_at: i64,
+    #[serde(default)]
+    archived: bool, // Повідомлення з ідеєю видалено або канал відключено
+    #[serde(default)]
+    anonymous: bool, // Автор прихований публічно, але зберігається для персоналу
+    #[serde(default)]
+    attachments: Vec<IdeaAttachment>,
 }
 
-fn get_role_for_level(level: u64) -> Option<&'static str> {
-    let roles = get_roles_config();
-    let mut best_role = None;
-    for (req_lvl, name, _) in roles {
-        if level >= req_lvl {
-            best_role = Some(name);
-        }
-    }
-    best_role
+// Вкладення ідеї, перезавантажене ботом у повідомлення з embed-ом
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct IdeaAttachment {
+    filename: String,
+    url: String,
 }
 
-async fn assign_role(
-    ctx: &serenity::Context,
-    guild_id: serenity::GuildId,
-    user_id: serenity::UserId,
-    level: u64,
-) {
-    let target_role_name = match get_role_for_level(level) {
-        Some(name) => name,
-        None => return,
-    };
+// Налаштування каналу ідей: правила авто-модерації
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct SuggestionChannelConfig {
+    #[serde(default)]
+    anonymous: bool, // Анонімні ідеї в цьому каналі
+    #[serde(default)]
+    staff_channel: Option<String>, // Куди надсилати позначки та нагадування
+    #[serde(default)]
+    popular_net_votes: Option<i64>, // N чистих голосів «за»
+    #[serde(default)]
+    popular_percent: Option<u32>, // X% схвалення...
+    #[serde(default)]
+    min_voters: u32, // ...за умови щонайменше M голосів
+    #[serde(default)]
+    auto_popular: bool, // true — статус «Популярна», false — лише позначка для персоналу
+    #[serde(default)]
+    negative_percent: Option<u32>, // Закрити, якщо схвалення не вище X%...
+    #[serde(default)]
+    negative_after_hours: u32, // ...після стількох годин
+    #[serde(default)]
+    stale_days: Option<u32>, // Нагадати персоналу про ідеї без рішення
+    #[serde(default)]
+    theme: SuggestionTheme,
+}
 
-    if let Ok(roles) = guild_id.roles(&ctx.http).await {
-        let target_role_id = roles
-            .values()
-            .find(|r| r.name == target_role_name)
-            .map(|r| r.id);
+// Оформлення ідей каналу; порожні поля — стандартні значення
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+struct SuggestionTheme {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>, // {author} — ім'я автора
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    colors: HashMap<SuggestionStatus, u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    like_label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dislike_label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    footer: Option<String>,
+}
 
-        if let Some(add_id) = target_role_id {
-            let member_res = guild_id.member(&ctx.http, user_id).await;
-            if let Ok(member) = member_res {
-                if !member.roles.contains(&add_id) {
-                    let _ = member.add_role(&ctx.http, add_id).await;
-                }
-                let config = get_roles_config();
-                for (_, name, _) in config {
-                    if name != target_role_name {
-                        if let Some(old_role) = roles.values().find(|r| r.name == name) {
-                            if member.roles.contains(&old_role.id) {
-                                let _ = member.remove_role(&ctx.http, old_role.id).await;
-                            }
-                        }
-                    }
-                }
-            }
+impl SuggestionTheme {
+    fn title_for(&self, author: Option<&str>) -> String {
+        match (self.title.as_deref(), author) {
+            (Some(t), _) => t.replace("{author}", author.unwrap_or("Анонім")),
+            (None, Some(a)) => format!("💡 Ідея користувача @{}", a),
+            (None, None) => "💡 Анонімна ідея".to_string(),
         }
     }
-}
 
-fn create_default_profile() -> UserProfile {
-    UserProfile {
-        xp: 0,
-        level: 0,
-        minutes: 0,
-        last_daily: 0,
-        chips: 100,
-        xp_booster_x2_until: 0,
-        xp_booster_x5_until: 0,
-        last_msg_time: 0,
-        spam_counter: 0,
-        spam_block_until: 0,
+    fn color(&self, status: SuggestionStatus) -> u32 {
+        self.colors.get(&status).copied().unwrap_or(status.color())
     }
-}
 
-// Функція для отримання активного множника XP
-fn get_xp_multiplier(profile: &UserProfile) -> u64 {
-    let now = Utc::now().timestamp();
-    if now < profile.xp_booster_x5_until {
-        5
-    } else if now < profile.xp_booster_x2_until {
-        2
-    } else {
-        1
+    fn like_label(&self) -> &str {
+        self.like_label.as_deref().unwrap_or("Класнючка")
+    }
+
+    fn dislike_label(&self) -> &str {
+        self.dislike_label.as_deref().unwrap_or("Жах")
+    }
+
+    fn footer(&self) -> &str {
+        self.footer
+            .as_deref()
+            .unwrap_or("Хочете додати свою ідею? Просто напишіть її прямо сюди")
     }
 }
 
-// --- КОМАНДИ ---
+/// What the idea moderator should do with an idea on this pass.
+#[derive(Debug, PartialEq, Eq)]
+enum IdeaAction {
+    Flag,
+    MarkPopular,
+    AutoClose,
+    StaleReminder,
+}
+
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Default, poise::ChoiceParameter,
+)]
+#[serde(rename_all = "lowercase")]
+enum SuggestionStatus {
+    #[default]
+    #[name = "На голосуванні"]
+    Pending,
+    #[name = "Розглядається"]
+    Considering,
+    #[name = "Популярна"]
+    Popular,
+    #[name = "Прийнято"]
+    Approved,
+    #[name = "Реалізовано"]
+    Implemented,
+    #[name = "Відхилено"]
+    Rejected,
+    #[name = "Дублікат"]
+    Duplicate,
+    #[name = "Відкликано"]
+    Withdrawn,
+}
+
+impl SuggestionStatus {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Pending => "📊 | Чекаємо на відгук спільноти! Все у ваших руках",
+            Self::Considering => "🤔 | Розглядаємо ідею",
+            Self::Popular => "🔥 | Популярна ідея! Чекаємо рішення команди",
+            Self::Approved => "✅ | Крута ідея, інтегруєм!",
+            Self::Implemented => "🚀 | Реалізовано!",
+            Self::Rejected => "❌ | До одного місця такі ідеї!",
+            Self::Duplicate => "📎 | Дублікат іншої ідеї",
+            Self::Withdrawn => "🗑️ | Відкликано автором",
+        }
+    }
+
+    fn color(self) -> u32 {
+        match self {
+            Self::Pending => 0xF1C40F,
+            Self::Considering => 0x3498DB,
+            Self::Popular => 0xE67E22,
+            Self::Approved => 0x2ECC71,
+            Self::Implemented => 0x9B59B6,
+            Self::Rejected => 0xE74C3C,
+            Self::Duplicate | Self::Withdrawn => 0x95A5A6,
+        }
+    }
+
+    fn emoji(self) -> &'static str {
+        self.label().split(" | ").next().unwrap_or_default()
+    }
+
+    /// Open ideas still accept votes and keep their buttons.
+    fn is_open(self) -> bool {
+        matches!(self, Self::Pending | Self::Considering | Self::Popular)
+    }
+}
+
+// Запис в історії статусів ідеї
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct StatusChange {
+    status: SuggestionStatus,
+    actor_id: String,
+    actor_name: String,
+    at: i64,
+    #[serde(default)]
+    response: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum IdeaVote {
+    Like,
+    Dislike,
+}
+
+/// What a click on a vote button did to the user's vote.
+#[derive(Debug, PartialEq, Eq)]
+enum VoteChange {
+    Added,
+    Switched,
+    Retracted,
+}
+
+impl SuggestionData {
+    fn votes_for(&self) -> u32 {
+        self.votes
+            .values()
+            .filter(|v| **v == IdeaVote::Like)
+            .count() as u32
+    }
+
+    fn votes_against(&self) -> u32 {
+        self.votes
+            .values()
+            .filter(|v| **v == IdeaVote::Dislike)
+            .count() as u32
+    }
+
+    /// Author name as shown publicly.
+    fn public_author(&self) -> Option<&str> {
+        (!self.anonymous).then_some(self.author_name.as_str())
+    }
+
+    fn net_votes(&self) -> i64 {
+        self.votes_for() as i64 - self.votes_against() as i64
+    }
+
+    /// Share of "for" votes, 0–100.
+    fn approval_percent(&self) -> u32 {
+        let total = self.votes_for() + self.votes_against();
+        if total > 0 {
+            (self.votes_for() as f64 / total as f64 * 100.0) as u32
+        } else {
+            0
+        }
+    }
+
+    /// Same vote again retracts it, the other one switches it.
+    fn apply_vote(&mut self, user_id: &str, vote: IdeaVote) -> VoteChange {
+        match self.votes.get(user_id).copied() {
+            Some(current) if current == vote => {
+                self.votes.remove(user_id);
+                VoteChange::Retracted
+            }
+            Some(_) => {
+                self.votes.insert(user_id.to_string(), vote);
+                VoteChange::Switched
+            }
+            None => {
+                self.votes.insert(user_id.to_string(), vote);
+                VoteChange::Added
+            }
+        }
+    }
+
+    /// Timeline lines for `/idea history`. For anonymous ideas the author is
+    /// hidden from other members, both as submitter and as actor of their
+    /// own changes (e.g. withdrawing).
+    fn history_lines(&self, viewer_id: &str, is_staff: bool) -> Vec<String> {
+        let hide_author = self.anonymous && !is_staff && self.author_id != viewer_id;
+        let author = if hide_author {
+            "анонім".to_string()
+        } else {
+            format!("<@{}>", self.author_id)
+        };
+        let mut lines = vec![format!("<t:{}:f> — 💡 Подано ({})", self.timestamp, author)];
+        for change in &self.status_history {
+            let actor = if hide_author && change.actor_id == self.author_id {
+                "анонім"
+            } else {
+                &change.actor_name
+            };
+            let mut line = format!(
+                "<t:{}:f> — {} ({})",
+                change.at,
+                change.status.label(),
+                actor
+            );
+            if let Some(response) = &change.response {
+                line.push_str(&format!("\n> {}", response));
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Sets a new status, replaces the staff response and records the change.
+    fn set_status(
+        &mut self,
+        status: SuggestionStatus,
+        actor: (&str, &str),
+        response: Option<String>,
+        duplicate_of: Option<String>,
+        now: i64,
+    ) {
+        self.status = status;
+        self.staff_response = response.clone();
+        self.duplicate_of = if status == SuggestionStatus::Duplicate {
+            duplicate_of
+        } else {
+            None
+        };
+        self.status_history.push(StatusChange {
+            status,
+            actor_id: actor.0.to_string(),
+            actor_name: actor.1.to_string(),
+            at: now,
+            response,
+        });
+    }
+
+    /// Moves votes from the legacy `voted_users` list into `votes`.
+    fn migrate_legacy_votes(&mut self) {
+        for entry in std::mem::take(&mut self.voted_users) {
+            let vote = match entry.rsplit_once(':') {
+                Some((id, "like")) => Some((id, IdeaVote::Like)),
+                Some((id, "dislike")) => Some((id, IdeaVote::Dislike)),
+                _ => None,
+            };
+            if let Some((id, vote)) = vote {
+                self.votes.entry(id.to_string()).or_insert(vote);
+            }
+        }
+    }
+}
+
+// Налаштування модмейлу для сервера
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ModmailConfig {
+    guild_id: String,
+    channel_id: String, // Канал персоналу, де створюються треди
+    #[serde(default)]
+    anonymous: bool, // Чи приховувати ім'я персоналу у відповідях за замовчуванням
+    #[serde(default)]
+    blocked_users: Vec<String>,
+}
+
+// Один запис у стенограмі модмейлу
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ModmailEntry {
+    author_id: String,
+    author_name: String,
+    content: String,
+    #[serde(default)]
+    attachments: Vec<String>, // URL вкладень
+    from_staff: bool,
+    #[serde(default)]
+    anonymous: bool,
+    timestamp: i64,
+}
+
+// Відкрита розмова модмейлу (ключ — ID користувача)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ModmailThread {
+    user_id: String,
+    user_name: String,
+    guild_id: String,
+    thread_id: String,
+    opened_at: i64,
+    #[serde(default)]
+    transcript: Vec<ModmailEntry>,
+}
+
+// Тег — адмін-визначена відповідь, що викликається через /tag або префікс
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Tag {
+    name: String,
+    content: String, // Текст із плейсхолдерами ({user}, {server}, {args}...)
+    #[serde(default)]
+    embed: bool,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    color: Option<u32>,
+    created_by: String,
+    created_at: i64,
+    #[serde(default)]
+    uses: u64,
+}
+
+// Теги та їх налаштування для одного сервера
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct GuildTags {
+    #[serde(default)]
+    prefix: Option<String>, // Напр. "!" — тоді "!правила" викликає тег
+    #[serde(default)]
+    creator_role: Option<String>, // Роль, якій дозволено створювати теги
+    #[serde(default)]
+    tags: HashMap<String, Tag>, // name -> Tag
+}
+
+// Спосіб зіставлення тексту для авто-відповідей
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, poise::ChoiceParameter)]
+#[serde(rename_all = "lowercase")]
+enum TriggerMatch {
+    #[name = "Точний збіг"]
+    Exact,
+    #[name = "Містить"]
+    Contains,
+    #[name = "Регулярний вираз"]
+    Regex,
+}
+
+// Авто-відповідь на повідомлення за шаблоном
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct AutoResponder {
+    id: u32,
+    guild_id: String,
+    pattern: String,
+    match_type: TriggerMatch,
+    #[serde(default)]
+    response: Option<String>, // Текст відповіді (плейсхолдери як у тегах)
+    #[serde(default)]
+    reaction: Option<String>, // Реакція замість/разом з відповіддю
+    #[serde(default)]
+    channels: Vec<String>, // Порожньо — усі канали
+    #[serde(default)]
+    cooldown_secs: i64, // Між будь-якими спрацюваннями тригера
+    #[serde(default)]
+    user_cooldown_secs: i64, // Між спрацюваннями для одного користувача
+}
+
+// Шаблон embed-у для оголошень; імпортується/експортується як JSON
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+struct EmbedTemplate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    thumbnail: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    footer: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    fields: Vec<EmbedFieldTemplate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct EmbedFieldTemplate {
+    name: String,
+    value: String,
+    #[serde(default)]
+    inline: bool,
+}
+
+// Правило повторення (час — за Києвом)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum Recurrence {
+    Once,
+    Daily {
+        hour: u32,
+        minute: u32,
+    },
+    Weekly {
+        weekdays: Vec<u32>,
+        hour: u32,
+        minute: u32,
+    }, // 0 = понеділок
+    Cron {
+        expr: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, poise::ChoiceParameter)]
+enum RecurrenceKind {
+    #[name = "Один раз"]
+    Once,
+    #[name = "Щодня"]
+    Daily,
+    #[name = "Щотижня"]
+    Weekly,
+    #[name = "Cron"]
+    Cron,
+}
+
+// Заплановане (можливо повторюване) оголошення
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ScheduledAnnouncement {
+    id: u32,
+    guild_id: String,
+    channel_id: String,
+    created_by: String,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    embed: Option<EmbedTemplate>,
+    recurrence: Recurrence,
+    next_run: i64, // Unix timestamp наступного запуску
+    #[serde(default)]
+    paused: bool,
+    #[serde(default)]
+    catch_up: bool, // Надіслати пропущене під час офлайну
+    #[serde(default)]
+    last_run: Option<i64>,
+}
+
+// Особисте нагадування користувача
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Reminder {
+    id: u32,
+    user_id: String,
+    channel_id: String, // Канал, де створено нагадування
+    text: String,
+    #[serde(default)]
+    dm: bool, // Надіслати в особисті замість каналу
+    due: i64, // Unix timestamp
+    created_at: i64,
+    #[serde(default)]
+    recurrence: Option<Recurrence>,
+}
+
+// Голосування з кнопками
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct PollData {
+    message_id: String,
+    channel_id: String,
+    author_id: String,
+    question: String,
+    options: Vec<String>,
+    #[serde(default)]
+    multi: bool, // Можна обрати кілька варіантів
+    #[serde(default)]
+    anonymous: bool, // Приховати, хто як голосував
+    #[serde(default)]
+    ends_at: Option<i64>,
+    #[serde(default)]
+    required_role: Option<String>,
+    #[serde(default)]
+    votes: HashMap<String, Vec<usize>>, // user_id -> індекси варіантів
+    created_at: i64,
+}
+
+// Гра в блекджек; усі ставки тримаються в депозиті до завершення
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct BlackjackGame {
+    user_id: String,
+    channel_id: String,
+    message_id: String,
+    game: blackjack::Game,
+    last_action: i64,
+}
+
+// Виклик на дуель; ставка того, хто викликав, у депозиті до відповіді
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Duel {
+    challenger_id: String,
+    opponent_id: String,
+    channel_id: String,
+    message_id: String,
+    stake: u64,
+    created_at: i64,
+}
+
+// Запис журналу гривень; журнал лише доповнюється і ніколи не змінюється
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Transaction {
+    user_id: String,
+    delta: i64,
+    reason: String,
+    // Пов'язаний об'єкт: інший гравець, розіграш, адмін тощо
+    #[serde(default)]
+    reference: Option<String>,
+    at: i64,
+    // Запис стосується банку, а не гаманця
+    #[serde(default)]
+    bank: bool,
+}
+
+fn default_house_edge() -> f64 {
+    3.0
+}
+
+fn default_min_bet() -> u64 {
+    10
+}
+
+fn default_max_bet() -> u64 {
+    10_000
+}
+
+// Налаштування ігор казино; спільні для всіх серверів, як і гаманці
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct CasinoConfig {
+    #[serde(default)]
+    blackjack: blackjack::Rules,
+    // Відсоток, що казино лишає собі з виграшу (і комісія з банку дуелі)
+    #[serde(default = "default_house_edge")]
+    house_edge: f64,
+    #[serde(default = "default_min_bet")]
+    min_bet: u64,
+    #[serde(default = "default_max_bet")]
+    max_bet: u64,
+    #[serde(default = "games::default_slot_table")]
+    slots: Vec<games::SlotSymbol>,
+}
+
+fn default_pay_daily_cap() -> u64 {
+    5_000
+}
+
+fn default_pay_confirm_above() -> u64 {
+    1_000
+}
+
+fn default_pay_min_level() -> u64 {
+    3
+}
+
+fn default_pay_min_account_days() -> i64 {
+    7
+}
+
+fn default_daily_min() -> u64 {
+    50
+}
+
+fn default_daily_max() -> u64 {
+    150
+}
+
+fn default_bank_interest_percent() -> f64 {
+    1.0
+}
+
+fn default_bank_interest_cap() -> u64 {
+    10_000
+}
+
+fn default_weekly_reward() -> u64 {
+    750
+}
+
+fn default_monthly_reward() -> u64 {
+    3_000
+}
+
+fn default_streak_bonus_percent() -> f64 {
+    10.0
+}
+
+fn default_streak_max_days() -> u32 {
+    10
+}
+
+fn default_streak_grace_hours() -> i64 {
+    12
+}
+
+fn default_booster_reward_multiplier() -> f64 {
+    1.5
+}
+
+fn default_level_bonus_percent() -> f64 {
+    5.0
+}
+
+// Глобальні налаштування економіки: перекази, нагороди, банк, звіти
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct EconomyConfig {
+    // Комісія з переказу у відсотках; гривні комісії згорають
+    #[serde(default)]
+    pay_fee_percent: f64,
+    // 0 — без ліміту
+    #[serde(default = "default_pay_daily_cap")]
+    pay_daily_cap: u64,
+    #[serde(default = "default_pay_confirm_above")]
+    pay_confirm_above: u64,
+    #[serde(default = "default_pay_min_level")]
+    pay_min_level: u64,
+    #[serde(default = "default_pay_min_account_days")]
+    pay_min_account_days: i64,
+    #[serde(default = "default_daily_min")]
+    daily_min: u64,
+    #[serde(default = "default_daily_max")]
+    daily_max: u64,
+    // Відсоток на добу, нараховується лише на суму до bank_interest_cap
+    #[serde(default = "default_bank_interest_percent")]
+    bank_interest_percent: f64,
+    #[serde(default = "default_bank_interest_cap")]
+    bank_interest_cap: u64,
+    // Канал щоденного звіту про економіку
+    #[serde(default)]
+    stats_channel: Option<String>,
+    #[serde(default)]
+    stats_posted_at: i64,
+    // Коли журнал гривень відкрито початковими балансами (0 — ще ні)
+    #[serde(default)]
+    ledger_opened_at: i64,
+    #[serde(default = "default_weekly_reward")]
+    weekly_reward: u64,
+    #[serde(default = "default_monthly_reward")]
+    monthly_reward: u64,
+    // Бонус за кожен день серії (у відсотках), не більше streak_max_days днів
+    #[serde(default = "default_streak_bonus_percent")]
+    streak_bonus_percent: f64,
+    #[serde(default = "default_streak_max_days")]
+    streak_max_days: u32,
+    // Запізнення з /daily у межах цього запасу не обриває серію
+    #[serde(default = "default_streak_grace_hours")]
+    streak_grace_hours: i64,
+    #[serde(default = "default_booster_reward_multiplier")]
+    booster_reward_multiplier: f64,
+    #[serde(default = "default_level_bonus_percent")]
+    level_bonus_percent: f64,
+}
+
+impl Default for EconomyConfig {
+    fn default() -> Self {
+        Self {
+            pay_fee_percent: 0.0,
+            pay_daily_cap: default_pay_daily_cap(),
+            pay_confirm_above: default_pay_confirm_above(),
+            pay_min_level: default_pay_min_level(),
+            pay_min_account_days: default_pay_min_account_days(),
+            daily_min: default_daily_min(),
+            daily_max: default_daily_max(),
+            bank_interest_percent: default_bank_interest_percent(),
+            bank_interest_cap: default_bank_interest_cap(),
+            stats_channel: None,
+            stats_posted_at: 0,
+            ledger_opened_at: 0,
+            weekly_reward: default_weekly_reward(),
+            monthly_reward: default_monthly_reward(),
+            streak_bonus_percent: default_streak_bonus_percent(),
+            streak_max_days: default_streak_max_days(),
+            streak_grace_hours: default_streak_grace_hours(),
+            booster_reward_multiplier: default_booster_reward_multiplier(),
+            level_bonus_percent: default_level_bonus_percent(),
+        }
+    }
+}
+
+// Підозріле коло переказів (можливий фарм через твінків)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct TransferFlag {
+    chain: Vec<String>,
+    amount: u64,
+    at: i64,
+}
+
+impl Default for CasinoConfig {
+    fn default() -> Self {
+        Self {
+            blackjack: blackjack::Rules::default(),
+            house_edge: default_house_edge(),
+            min_bet: default_min_bet(),
+            max_bet: default_max_bet(),
+            slots: games::default_slot_table(),
+        }
+    }
+}
+
+/// Result of atomically recording a poll vote.
+enum PollVoteOutcome {
+    Gone,
+    Expired,
+    Recorded(Box<PollData>),
+}
+
+// Розіграші
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Giveaway {
+    message_id: String,
+    channel_id: String,
+    guild_id: String,
+    host_id: String,
+    prize: String,
+    winners: usize, // Кількість переможців
+    ends_at: i64,
+    #[serde(default)]
+    min_level: u64,
+    #[serde(default)]
+    required_role: Option<String>,
+    #[serde(default)]
+    entry_fee: u64, // Вартість участі в гривнях
+    #[serde(default)]
+    entrants: Vec<String>,
+    #[serde(default)]
+    ended: bool,
+    #[serde(default)]
+    winner_ids: Vec<String>,
+    // Переможці попередніх раундів, які не можуть виграти при переобранні
+    #[serde(default)]
+    past_winners: Vec<String>,
+}
+
+impl Giveaway {
+    /// Draws winners. A reroll moves the current winners to `past_winners`
+    /// and skips everyone who has already won any round.
+    fn draw<R: Rng>(&mut self, reroll: bool, rng: &mut R) {
+        if reroll {
+            let previous = std::mem::take(&mut self.winner_ids);
+            self.past_winners.extend(previous);
+        }
+        self.winner_ids =
+            pick_giveaway_winners(&self.entrants, &self.past_winners, self.winners, rng);
+    }
+}
+
+struct Data {
+    users: Arc<Mutex<HashMap<String, UserProfile>>>,
+    birthdays: Arc<Mutex<HashMap<String, String>>>,
+    auto_roles: Arc<Mutex<Vec<AutoRole>>>,
+    banned_words: Arc<Mutex<Vec<String>>>,
+    // Кеш скомпільованих регулярних виразів для заборонених слів, щоб не
+    // компілювати їх заново на кожне повідомлення.
+    banned_regex_cache: Arc<Mutex<HashMap<String, Regex>>>,
+    suggestions_channels: Arc<Mutex<Vec<String>>>, // ID каналів для ідей
+    suggestions_data: Arc<Mutex<HashMap<String, SuggestionData>>>, // message_id -> SuggestionData
+    modmail_config: Arc<Mutex<Vec<ModmailConfig>>>,
+    modmail_threads: Arc<Mutex<HashMap<String, ModmailThread>>>, // user_id -> ModmailThread
+    tags: Arc<Mutex<HashMap<String, GuildTags>>>,                // guild_id -> GuildTags
+    autoresponders: Arc<Mutex<Vec<AutoResponder>>>,
+    // Той самий підхід, що й banned_regex_cache: кожен regex-тригер
+    // компілюється один раз і перевикористовується.
+    responder_regex_cache: Arc<Mutex<HashMap<String, Regex>>>,
+    // Коли закінчується кулдаун: "guild:id" та "guild:id:user_id" (не зберігається).
+    // Містить лише активні кулдауни: прострочені видаляються при новому спрацюванні.
+    responder_cooldowns: Arc<Mutex<HashMap<String, i64>>>,
+    schedules: Arc<Mutex<Vec<ScheduledAnnouncement>>>,
+    reminders: Arc<Mutex<Vec<Reminder>>>,
+    // Будить планувальник нагадувань, коли додано нове нагадування
+    reminders_wakeup: Arc<tokio::sync::Notify>,
+    polls: Arc<Mutex<HashMap<String, PollData>>>, // message_id -> PollData
+    giveaways: Arc<Mutex<HashMap<String, Giveaway>>>, // message_id -> Giveaway
+    suggestions_config: Arc<Mutex<HashMap<String, SuggestionChannelConfig>>>, // channel_id -> правила
+    blackjack_games: Arc<Mutex<HashMap<String, BlackjackGame>>>, // user_id -> активна гра
+    casino_config: Arc<Mutex<CasinoConfig>>,                     // правила ігор казино
+    transactions: Arc<Mutex<Vec<Transaction>>>,                  // журнал руху гривень
+    duels: Arc<Mutex<HashMap<String, Duel>>>,                    // challenger_id -> виклик
+    economy_config: Arc<Mutex<EconomyConfig>>,                   // глобальні налаштування економіки
+    transfer_flags: Arc<Mutex<Vec<TransferFlag>>>,               // позначені кругові перекази
+    shop: Arc<Mutex<HashMap<String, Vec<ShopItem>>>>,            // guild_id -> товари магазину
+    role_grants: Arc<Mutex<Vec<RoleGrant>>>,                     // тимчасові ролі з магазину
+    xp_boosts: Arc<Mutex<HashMap<String, XpBoostSettings>>>,     // guild_id -> множники XP
+}
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+type Context<'a> = poise::Context<'a, Data, Error>;
+
+/// Result of atomically recording a vote on a suggestion.
+enum VoteOutcome {
+    Gone,
+    Recorded(Box<SuggestionData>, VoteChange),
+}
+
+// --- КОНФІГУРАЦІЯ РОЛЕЙ ---
+fn get_roles_config() -> Vec<(u64, &'static str, u32)> {
+    vec![
+        (0, "草 Дачник 草", 0x78B159),
+        (5, "目 Сусід через паркан 目", 0x4E7F38),
+        (10, "力 Тракторист 力", 0x3498DB),
+        (15, "土 Агроном 土", 0x1ABC9C),
+        (20, "牛 Зоотехнік 牛", 0xE67E22),
+        (25, "蜂 Бджоляр 蜂", 0xF1C40F),
+        (30, "長 Голова колгоспу 長", 0x9B59B6),
+        (35, "金 Олігарх місцевий 金", 0xE91E63),
+        (40, "城 Депутат райради 城", 0x2C3E50),
+        (45, "仙 Мольфар 仙", 0x11806A),
+        (50, "神 Дід Панас 神", 0xFFD700),
+    ]
+}
+
+// --- ДОПОМІЖНІ ФУНКЦІЇ ---
+
+/// Safely lock a mutex, recovering from poisoning instead of panicking.
+fn safe_lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => {
+            warn!("⚠️ Mutex was poisoned, recovering...");
+            poisoned.into_inner()
+        }
+    }
+}
+
+fn load_json<T: for<'a> Deserialize<'a> + Default>(path: &str) -> T {
+    match fs::read_to_string(path) {
+        Ok(data) => match serde_json::from_str(&data) {
+            Ok(parsed) => {
+                info!("✅ Завантажено файл: {}", path);
+                parsed
+            }
+            Err(e) => {
+                warn!(
+                    "⚠️ Помилка парсингу {}: {}. Використовую значення за замовчуванням.",
+                    path, e
+                );
+                T::default()
+            }
+        },
+        Err(e) => {
+            info!("ℹ️ Файл {} не знайдено ({}), створюю новий.", path, e);
+            T::default()
+        }
+    }
+}
+
+fn save_json<T: Serialize>(path: &str, data: &T) {
+    let json = match serde_json::to_string_pretty(data) {
+        Ok(json) => json,
+        Err(e) => {
+            error!("❌ Помилка серіалізації {}: {}", path, e);
+            return;
+        }
+    };
+
+    // Write atomically: serialize to a temp file in the same directory, then
+    // rename over the target. A crash mid-write can never truncate/corrupt the
+    // real file (which would otherwise wipe all data on the next load).
+    let tmp_path = format!("{}.tmp", path);
+    if let Err(e) = fs::write(&tmp_path, &json) {
+        error!("❌ Не вдалося записати тимчасовий файл {}: {}", tmp_path, e);
+        return;
+    }
+    if let Err(e) = fs::rename(&tmp_path, path) {
+        error!("❌ Не вдалося зберегти {}: {}", path, e);
+        let _ = fs::remove_file(&tmp_path);
+        return;
+    }
+    info!("💾 Збережено: {}", path);
+}
+
+fn get_xp_needed(level: u64) -> u64 {
+    // Power-logarithmic curve: combines exponential growth with log scaling.
+    // Formula: 100 * (level + 1)^1.3 * ln(level + 2) + 100
+    // Lv0→1: ~169 | Lv5→6: ~2098 | Lv10→11: ~5957 | Lv20→21: ~16822 | Lv37→38: ~41055
+    let level_f = level as f64;
+    let needed = 100.0 * (level_f + 1.0).powf(1.3) * (level_f + 2.0).ln() + 100.0;
+    needed as u64
+}
+
+fn try_levelup(profile: &mut UserProfile) -> Option<u64> {
+    let mut leveled_up = None;
+    loop {
+        let needed = get_xp_needed(profile.level);
+        if profile.xp >= needed {
+            profile.xp -= needed;
+            profile.level += 1;
+            leveled_up = Some(profile.level);
+        } else {
+            break;
+        }
+    }
+    leveled_up
+}
+
+fn get_role_for_level(level: u64) -> Option<&'static str> {
+    let roles = get_roles_config();
+    let mut best_role = None;
+    for (req_lvl, name, _) in roles {
+        if level >= req_lvl {
+            best_role = Some(name);
+        }
+    }
+    best_role
+}
+
+async fn assign_role(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    user_id: serenity::UserId,
+    level: u64,
+) {
+    let target_role_name = match get_role_for_level(level) {
+        Some(name) => name,
+        None => return,
+    };
+
+    if let Ok(roles) = guild_id.roles(&ctx.http).await {
+        let target_role_id = roles
+            .values()
+            .find(|r| r.name == target_role_name)
+            .map(|r| r.id);
+
+        if let Some(add_id) = target_role_id {
+            let member_res = guild_id.member(&ctx.http, user_id).await;
+            if let Ok(member) = member_res {
+                if !member.roles.contains(&add_id) {
+                    let _ = member.add_role(&ctx.http, add_id).await;
+                }
+                let config = get_roles_config();
+                for (_, name, _) in config {
+                    if name != target_role_name {
+                        if let Some(old_role) = roles.values().find(|r| r.name == name) {
+                            if member.roles.contains(&old_role.id) {
+                                let _ = member.remove_role(&ctx.http, old_role.id).await;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn create_default_profile() -> UserProfile {
+    UserProfile {
+        xp: 0,
+        level: 0,
+        minutes: 0,
+        last_daily: 0,
+        daily_streak: 0,
+        best_streak: 0,
+        last_weekly: 0,
+        last_monthly: 0,
+        chips: 100,
+        bank: 0,
+        bank_interest_at: 0,
+        xp_boosters: Vec::new(),
+        xp_booster_x2_until: 0,
+        xp_booster_x5_until: 0,
+        inventory: Vec::new(),
+        title: None,
+        last_msg_time: 0,
+        spam_counter: 0,
+        spam_block_until: 0,
+    }
+}
+
+// Множник XP разом із джерелами, з яких він складається
+#[derive(Debug, Clone, PartialEq)]
+struct XpMultiplier {
+    sources: Vec<(String, f64)>,
+    total: f64,
+}
+
+/// Combines personal boosters, running server boosts and role multipliers
+/// of the member into one XP multiplier according to the server's stacking
+/// rule, capped at its maximum.
+fn xp_multiplier(
+    profile: &UserProfile,
+    settings: Option<&XpBoostSettings>,
+    member_roles: &[String],
+    now: i64,
+) -> XpMultiplier {
+    let mut sources: Vec<(String, f64)> = profile
+        .xp_boosters
+        .iter()
+        .filter(|b| b.until > now)
+        .map(|b| {
+            (
+                format!("🚀 Бустер до <t:{}:R>", b.until),
+                b.multiplier as f64,
+            )
+        })
+        .collect();
+    let stacking = settings.map(|s| s.stacking).unwrap_or_default();
+    let max = settings.map_or(default_max_xp_multiplier(), |s| s.max_multiplier);
+    if let Some(settings) = settings {
+        sources.extend(
+            settings
+                .server_boosts
+                .iter()
+                .filter(|b| b.until > now)
+                .map(|b| (format!("🎉 {}", b.name), b.multiplier)),
+        );
+        let mut roles: Vec<(String, f64)> = settings
+            .role_multipliers
+            .iter()
+            .filter(|(role_id, _)| member_roles.contains(role_id))
+            .map(|(role_id, m)| (format!("🎭 <@&{}>", role_id), *m))
+            .collect();
+        roles.sort_by(|a, b| a.0.cmp(&b.0));
+        sources.extend(roles);
+    }
+
+    let total = match stacking {
+        BoostStacking::Additive => 1.0 + sources.iter().map(|(_, m)| m - 1.0).sum::<f64>(),
+        BoostStacking::Multiplicative => sources.iter().map(|(_, m)| m).product(),
+        BoostStacking::Highest => sources.iter().map(|(_, m)| *m).fold(1.0, f64::max),
+    };
+    XpMultiplier {
+        sources,
+        total: total.clamp(1.0, max.max(1.0)),
+    }
+}
+
+fn boosted_xp(base: u64, multiplier: f64) -> u64 {
+    (base as f64 * multiplier).round() as u64
+}
+
+fn format_multiplier(m: f64) -> String {
+    format!("x{}", (m * 100.0).round() / 100.0)
+}
+
+/// XP boost settings of a guild, cloned so no lock is held while the
+/// users map is locked.
+fn guild_xp_boosts(data: &Data, guild_id: Option<serenity::GuildId>) -> Option<XpBoostSettings> {
+    let guild_id = guild_id?;
+    safe_lock(&data.xp_boosts)
+        .get(&guild_id.to_string())
+        .cloned()
+}
+
+// --- КОМАНДИ ---
+
+/// 📚 Показати всі доступні команди
+#[poise::command(slash_command)]
+async fn help(ctx: Context<'_>) -> Result<(), Error> {
+    let embed = CreateEmbed::new()
+        .title("📚 Довідка по боту StarostaBot")
+        .description("**Привіт! Я — твій сільський помічник з автоматичним управлінням! 🌾**")
+        .color(0x2ECC71)
+        .field(
+            "👤 **Профіль і прогрес**",
+            "`/rank` — Твоя картка з рівнем і XP\n\
+             `/leaderboard` — Топ учасників сервера\n\
+             `/daily`, `/weekly`, `/monthly` — Нагороди й серії\n\
+             `/balance show/history` — Гаманець та історія гривень\n\
+             `/pay @гравець <сума>` — Переказати гривні\n\
+             `/bank deposit/withdraw` — Банк з відсотками",
+            false,
+        )
+        .field(
+            "🎰 **Розваги**",
+            "`/casino <сума>` — Випробуй удачу!\n\
+             `/blackjack <ставка>` — Зіграй в блекджек\n\
+             `/roulette`, `/slots`, `/dice` — Рулетка, слоти й кубики\n\
+             `/duel @гравець <ставка>` — Дуель на монетку\n\
+             `/poll <питання> [варіанти]` — Голосування з кнопками й дедлайном\n\
+             `/giveaway start|end|reroll` — Розіграші (адмін)",
+            false,
+        )
+        .field(
+            "🎂 **Дні народження**",
+            "`/set_birthday <день> <місяць>` — Вкажи свій ДН\n\
+             `/birthdays` — Календар іменинників",
+            false,
+        )
+        .field(
+            "🛒 **Магазин і бустери**",
+            "`/shop list` — Товари магазину сервера\n\
+             `/shop buy <товар>` — Купити товар\n\
+             `/inventory` — Твій інвентар, титул і бустери\n\
+             `/use <предмет>` — Використати предмет",
+            false,
+        )
+        .field(
+            "💬 **Комунікація**",
+            "`/suggest <ідея>` — Запропонувати ідею\n\
+             📨 Напиши боту в особисті, щоб звернутися до персоналу",
+            false,
+        )
+        .field(
+            "🛠️ **Утиліти**",
+            "`/avatar [@користувач]` — Показати аватар\n\
+             `/remind <коли> <текст>` — Нагадування\n\
+             `/reminders list/cancel` — Мої нагадування\n\
+             `/tag <назва>` — Викликати тег сервера\n\
+             `/tags create/edit/delete/list` — Керування тегами\n\
+             `/idea status/withdraw/history` — Статус та історія ідеї\n\
+             `/ideas list/top/mine/search` — Огляд і пошук ідей\n\
+             `/info` — Інформація про бота",
+            false,
+        )
+        .field(
+            "👮 **Адмін: Основне**",
+            "`/setup_roles` — Налаштувати ролі\n\
+             `/admin_set_level/xp/chips` — Встановити рівень/XP/гривні\n\
+             `/economy audit/check/flags/stats` — Журнал, звірка, статистика\n\
+             `/economy config/rewards/transfers` — Параметри економіки\n\
+             `/admin_mute/unmute` — Мут/розмут (текст/голос/всюди)\n\
+             `/admin_add/remove_birthday` — Керувати ДН\n\
+             `/suggest [anonymous]` — Канал для ідей (можна анонімний)\n\
+             `/idea rules/theme/repost/rebuild` — Налаштування ідей\n\
+             `/games_config` — Ставки, перевага казино, слоти, блекджек\n\
+             `/shop add/edit/remove` — Товари магазину\n\
+             `/xp_boost event/stop/role/stacking` — Множники XP\n\
+             `/purge` — Видалити повідомлення\n\
+             `/clean` — Видалити повідомлення бота\n\
+             `/admin_announce` — Оголошення\n\
+             `/embed create/edit/export` — Конструктор embed-оголошень\n\
+             `/schedule create/list/pause/delete` — Заплановані оголошення\n\
+             `/modmail setup/reply/close/block/unblock` — Модмейл",
+            false,
+        )
+        .field(
+            "🤖 **Адмін: Автоматизація**",
+            "`/setup_autorole` — Авто-роль для новачків\n\
+             `/remove_autorole` — Видалити авто-роль\n\
+             `/cleanup_inactive` — Очистити неактивних\n\
+             `/add_banned_word` — Додати заборонене слово\n\
+             `/remove_banned_word` — Видалити заборонене слово\n\
+             `/list_banned_words` — Список заборонених слів\n\
+             `/autoresponder add/remove/list/scope` — Авто-відповіді",
+            false,
+        )
+        .field(
+            "✨ **Автоматичні функції**",
+            "• 🎭 Авто-роль для нових учасників\n\
+             • 👋 Привітання новачків\n\
+             • 🚫 Автоматична модерація лайок\n\
+             • 🔄 Анті-спам система\n\
+             • 🚀 Бустери XP для прискорення прогресу",
+            false,
+        )
+        .footer(CreateEmbedFooter::new(
+            "💡 Пиши в голосових каналах для XP!",
+        ))
+        .thumbnail("https://cdn.discordapp.com/emojis/1234567890.png");
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// ℹ️ Інформація про бота
+#[poise::command(slash_command)]
+async fn info(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_count = ctx.serenity_context().cache.guilds().len();
+    let user_count = {
+        let users = safe_lock(&ctx.data().users);
+        users.len()
+    };
+
+    let embed = CreateEmbed::new()
+        .title("ℹ️ Інформація про StarostaBot")
+        .description("**Сільський бот для Discord серверів** 🌾")
+        .color(0x3498DB)
+        .field(
+            "📊 Статистика",
+            format!(
+                "Серверів: **{}**\nКористувачів: **{}**",
+                guild_count, user_count
+            ),
+            true,
+        )
+        .field(
+            "⚙️ Технології",
+            "Rust 🦀\nSerenity + Poise\nHosted on Discloud",
+            true,
+        )
+        .field(
+            "🎯 Можливості",
+            "• Система рівнів і ролей\n\
+             • XP за повідомлення та голос\n\
+             • Ігри та казино\n\
+             • Привітання з ДН\n\
+             • Інструменти модерації",
+            false,
+        )
+        .footer(CreateEmbedFooter::new("Створено з ❤️ для твоєї спільноти"))
+        .timestamp(Timestamp::now());
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// 🏆 Таблиця лідерів
+#[poise::command(slash_command)]
+async fn leaderboard(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let mut leaders: Vec<(String, u64, u64, u64)> = {
+        let users = safe_lock(&ctx.data().users);
+        users
+            .iter()
+            .map(|(id, p)| (id.clone(), p.level, p.xp, p.minutes))
+            .collect()
+    };
+
+    // Сортуємо по рівню (спадаюче), потім по XP
+    leaders.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)));
+
+    let mut description = String::new();
+    let medals = ["🥇", "🥈", "🥉"];
+
+    for (i, (user_id, level, xp, minutes)) in leaders.iter().take(10).enumerate() {
+        let medal = if i < 3 { medals[i] } else { "🏅" };
+        let role_name = get_role_for_level(*level).unwrap_or("Новачок");
+        description.push_str(&format!(
+            "{}**{}. <@{}>**\n└ Рівень: **{}** | XP: **{}** | Голос: **{}** год\n└ Звання: *{}*\n\n",
+            medal, i + 1, user_id, level, xp, minutes / 60, role_name
+        ));
+    }
+
+    if description.is_empty() {
+        description = "Поки що немає активних користувачів 😔".to_string();
+    }
+
+    let embed = CreateEmbed::new()
+        .title("🏆 Таблиця лідерів")
+        .description(description)
+        .color(0xFFD700)
+        .footer(CreateEmbedFooter::new(
+            "💪 Продовжуй працювати, щоб потрапити в топ!",
+        ));
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// [ADMIN] Налаштувати всі ролі на сервері
+#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
+async fn setup_roles(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer().await?;
+    let guild = ctx.guild_id().ok_or("Not in a guild")?;
+    let existing_roles = guild.roles(&ctx.http()).await?;
+
+    let config = get_roles_config();
+
+    for (level, name, color_hex) in config {
+        // Determine safe permissions based on level tier
+        let permissions = if level < 10 {
+            // Levels 0-9 (Basic): Basic chat and voice permissions
+            serenity::Permissions::VIEW_CHANNEL
+                | serenity::Permissions::SEND_MESSAGES
+                | serenity::Permissions::READ_MESSAGE_HISTORY
+                | serenity::Permissions::CONNECT
+                | serenity::Permissions::SPEAK
+                | serenity::Permissions::USE_VAD
+        } else if level < 30 {
+            // Levels 10-29 (Trusted): Basic + enhanced interaction permissions
+            serenity::Permissions::VIEW_CHANNEL
+                | serenity::Permissions::SEND_MESSAGES
+                | serenity::Permissions::READ_MESSAGE_HISTORY
+                | serenity::Permissions::CONNECT
+                | serenity::Permissions::SPEAK
+                | serenity::Permissions::USE_VAD
+                | serenity::Permissions::CHANGE_NICKNAME
+                | serenity::Permissions::ATTACH_FILES
+                | serenity::Permissions::USE_EXTERNAL_EMOJIS
+                | serenity::Permissions::ADD_REACTIONS
+        } else {
+            // Levels 30-50 (Elite): Trusted + advanced community features
+            serenity::Permissions::VIEW_CHANNEL
+                | serenity::Permissions::SEND_MESSAGES
+                | serenity::Permissions::READ_MESSAGE_HISTORY
+                | serenity::Permissions::CONNECT
+                | serenity::Permissions::SPEAK
+                | serenity::Permissions::USE_VAD
+                | serenity::Permissions::CHANGE_NICKNAME
+                | serenity::Permissions::ATTACH_FILES
+                | serenity::Permissions::USE_EXTERNAL_EMOJIS
+                | serenity::Permissions::ADD_REACTIONS
+                | serenity::Permissions::USE_SOUNDBOARD
+                | serenity::Permissions::PRIORITY_SPEAKER
+                | serenity::Permissions::CREATE_PUBLIC_THREADS
+                | serenity::Permissions::SEND_MESSAGES_IN_THREADS
+        };
+
+        if let Some(role) = existing_roles.values().find(|r| r.name == name) {
+            // Update existing role with color, hoist, and safe permissions
+            let _ = guild
+                .edit_role(
+                    &ctx.http(),
+                    role.id,
+                    EditRole::new()
+                        .colour(Color::from(color_hex))
+                        .hoist(true)
+                        .permissions(permissions),
+                )
+                .await;
+        } else {
+            // Create new role with color, hoist, and safe permissions
+            let _ = guild
+                .create_role(
+                    &ctx.http(),
+                    EditRole::new()
+                        .name(name)
+                        .colour(Color::from(color_hex))
+                        .hoist(true)
+                        .permissions(permissions),
+                )
+                .await;
+        }
+    }
+
+    // Create birthday role with basic permissions (no admin/moderation rights)
+    if !existing_roles
+        .values()
+        .any(|r| r.name == BIRTHDAY_ROLE_NAME)
+    {
+        let birthday_perms = serenity::Permissions::VIEW_CHANNEL
+            | serenity::Permissions::SEND_MESSAGES
+            | serenity::Permissions::READ_MESSAGE_HISTORY
+            | serenity::Permissions::CONNECT
+            | serenity::Permissions::SPEAK
+            | serenity::Permissions::USE_VAD;
+
+        let _ = guild
+            .create_role(
+                &ctx.http(),
+                EditRole::new()
+                    .name(BIRTHDAY_ROLE_NAME)
+                    .colour(0xFF69B4)
+                    .hoist(true)
+                    .permissions(birthday_perms),
+            )
+            .await;
+    }
+
+    ctx.say("✅ Всі ролі створено та пофарбовано з безпечними правами!")
+        .await?;
+    Ok(())
+}
+
+/// [ADMIN] Встановити рівень користувачу
+#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
+async fn admin_set_level(ctx: Context<'_>, user: serenity::User, level: u64) -> Result<(), Error> {
+    let user_id = user.id.to_string();
+    {
+        let mut users = safe_lock(&ctx.data().users);
+        let profile = users.entry(user_id).or_insert(create_default_profile());
+        profile.level = level;
+        save_json(USERS_FILE, &*users);
+    }
+
+    if let Some(guild_id) = ctx.guild_id() {
+        assign_role(ctx.serenity_context(), guild_id, user.id, level).await;
+    }
+
+    ctx.say(format!(
+        "👮‍♂️ Адмін встановив рівень **{}** для користувача <@{}>.",
+        level, user.id
+    ))
+    .await?;
+    Ok(())
+}
+
+/// [ADMIN] Змінити XP користувачу
+#[poise::command(
+    slash_command,
+    default_member_permissions = "ADMINISTRATOR",
+    rename = "admin_set_xp"
+)]
+async fn admin_set_xp(ctx: Context<'_>, user: serenity::User, xp: u64) -> Result<(), Error> {
+    let user_id = user.id.to_string();
+    {
+        let mut users = safe_lock(&ctx.data().users);
+        let profile = users.entry(user_id).or_insert(create_default_profile());
+        profile.xp = xp;
+        save_json(USERS_FILE, &*users);
+    }
+
+    ctx.say(format!(
+        "👮‍♂️ Адмін встановив **{} XP** для користувача <@{}>.",
+        xp, user.id
+    ))
+    .await?;
+    Ok(())
+}
+
+/// [ADMIN] Змінити гривні користувачу
+#[poise::command(
+    slash_command,
+    default_member_permissions = "ADMINISTRATOR",
+    rename = "admin_set_chips"
+)]
+async fn admin_set_chips(ctx: Context<'_>, user: serenity::User, chips: u64) -> Result<(), Error> {
+    let user_id = user.id.to_string();
+    {
+        let mut users = safe_lock(&ctx.data().users);
+        let profile = users
+            .entry(user_id.clone())
+            .or_insert(create_default_profile());
+        let delta = chips as i64 - profile.chips as i64;
+        profile.chips = chips;
+        log_transactions(
+            ctx.data(),
+            &[(&user_id, delta)],
+            "admin",
+            Some(&ctx.author().id.to_string()),
+        );
+        save_json(USERS_FILE, &*users);
+    }
+
+    ctx.say(format!(
+        "👮‍♂️ Адмін встановив **{} гривень** 💰 для користувача <@{}>.",
+        chips, user.id
+    ))
+    .await?;
+    Ok(())
+}
+
+/// [ADMIN] Замутити користувача
+#[poise::command(slash_command, default_member_permissions = "MODERATE_MEMBERS")]
+async fn admin_mute(
+    ctx: Context<'_>,
+    user: serenity::User,
+    minutes: i64,
+    #[description = "Тип мута: text (текст), voice (голос), all (всюди)"] mute_type: Option<String>,
+    reason: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?;
+    let mute_mode = mute_type.unwrap_or("all".to_string()).to_lowercase();
+
+    let _member = guild_id.member(&ctx.http(), user.id).await?;
+
+    match mute_mode.as_str() {
+        "text" | "текст" => {
+            // Мут тільки в текстових каналах
+            let time_until =
+                Timestamp::from_unix_timestamp(Utc::now().timestamp() + (minutes * 60))?;
+            guild_id
+                .edit_member(
+                    &ctx.http(),
+                    user.id,
+                    EditMember::new().disable_communication_until(time_until.to_string()),
+                )
+                .await?;
+            ctx.say(format!(
+                "🔇 Користувача <@{}> замучено в **текстових каналах** на {} хв.\nПричина: {}",
+                user.id,
+                minutes,
+                reason.unwrap_or("Не вказана".to_string())
+            ))
+            .await?;
+        }
+        "voice" | "голос" => {
+            // Мут тільки в голосових каналах
+            guild_id
+                .edit_member(&ctx.http(), user.id, EditMember::new().mute(true))
+                .await?;
+            ctx.say(format!("🔇 Користувача <@{}> замучено в **голосових каналах** на {} хв.\nПричина: {}\n\n⚠️ Потрібно вручну розмутити після закінчення часу.", 
+                user.id, minutes, reason.clone().unwrap_or("Не вказана".to_string()))).await?;
+        }
+        _ => {
+            // Мут всюди (текст + голос)
+            let time_until =
+                Timestamp::from_unix_timestamp(Utc::now().timestamp() + (minutes * 60))?;
+            guild_id
+                .edit_member(
+                    &ctx.http(),
+                    user.id,
+                    EditMember::new()
+                        .disable_communication_until(time_until.to_string())
+                        .mute(true),
+                )
+                .await?;
+            ctx.say(format!("🔇 Користувача <@{}> замучено **всюди** на {} хв.\nПричина: {}\n\n⚠️ Текстовий мут автоматичний, голосовий потрібно зняти вручну.", 
+                user.id, minutes, reason.unwrap_or("Не вказана".to_string()))).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// [ADMIN] Розмутити користувача
+#[poise::command(slash_command, default_member_permissions = "MODERATE_MEMBERS")]
+async fn admin_unmute(ctx: Context<'_>, user: serenity::User) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?;
+    guild_id
+        .edit_member(
+            &ctx.http(),
+            user.id,
+            EditMember::new().enable_communication(),
+        )
+        .await?;
+    ctx.say(format!("🔊 Користувача <@{}> розмучено.", user.id))
+        .await?;
+    Ok(())
+}
+
+/// [ADMIN] Відправити оголошення в канал
+#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
+async fn admin_announce(ctx: Context<'_>, channel: ChannelId, text: String) -> Result<(), Error> {
+    channel.say(&ctx.http(), text).await?;
+    ctx.send(
+        poise::CreateReply::default()
+            .content("✅ Оголошення надіслано.")
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Deletes messages while respecting Discord's rules: bulk delete only accepts
+/// 2–100 messages newer than 14 days. Older messages — and lone messages — are
+/// removed one by one so the command never errors out on those edge cases.
+async fn delete_messages_safely(
+    http: &serenity::Http,
+    channel_id: serenity::ChannelId,
+    messages: &[serenity::Message],
+) -> usize {
+    let cutoff = Utc::now().timestamp() - 14 * 24 * 60 * 60;
+    let mut recent: Vec<serenity::MessageId> = Vec::new();
+    let mut old: Vec<serenity::MessageId> = Vec::new();
+    for m in messages {
+        if m.timestamp.unix_timestamp() >= cutoff {
+            recent.push(m.id);
+        } else {
+            old.push(m.id);
+        }
+    }
+
+    let mut deleted = 0;
+    for chunk in recent.chunks(100) {
+        match chunk {
+            [] => {}
+            // Bulk delete requires at least 2 messages; delete a lone one directly.
+            [single] => {
+                if channel_id.delete_message(http, *single).await.is_ok() {
+                    deleted += 1;
+                }
+            }
+            many => {
+                if channel_id.delete_messages(http, many).await.is_ok() {
+                    deleted += many.len();
+                }
+            }
+        }
+    }
+    for id in old {
+        if channel_id.delete_message(http, id).await.is_ok() {
+            deleted += 1;
+        }
+    }
+    deleted
+}
+
+/// [ADMIN] Видалити повідомлення
+#[poise::command(slash_command, default_member_permissions = "MANAGE_MESSAGES")]
+async fn purge(ctx: Context<'_>, amount: u64) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let count = amount.clamp(1, 100);
+    let messages = ctx
+        .channel_id()
+        .messages(&ctx.http(), GetMessages::new().limit(count as u8))
+        .await?;
+
+    let deleted = delete_messages_safely(ctx.http(), ctx.channel_id(), &messages).await;
+    ctx.say(format!("🧹 Адмін видалив {} повідомлень.", deleted))
+        .await?;
+    Ok(())
+}
+
+/// [ADMIN] Видалити повідомлення бота
+#[poise::command(slash_command, default_member_permissions = "MANAGE_MESSAGES")]
+async fn clean(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let messages = ctx
+        .channel_id()
+        .messages(&ctx.http(), GetMessages::new().limit(100))
+        .await?;
+    let bot_id = ctx.framework().bot_id;
+    let to_delete: Vec<serenity::Message> = messages
+        .into_iter()
+        .filter(|m| m.author.id == bot_id)
+        .collect();
+
+    let deleted = delete_messages_safely(ctx.http(), ctx.channel_id(), &to_delete).await;
+    ctx.say(format!("🧹 Видалено {} моїх повідомлень.", deleted))
+        .await?;
+    Ok(())
+}
+
+/// Splits `a; b; c` (or `a | b | c`) into at most `MAX_POLL_OPTIONS` options.
+fn parse_poll_options(input: &str) -> Vec<String> {
+    input
+        .split([';', '|'])
+        .map(|o| o.trim().to_string())
+        .filter(|o| !o.is_empty())
+        .take(MAX_POLL_OPTIONS)
+        .collect()
+}
+
+/// Toggles `user_id`'s vote for `option`. Single-choice polls move the vote;
+/// clicking the same option again retracts it. Returns `true` when a vote
+/// was added and `false` when one was removed.
+fn apply_poll_vote(poll: &mut PollData, user_id: &str, option: usize) -> bool {
+    let choices = poll.votes.entry(user_id.to_string()).or_default();
+    let added = if let Some(pos) = choices.iter().position(|c| *c == option) {
+        choices.remove(pos);
+        false
+    } else {
+        if !poll.multi {
+            choices.clear();
+        }
+        choices.push(option);
+        true
+    };
+    if choices.is_empty() {
+        poll.votes.remove(user_id);
+    }
+    added
+}
+
+fn poll_counts(poll: &PollData) -> Vec<usize> {
+    let mut counts = vec![0; poll.options.len()];
+    for choices in poll.votes.values() {
+        for c in choices {
+            if let Some(n) = counts.get_mut(*c) {
+                *n += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// Text bar chart of the results: one line per option with a bar, percent
+/// of voters and raw count.
+fn poll_results_lines(poll: &PollData) -> String {
+    let counts = poll_counts(poll);
+    let voters = poll.votes.len();
+    let mut out = String::new();
+    for (i, (option, count)) in poll.options.iter().zip(&counts).enumerate() {
+        let pct = if voters > 0 {
+            *count as f64 / voters as f64 * 100.0
+        } else {
+            0.0
+        };
+        let filled = ((pct / 10.0).round() as usize).min(10);
+        out.push_str(&format!(
+            "**{}. {}**\n{}{} {:.0}% ({})\n",
+            i + 1,
+            option,
+            "▰".repeat(filled),
+            "▱".repeat(10 - filled),
+            pct,
+            count
+        ));
+    }
+    out
+}
+
+fn poll_embed(poll: &PollData, author_name: &str, closed: bool) -> CreateEmbed {
+    let mut description = format!("**{}**\n\n{}", poll.question, poll_results_lines(poll));
+    if let Some(role) = &poll.required_role {
+        description.push_str(&format!("\n🔒 Голосують лише <@&{}>", role));
+    }
+    if let Some(ends) = poll.ends_at {
+        if closed {
+            description.push_str(&format!(
+                "\n🏁 Завершено <t:{}:R>",
+                ends.min(Utc::now().timestamp())
+            ));
+        } else {
+            description.push_str(&format!("\n⏳ Завершення: <t:{}:R>", ends));
+        }
+    } else if closed {
+        description.push_str("\n🏁 Голосування завершено");
+    }
+
+    let mode = format!(
+        "{} | {}",
+        if poll.multi {
+            "Кілька варіантів"
+        } else {
+            "Один варіант"
+        },
+        if poll.anonymous {
+            "Анонімно"
+        } else {
+            "Публічно"
+        }
+    );
+    CreateEmbed::new()
+        .title(if closed {
+            "📊 Результати голосування"
+        } else {
+            "📊 Голосування"
+        })
+        .description(description)
+        .colour(if closed { 0x95A5A6 } else { 0xF1C40F })
+        .footer(CreateEmbedFooter::new(format!(
+            "Автор: {} | {} | Голосів: {}",
+            author_name,
+            mode,
+            poll.votes.len()
+        )))
+}
+
+fn poll_buttons(poll: &PollData) -> Vec<CreateActionRow> {
+    let option_buttons: Vec<CreateButton> = poll
+        .options
+        .iter()
+        .enumerate()
+        .map(|(i, o)| {
+            let label: String = o.chars().take(70).collect();
+            CreateButton::new(format!("poll_vote_{}", i))
+                .label(format!("{}. {}", i + 1, label))
+                .style(ButtonStyle::Primary)
+        })
+        .collect();
+
+    let mut rows: Vec<CreateActionRow> = option_buttons
+        .chunks(5)
+        .map(|c| CreateActionRow::Buttons(c.to_vec()))
+        .collect();
+
+    let mut controls = Vec::new();
+    if !poll.anonymous {
+        controls.push(
+            CreateButton::new("poll_voters")
+                .label("Хто голосував")
+                .style(ButtonStyle::Secondary)
+                .emoji('👥'),
+        );
+    }
+    controls.push(
+        CreateButton::new("poll_close")
+            .label("Завершити")
+            .style(ButtonStyle::Danger)
+            .emoji('🏁'),
+    );
+    rows.push(CreateActionRow::Buttons(controls));
+    rows
+}
+
+/// Closes a poll: freezes the original message and posts a results embed.
+async fn close_poll(ctx: &serenity::Context, data: &Data, message_id: &str) {
+    let Some(poll) = ({
+        let mut polls = safe_lock(&data.polls);
+        let removed = polls.remove(message_id);
+        if removed.is_some() {
+            save_json(POLLS_FILE, &*polls);
+        }
+        removed
+    }) else {
+        return;
+    };
+
+    let (Ok(channel_num), Ok(msg_num)) = (
+        poll.channel_id.parse::<u64>(),
+        poll.message_id.parse::<u64>(),
+    ) else {
+        return;
+    };
+    let channel = ChannelId::new(channel_num);
+    let author_name = match poll.author_id.parse::<u64>() {
+        Ok(id) => serenity::UserId::new(id)
+            .to_user(&ctx.http)
+            .await
+            .map(|u| u.name)
+            .unwrap_or_default(),
+        Err(_) => String::new(),
+    };
+
+    let _ = channel
+        .edit_message(
+            &ctx.http,
+            serenity::MessageId::new(msg_num),
+            serenity::EditMessage::new()
+                .embed(poll_embed(&poll, &author_name, true))
+                .components(vec![]),
+        )
+        .await;
+
+    let counts = poll_counts(&poll);
+    let best = counts.iter().copied().max().unwrap_or(0);
+    let winners: Vec<&str> = poll
+        .options
+        .iter()
+        .zip(&counts)
+        .filter(|(_, c)| best > 0 && **c == best)
+        .map(|(o, _)| o.as_str())
+        .collect();
+    let verdict = match winners.len() {
+        0 => "Ніхто не проголосував 😔".to_string(),
+        1 => format!("🏆 Переміг варіант: **{}**", winners[0]),
+        _ => format!("🤝 Нічия: **{}**", winners.join("**, **")),
+    };
+    // Заголовок ембеда обмежено 256 символами
+    let question: String = poll.question.chars().take(240).collect();
+    let results = CreateEmbed::new()
+        .title(format!("🏁 Підсумки: {}", question))
+        .description(format!("{}\n\n{}", poll_results_lines(&poll), verdict))
+        .colour(0x2ECC71)
+        .footer(CreateEmbedFooter::new(format!(
+            "Проголосувало: {}",
+            poll.votes.len()
+        )));
+    let _ = channel
+        .send_message(
+            &ctx.http,
+            CreateMessage::new()
+                .embed(results)
+                .reference_message((channel, serenity::MessageId::new(msg_num))),
+        )
+        .await;
+}
+
+/// Closes every poll whose deadline has passed. Runs from `background_tasks`.
+async fn close_expired_polls(ctx: &serenity::Context, data: &Data) {
+    let now = Utc::now().timestamp();
+    let expired: Vec<String> = {
+        let polls = safe_lock(&data.polls);
+        polls
+            .values()
+            .filter(|p| p.ends_at.is_some_and(|e| e <= now))
+            .map(|p| p.message_id.clone())
+            .collect()
+    };
+    for id in expired {
+        close_poll(ctx, data, &id).await;
+    }
+}
+
+/// Handles `poll_*` buttons: voting, the voter list and closing.
+async fn handle_poll_interaction(
+    ctx: &serenity::Context,
+    data: &Data,
+    interaction: &serenity::ComponentInteraction,
+) -> Result<(), Error> {
+    let custom_id = interaction.data.custom_id.as_str();
+    let msg_id = interaction.message.id.to_string();
+    let user_id = interaction.user.id.to_string();
+
+    let reply = |text: String| {
+        serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::new()
+                .content(text)
+                .ephemeral(true),
+        )
+    };
+
+    let Some(poll) = safe_lock(&data.polls).get(&msg_id).cloned() else {
+        interaction
+            .create_response(
+                &ctx.http,
+                reply("❌ Це голосування вже завершено.".to_string()),
+            )
+            .await?;
+        return Ok(());
+    };
+
+    if custom_id == "poll_close" {
+        let is_admin = interaction
+            .member
+            .as_ref()
+            .and_then(|m| m.permissions)
+            .map(|p| p.administrator() || p.manage_messages())
+            .unwrap_or(false);
+        if user_id != poll.author_id && !is_admin {
+            interaction
+                .create_response(
+                    &ctx.http,
+                    reply("❌ Завершити може лише автор або модератор.".to_string()),
+                )
+                .await?;
+            return Ok(());
+        }
+        interaction
+            .create_response(&ctx.http, reply("🏁 Голосування завершено.".to_string()))
+            .await?;
+        close_poll(ctx, data, &msg_id).await;
+        return Ok(());
+    }
+
+    if custom_id == "poll_voters" {
+        let mut text = String::new();
+        if poll.anonymous {
+            text.push_str("🙈 Це анонімне голосування.");
+        } else {
+            for (i, option) in poll.options.iter().enumerate() {
+                let voters: Vec<String> = poll
+                    .votes
+                    .iter()
+                    .filter(|(_, c)| c.contains(&i))
+                    .map(|(u, _)| format!("<@{}>", u))
+                    .collect();
+                let shown = if voters.is_empty() {
+                    "—".to_string()
+                } else {
+                    voters.join(", ")
+                };
+                text.push_str(&format!("**{}**: {}\n", option, shown));
+            }
+        }
+        let text: String = text.chars().take(1900).collect();
+        interaction.create_response(&ctx.http, reply(text)).await?;
+        return Ok(());
+    }
+
+    let Some(option) = custom_id
+        .strip_prefix("poll_vote_")
+        .and_then(|i| i.parse::<usize>().ok())
+        .filter(|i| *i < poll.options.len())
+    else {
+        return Ok(());
+    };
+
+    if let Some(role) = poll
+        .required_role
+        .as_ref()
+        .and_then(|r| r.parse::<u64>().ok())
+    {
+        let has_role = interaction
+            .member
+            .as_ref()
+            .map(|m| m.roles.contains(&serenity::RoleId::new(role)))
+            .unwrap_or(false);
+        if !has_role {
+            interaction
+                .create_response(
+                    &ctx.http,
+                    reply(format!(
+                        "🔒 Голосувати можуть лише учасники з роллю <@&{}>.",
+                        role
+                    )),
+                )
+                .await?;
+            return Ok(());
+        }
+    }
+
+    // Атомарно записуємо голос під одним локом, щоб одночасні натискання
+    // не загубили жодного голосу.
+    let now = Utc::now().timestamp();
+    let outcome = {
+        let mut polls = safe_lock(&data.polls);
+        match polls.get_mut(&msg_id) {
+            None => PollVoteOutcome::Gone,
+            Some(p) if p.ends_at.is_some_and(|e| e <= now) => PollVoteOutcome::Expired,
+            Some(p) => {
+                apply_poll_vote(p, &user_id, option);
+                let snap = Box::new(p.clone());
+                save_json(POLLS_FILE, &*polls);
+                PollVoteOutcome::Recorded(snap)
+            }
+        }
+    };
+
+    match outcome {
+        PollVoteOutcome::Gone | PollVoteOutcome::Expired => {
+            interaction
+                .create_response(
+                    &ctx.http,
+                    reply("❌ Це голосування вже завершено.".to_string()),
+                )
+                .await?;
+        }
+        PollVoteOutcome::Recorded(poll) => {
+            let author_name = match poll.author_id.parse::<u64>() {
+                Ok(id) => serenity::UserId::new(id)
+                    .to_user(&ctx.http)
+                    .await
+                    .map(|u| u.name)
+                    .unwrap_or_default(),
+                Err(_) => String::new(),
+            };
+            interaction
+                .create_response(
+                    &ctx.http,
+                    serenity::CreateInteractionResponse::UpdateMessage(
+                        serenity::CreateInteractionResponseMessage::new().embed(poll_embed(
+                            &poll,
+                            &author_name,
+                            false,
+                        )),
+                    ),
+                )
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// 📊 Створити голосування
+#[poise::command(slash_command)]
+async fn poll(
+    ctx: Context<'_>,
+    #[description = "Питання"] question: String,
+    #[description = "Варіанти через ; (до 10). Без них — Так/Ні"] options: Option<String>,
+    #[description = "Дозволити кілька варіантів"] multi: Option<bool>,
+    #[description = "Приховати, хто як голосував"] anonymous: Option<bool>,
+    #[description = "Тривалість: 30m, 2h, 1d"] duration: Option<String>,
+    #[description = "Голосувати можуть лише з цією роллю"] role: Option<serenity::Role>,
+) -> Result<(), Error> {
+    let options = match options.as_deref().map(parse_poll_options) {
+        Some(o) if o.len() >= 2 => o,
+        Some(_) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content("❌ Потрібно щонайменше 2 варіанти (через `;`).")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+        None => vec!["👍 Так".to_string(), "👎 Ні".to_string()],
+    };
+
+    let now = Utc::now().timestamp();
+    let ends_at = match duration.as_deref().map(parse_duration) {
+        Some(Some(secs)) => Some(now + secs),
+        Some(None) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content("❌ Невірна тривалість. Приклади: `30m`, `2h`, `1d`.")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+        None => None,
+    };
+
+    let mut poll = PollData {
+        message_id: String::new(),
+        channel_id: ctx.channel_id().to_string(),
+        author_id: ctx.author().id.to_string(),
+        question,
+        options,
+        multi: multi.unwrap_or(false),
+        anonymous: anonymous.unwrap_or(false),
+        ends_at,
+        required_role: role.map(|r| r.id.to_string()),
+        votes: HashMap::new(),
+        created_at: now,
+    };
+
+    let msg = ctx
+        .send(
+            poise::CreateReply::default()
+                .embed(poll_embed(&poll, &ctx.author().name, false))
+                .components(poll_buttons(&poll)),
+        )
+        .await?;
+    let m = msg.message().await?;
+    poll.message_id = m.id.to_string();
+
+    let mut polls = safe_lock(&ctx.data().polls);
+    polls.insert(poll.message_id.clone(), poll);
+    save_json(POLLS_FILE, &*polls);
+    Ok(())
+}
+
+/// Draws up to `count` distinct winners from `entrants`, skipping anyone in
+/// `exclude` (previous winners on reroll).
+fn pick_giveaway_winners<R: Rng>(
+    entrants: &[String],
+    exclude: &[String],
+    count: usize,
+    rng: &mut R,
+) -> Vec<String> {
+    use rand::seq::SliceRandom;
+    let pool: Vec<&String> = entrants.iter().filter(|e| !exclude.contains(e)).collect();
+    pool.choose_multiple(rng, count)
+        .map(|e| (*e).clone())
+        .collect()
+}
+
+fn giveaway_embed(g: &Giveaway) -> CreateEmbed {
+    let mut description = format!("🎁 **{}**\n\n", g.prize);
+    if g.ended {
+        if g.winner_ids.is_empty() {
+            description.push_str("😔 Переможців немає — ніхто не взяв участь.\n");
+        } else {
+            let list: Vec<String> = g.winner_ids.iter().map(|w| format!("<@{}>", w)).collect();
+            description.push_str(&format!("🏆 Переможці: {}\n", list.join(", ")));
+        }
+        description.push_str(&format!("🏁 Завершено <t:{}:R>\n", g.ends_at));
+    } else {
+        description.push_str(&format!(
+            "⏳ Завершення: <t:{}:R>\n🏆 Переможців: **{}**\n",
+            g.ends_at, g.winners
+        ));
+    }
+
+    let mut reqs = Vec::new();
+    if g.min_level > 0 {
+        reqs.push(format!("⭐ Рівень від **{}**", g.min_level));
+    }
+    if let Some(role) = &g.required_role {
+        reqs.push(format!("🔒 Роль <@&{}>", role));
+    }
+    if g.entry_fee > 0 {
+        reqs.push(format!("💰 Внесок **{} гривень**", g.entry_fee));
+    }
+    if !reqs.is_empty() {
+        description.push_str(&format!("\n**Умови участі:**\n{}\n", reqs.join("\n")));
+    }
+
+    CreateEmbed::new()
+        .title(if g.ended {
+            "🎉 Розіграш завершено"
+        } else {
+            "🎉 Розіграш!"
+        })
+        .description(description)
+        .colour(if g.ended { 0x95A5A6 } else { 0xE91E63 })
+        .footer(CreateEmbedFooter::new(format!(
+            "Учасників: {}",
+            g.entrants.len()
+        )))
+}
+
+fn giveaway_buttons() -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(vec![CreateButton::new(
+        "giveaway_enter",
+    )
+    .label("Взяти участь")
+    .style(ButtonStyle::Success)
+    .emoji('🎉')])]
+}
+
+/// Accepts either a message link or a bare message ID.
+fn message_id_from_input(input: &str) -> String {
+    parse_message_link(input)
+        .map(|(_, _, m)| m.to_string())
+        .unwrap_or_else(|| input.trim().to_string())
+}
+
+/// Draws winners (a reroll skips earlier winners), stores them and announces
+/// the result. Returns `None` if the giveaway does not exist, or has already
+/// ended and this is not a reroll.
+async fn finish_giveaway(
+    ctx: &serenity::Context,
+    data: &Data,
+    message_id: &str,
+    reroll: bool,
+) -> Option<Vec<String>> {
+    let giveaway = {
+        let mut giveaways = safe_lock(&data.giveaways);
+        let g = giveaways.get_mut(message_id)?;
+        // Перевірка під локом: /giveaway end і фонова задача не завершать
+        // розіграш двічі
+        if g.ended && !reroll {
+            return None;
+        }
+        g.draw(reroll, &mut rand::thread_rng());
+        g.ended = true;
+        let snap = g.clone();
+        save_json(GIVEAWAYS_FILE, &*giveaways);
+        snap
+    };
+
+    let (Ok(channel_num), Ok(msg_num)) = (
+        giveaway.channel_id.parse::<u64>(),
+        giveaway.message_id.parse::<u64>(),
+    ) else {
+        return Some(giveaway.winner_ids);
+    };
+    let channel = ChannelId::new(channel_num);
+    let message = serenity::MessageId::new(msg_num);
+
+    let _ = channel
+        .edit_message(
+            &ctx.http,
+            message,
+            serenity::EditMessage::new()
+                .embed(giveaway_embed(&giveaway))
+                .components(vec![]),
+        )
+        .await;
+
+    let text = if giveaway.winner_ids.is_empty() {
+        format!(
+            "😔 Розіграш **{}** завершився без переможців.",
+            giveaway.prize
+        )
+    } else {
+        let list: Vec<String> = giveaway
+            .winner_ids
+            .iter()
+            .map(|w| format!("<@{}>", w))
+            .collect();
+        format!(
+            "🎉 {}{}! Ви виграли **{}**!",
+            if reroll {
+                "Новий розіграш: "
+            } else {
+                "Вітаємо, "
+            },
+            list.join(", "),
+            giveaway.prize
+        )
+    };
+    let _ = channel
+        .send_message(
+            &ctx.http,
+            CreateMessage::new()
+                .content(text)
+                .reference_message((channel, message)),
+        )
+        .await;
+    Some(giveaway.winner_ids)
+}
+
+/// Finishes giveaways past their deadline and forgets ones that ended over
+/// a month ago. Runs from `background_tasks`.
+async fn finish_due_giveaways(ctx: &serenity::Context, data: &Data) {
+    let now = Utc::now().timestamp();
+    let due: Vec<String> = {
+        let mut giveaways = safe_lock(&data.giveaways);
+        let before = giveaways.len();
+        giveaways.retain(|_, g| !(g.ended && now - g.ends_at > 30 * 86400));
+        if giveaways.len() != before {
+            save_json(GIVEAWAYS_FILE, &*giveaways);
+        }
+        giveaways
+            .values()
+            .filter(|g| !g.ended && g.ends_at <= now)
+            .map(|g| g.message_id.clone())
+            .collect()
+    };
+    for id in due {
+        finish_giveaway(ctx, data, &id, false).await;
+    }
+}
+
+/// Handles the `giveaway_enter` button: checks requirements, charges the
+/// entry fee and toggles participation.
+async fn handle_giveaway_interaction(
+    ctx: &serenity::Context,
+    data: &Data,
+    interaction: &serenity::ComponentInteraction,
+) -> Result<(), Error> {
+    let msg_id = interaction.message.id.to_string();
+    let user_id = interaction.user.id.to_string();
+    let now = Utc::now().timestamp();
+    let roles = interaction
+        .member
+        .as_ref()
+        .map(|m| m.roles.clone())
+        .unwrap_or_default();
+
+    // Лок розіграшів береться перед локом користувачів: внесок списується
+    // і учасник додається атомарно, тож подвійне натискання не спише двічі.
+    let result: Result<(String, Giveaway), String> = {
+        let mut giveaways = safe_lock(&data.giveaways);
+        match giveaways.get_mut(&msg_id) {
+            None => Err("❌ Цей розіграш не знайдено.".to_string()),
+            Some(g) if g.ended || g.ends_at <= now => {
+                Err("❌ Цей розіграш уже завершено.".to_string())
+            }
+            Some(g) => {
+                let mut users = safe_lock(&data.users);
+                let outcome = if let Some(pos) = g.entrants.iter().position(|e| *e == user_id) {
+                    g.entrants.remove(pos);
+                    if g.entry_fee > 0 {
+                        let profile = users
+                            .entry(user_id.clone())
+                            .or_insert(create_default_profile());
+                        profile.chips = profile.chips.saturating_add(g.entry_fee);
+                        log_transactions(
+                            data,
+                            &[(&user_id, g.entry_fee as i64)],
+                            "giveaway_refund",
+                            Some(&msg_id),
+                        );
+                        save_json(USERS_FILE, &*users);
+                        Ok(format!(
+                            "👋 Ти вийшов з розіграшу. Повернено **{} гривень**.",
+                            g.entry_fee
+                        ))
+                    } else {
+                        Ok("👋 Ти вийшов з розіграшу.".to_string())
+                    }
+                } else {
+                    let profile = users
+                        .entry(user_id.clone())
+                        .or_insert(create_default_profile());
+                    let role_ok = g
+                        .required_role
+                        .as_ref()
+                        .and_then(|r| r.parse::<u64>().ok())
+                        .is_none_or(|r| roles.contains(&serenity::RoleId::new(r)));
+                    if profile.level < g.min_level {
+                        Err(format!(
+                            "⭐ Потрібен рівень **{}** (у тебе {}).",
+                            g.min_level, profile.level
+                        ))
+                    } else if !role_ok {
+                        Err("🔒 У тебе немає потрібної ролі.".to_string())
+                    } else if profile.chips < g.entry_fee {
+                        Err(format!(
+                            "💸 Потрібно **{} гривень** для участі (у тебе {}).",
+                            g.entry_fee, profile.chips
+                        ))
+                    } else {
+                        profile.chips -= g.entry_fee;
+                        if g.entry_fee > 0 {
+                            log_transactions(
+                                data,
+                                &[(&user_id, -(g.entry_fee as i64))],
+                                "giveaway_fee",
+                                Some(&msg_id),
+                            );
+                            save_json(USERS_FILE, &*users);
+                        }
+                        g.entrants.push(user_id.clone());
+                        Ok(if g.entry_fee > 0 {
+                            format!(
+                                "🎉 Ти береш участь! Списано **{} гривень**. Натисни ще раз, щоб вийти.",
+                                g.entry_fee
+                            )
+                        } else {
+                            "🎉 Ти береш участь! Натисни ще раз, щоб вийти.".to_string()
+                        })
+                    }
+                };
+                match outcome {
+                    Ok(text) => {
+                        let snap = g.clone();
+                        save_json(GIVEAWAYS_FILE, &*giveaways);
+                        Ok((text, snap))
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+        }
+    };
+
+    match result {
+        Err(text) => {
+            interaction
+                .create_response(
+                    &ctx.http,
+                    serenity::CreateInteractionResponse::Message(
+                        serenity::CreateInteractionResponseMessage::new()
+                            .content(text)
+                            .ephemeral(true),
+                    ),
+                )
+                .await?;
+        }
+        Ok((text, giveaway)) => {
+            interaction
+                .create_response(
+                    &ctx.http,
+                    serenity::CreateInteractionResponse::UpdateMessage(
+                        serenity::CreateInteractionResponseMessage::new()
+                            .embed(giveaway_embed(&giveaway)),
+                    ),
+                )
+                .await?;
+            let _ = interaction
+                .create_followup(
+                    &ctx.http,
+                    serenity::CreateInteractionResponseFollowup::new()
+                        .content(text)
+                        .ephemeral(true),
+                )
+                .await;
+        }
+    }
+    Ok(())
+}
+
+/// 🎉 [ADMIN] Розіграші
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "MANAGE_GUILD",
+    subcommands("giveaway_start", "giveaway_end", "giveaway_reroll")
+)]
+async fn giveaway(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Почати розіграш у цьому каналі
+#[poise::command(slash_command, rename = "start")]
+async fn giveaway_start(
+    ctx: Context<'_>,
+    #[description = "Приз"] prize: String,
+    #[description = "Тривалість: 30m, 2h, 1d"] duration: String,
+    #[description = "Кількість переможців"]
+    #[min = 1]
+    #[max = 20]
+    winners: u32,
+    #[description = "Мінімальний рівень учасника"] min_level: Option<u64>,
+    #[description = "Потрібна роль"] role: Option<serenity::Role>,
+    #[description = "Внесок за участь (гривні)"] entry_fee: Option<u64>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
+    let Some(secs) = parse_duration(&duration) else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Невірна тривалість. Приклади: `30m`, `2h`, `1d`.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let mut giveaway = Giveaway {
+        message_id: String::new(),
+        channel_id: ctx.channel_id().to_string(),
+        guild_id,
+        host_id: ctx.author().id.to_string(),
+        prize,
+        winners: winners.max(1) as usize,
+        ends_at: Utc::now().timestamp() + secs,
+        min_level: min_level.unwrap_or(0),
+        required_role: role.map(|r| r.id.to_string()),
+        entry_fee: entry_fee.unwrap_or(0),
+        entrants: Vec::new(),
+        ended: false,
+        winner_ids: Vec::new(),
+        past_winners: Vec::new(),
+    };
+
+    let msg = ctx
+        .send(
+            poise::CreateReply::default()
+                .embed(giveaway_embed(&giveaway))
+                .components(giveaway_buttons()),
+        )
+        .await?;
+    giveaway.message_id = msg.message().await?.id.to_string();
+
+    let mut giveaways = safe_lock(&ctx.data().giveaways);
+    giveaways.insert(giveaway.message_id.clone(), giveaway);
+    save_json(GIVEAWAYS_FILE, &*giveaways);
+    Ok(())
+}
+
+/// Завершити розіграш достроково
+#[poise::command(slash_command, rename = "end")]
+async fn giveaway_end(
+    ctx: Context<'_>,
+    #[description = "Посилання на розіграш або ID повідомлення"] message: String,
+) -> Result<(), Error> {
+    let key = message_id_from_input(&message);
+    let guild_id = ctx.guild_id().map(|g| g.to_string()).unwrap_or_default();
+    let active = safe_lock(&ctx.data().giveaways)
+        .get(&key)
+        .is_some_and(|g| g.guild_id == guild_id && !g.ended);
+    if !active {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Активний розіграш не знайдено.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    finish_giveaway(ctx.serenity_context(), ctx.data(), &key, false).await;
+    ctx.send(
+        poise::CreateReply::default()
+            .content("🏁 Розіграш завершено.")
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Переобрати переможців завершеного розіграшу
+#[poise::command(slash_command, rename = "reroll")]
+async fn giveaway_reroll(
+    ctx: Context<'_>,
+    #[description = "Посилання на розіграш або ID повідомлення"] message: String,
+) -> Result<(), Error> {
+    let key = message_id_from_input(&message);
+    let guild_id = ctx.guild_id().map(|g| g.to_string()).unwrap_or_default();
+    let ended = safe_lock(&ctx.data().giveaways)
+        .get(&key)
+        .is_some_and(|g| g.guild_id == guild_id && g.ended);
+    let text = if !ended {
+        "❌ Завершений розіграш не знайдено.".to_string()
+    } else {
+        match finish_giveaway(ctx.serenity_context(), ctx.data(), &key, true).await {
+            Some(w) if !w.is_empty() => "🎲 Переможців переобрано.".to_string(),
+            _ => "😔 Немає інших учасників для переобрання.".to_string(),
+        }
+    };
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// 🖼️ Показати аватар користувача
+#[poise::command(slash_command)]
+async fn avatar(ctx: Context<'_>, user: Option<serenity::User>) -> Result<(), Error> {
+    let u = user.as_ref().unwrap_or_else(|| ctx.author());
+    ctx.send(
+        poise::CreateReply::default().embed(
+            CreateEmbed::new()
+                .title(u.name.clone())
+                .image(u.face())
+                .colour(0x99AAB5),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+/// 📊 Переглянути профіль і статистику
+#[poise::command(slash_command)]
+async fn rank(ctx: Context<'_>, user: Option<serenity::User>) -> Result<(), Error> {
+    let target = user.as_ref().unwrap_or_else(|| ctx.author());
+    let grace = safe_lock(&ctx.data().economy_config).streak_grace_hours * 3600;
+    let (level, xp, minutes, chips, streak, best_streak, title) = {
+        let users = safe_lock(&ctx.data().users);
+        match users.get(&target.id.to_string()) {
+            Some(p) => (
+                p.level,
+                p.xp,
+                p.minutes,
+                p.chips,
+                current_daily_streak(p, Utc::now().timestamp(), grace),
+                p.best_streak,
+                p.title.clone(),
+            ),
+            None => (0, 0, 0, 100, 0, 0, None),
+        }
+    };
+    let boosts = guild_xp_boosts(ctx.data(), ctx.guild_id());
+    let member_roles: Vec<String> = match ctx.guild_id() {
+        Some(g) => g
+            .member(ctx, target.id)
+            .await
+            .map(|m| m.roles.iter().map(|r| r.to_string()).collect())
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+    let multiplier = {
+        let users = safe_lock(&ctx.data().users);
+        let default = create_default_profile();
+        let p = users.get(&target.id.to_string()).unwrap_or(&default);
+        xp_multiplier(p, boosts.as_ref(), &member_roles, Utc::now().timestamp())
+    };
+    let mut multiplier_text = format!("**{}**", format_multiplier(multiplier.total));
+    if !multiplier.sources.is_empty() {
+        let stacking = boosts.as_ref().map(|b| b.stacking).unwrap_or_default();
+        multiplier_text.push_str(&format!(" ({})", stacking.label()));
+        for (source, m) in &multiplier.sources {
+            multiplier_text.push_str(&format!("\n{} — {}", source, format_multiplier(*m)));
+        }
+    }
+    let needed = get_xp_needed(level);
+    let pct = ((xp as f64 / needed as f64) * 10.0) as usize;
+    let bar = format!(
+        "{}{}",
+        "🟩".repeat(pct.min(10)),
+        "⬜".repeat(10 - pct.min(10))
+    );
+    let role_name = get_role_for_level(level).unwrap_or("Немає");
+
+    ctx.send(
+        poise::CreateReply::default().embed(
+            CreateEmbed::new()
+                .title(match &title {
+                    Some(t) => format!("Картка {} • {}", target.name, t),
+                    None => format!("Картка {}", target.name),
+                })
+                .thumbnail(target.face())
+                .fields(vec![
+                    ("Звання", role_name, false),
+                    ("Рівень", &level.to_string(), true),
+                    ("Гривні", &format!("🪙 {}", chips), true),
+                    ("XP", &format!("{}/{}", xp, needed), true),
+                    (
+                        "В голосі",
+                        &format!("{} год {} хв", minutes / 60, minutes % 60),
+                        true,
+                    ),
+                    (
+                        "Серія",
+                        &format!("🔥 {} дн. (рекорд {})", streak, best_streak),
+                        true,
+                    ),
+                    ("Множник XP", &multiplier_text, false),
+                    ("Прогрес", &bar, false),
+                ])
+                .colour(0x006400),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RewardPeriod {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl RewardPeriod {
+    fn cooldown(self) -> i64 {
+        match self {
+            RewardPeriod::Daily => 86400,
+            RewardPeriod::Weekly => 7 * 86400,
+            RewardPeriod::Monthly => 30 * 86400,
+        }
+    }
+
+    fn reason(self) -> &'static str {
+        match self {
+            RewardPeriod::Daily => "daily",
+            RewardPeriod::Weekly => "weekly",
+            RewardPeriod::Monthly => "monthly",
+        }
+    }
+}
+
+/// Streak after a daily claim at `now`: it continues if the claim comes
+/// within a day plus the grace window of the previous one, else restarts.
+fn next_daily_streak(streak: u32, last_daily: i64, now: i64, grace_secs: i64) -> u32 {
+    if last_daily == 0 || now - last_daily > 86400 + grace_secs {
+        1
+    } else {
+        streak.saturating_add(1)
+    }
+}
+
+/// Streak as shown on `/rank`: zero once the grace window has passed.
+fn current_daily_streak(profile: &UserProfile, now: i64, grace_secs: i64) -> u32 {
+    if now - profile.last_daily > 86400 + grace_secs {
+        0
+    } else {
+        profile.daily_streak
+    }
+}
+
+/// Streak bonus in percent: every day after the first adds
+/// `streak_bonus_percent`, up to `streak_max_days` days.
+fn streak_bonus_percent(config: &EconomyConfig, streak: u32) -> f64 {
+    streak.saturating_sub(1).min(config.streak_max_days) as f64 * config.streak_bonus_percent
+}
+
+/// Reward multiplier for server boosters and high levels (every 10 levels
+/// add `level_bonus_percent`).
+fn reward_multiplier(config: &EconomyConfig, level: u64, server_booster: bool) -> f64 {
+    let mut multiplier = 1.0 + (level / 10) as f64 * config.level_bonus_percent / 100.0;
+    if server_booster {
+        multiplier *= config.booster_reward_multiplier.max(1.0);
+    }
+    multiplier
+}
+
+/// Result of a successful reward claim.
+struct RewardClaim {
+    amount: u64,
+    streak: u32,
+    streak_bonus: f64,
+    multiplier: f64,
+}
+
+/// Claims a periodic reward under the users lock. Returns the seconds left
+/// on the cooldown if it is too early.
+fn claim_reward(
+    data: &Data,
+    user_id: &str,
+    period: RewardPeriod,
+    server_booster: bool,
+) -> Result<RewardClaim, i64> {
+    let config = safe_lock(&data.economy_config).clone();
+    let now = Utc::now().timestamp();
+    let mut users = safe_lock(&data.users);
+    let profile = users
+        .entry(user_id.to_string())
+        .or_insert(create_default_profile());
+
+    let last = match period {
+        RewardPeriod::Daily => profile.last_daily,
+        RewardPeriod::Weekly => profile.last_weekly,
+        RewardPeriod::Monthly => profile.last_monthly,
+    };
+    if now - last < period.cooldown() {
+        return Err(period.cooldown() - (now - last));
+    }
+
+    let (base, streak) = match period {
+        RewardPeriod::Daily => {
+            let max = config.daily_max.max(config.daily_min);
+            let streak = next_daily_streak(
+                profile.daily_streak,
+                profile.last_daily,
+                now,
+                config.streak_grace_hours * 3600,
+            );
+            (rand::thread_rng().gen_range(config.daily_min..=max), streak)
+        }
+        RewardPeriod::Weekly => (config.weekly_reward, profile.daily_streak),
+        RewardPeriod::Monthly => (config.monthly_reward, profile.daily_streak),
+    };
+    let streak_bonus = if period == RewardPeriod::Daily {
+        streak_bonus_percent(&config, streak)
+    } else {
+        0.0
+    };
+    let multiplier = reward_multiplier(&config, profile.level, server_booster);
+    let amount = (base as f64 * (1.0 + streak_bonus / 100.0) * multiplier).round() as u64;
+
+    match period {
+        RewardPeriod::Daily => {
+            profile.last_daily = now;
+            profile.daily_streak = streak;
+            profile.best_streak = profile.best_streak.max(streak);
+        }
+        RewardPeriod::Weekly => profile.last_weekly = now,
+        RewardPeriod::Monthly => profile.last_monthly = now,
+    }
+    profile.chips = profile.chips.saturating_add(amount);
+    log_transactions(data, &[(user_id, amount as i64)], period.reason(), None);
+    save_json(USERS_FILE, &*users);
+    Ok(RewardClaim {
+        amount,
+        streak,
+        streak_bonus,
+        multiplier,
+    })
+}
+
+/// Shared body of `/daily`, `/weekly` and `/monthly`.
+async fn reward_command(ctx: Context<'_>, period: RewardPeriod) -> Result<(), Error> {
+    let server_booster = match ctx.author_member().await {
+        Some(member) => member.premium_since.is_some(),
+        None => false,
+    };
+
+    match claim_reward(
+        ctx.data(),
+        &ctx.author().id.to_string(),
+        period,
+        server_booster,
+    ) {
+        Err(wait) => {
+            let time = if wait >= 86400 {
+                format!("{} дн {} год", wait / 86400, (wait % 86400) / 3600)
+            } else {
+                format!("{} год {} хв", wait / 3600, (wait % 3600) / 60)
+            };
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!("⏳ Чекай **{}**.", time))
+                    .ephemeral(true),
+            )
+            .await?;
+        }
+        Ok(claim) => {
+            let mut text = format!("🎁 Ти отримав **{} гривень** 💰!", claim.amount);
+            if period == RewardPeriod::Daily {
+                text.push_str(&format!("\n🔥 Серія: **{} дн.**", claim.streak));
+                if claim.streak_bonus > 0.0 {
+                    text.push_str(&format!(" (+{}%)", claim.streak_bonus));
+                }
+            }
+            if claim.multiplier > 1.0 {
+                text.push_str(&format!("\n✨ Множник: x{:.2}", claim.multiplier));
+            }
+            ctx.say(text).await?;
+        }
+    }
+    Ok(())
+}
+
+/// 🎁 Отримати щоденну винагороду
+#[poise::command(slash_command)]
+async fn daily(ctx: Context<'_>) -> Result<(), Error> {
+    reward_command(ctx, RewardPeriod::Daily).await
+}
+
+/// 📅 Отримати щотижневу винагороду
+#[poise::command(slash_command)]
+async fn weekly(ctx: Context<'_>) -> Result<(), Error> {
+    reward_command(ctx, RewardPeriod::Weekly).await
+}
+
+/// 🗓️ Отримати щомісячну винагороду
+#[poise::command(slash_command)]
+async fn monthly(ctx: Context<'_>) -> Result<(), Error> {
+    reward_command(ctx, RewardPeriod::Monthly).await
+}
+
+/// 🎰 Спробуй удачу в казино
+#[poise::command(slash_command)]
+async fn casino(ctx: Context<'_>, amount: u64) -> Result<(), Error> {
+    let user_id = ctx.author().id.to_string();
+    let config = safe_lock(&ctx.data().casino_config).clone();
+
+    let gross = if rand::thread_rng().gen_bool(0.5) {
+        games::apply_edge(amount, amount.saturating_mul(2), config.house_edge)
+    } else {
+        0
+    };
+    let played = check_bet(&config, amount)
+        .and_then(|_| play_house_game(ctx.data(), &user_id, amount, gross, "casino"));
+
+    match played {
+        Err(text) => {
+            ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+                .await?;
+        }
+        Ok(balance) => {
+            let msg = if gross > amount {
+                format!("🎰 Виграв **{} гривень**! 🤑", gross - amount)
+            } else {
+                format!("🎰 Програв **{} гривень**. 📉", amount)
+            };
+            ctx.say(format!("{}\n💰 Баланс: {}", msg, balance)).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Appends wallet movements to the ledger. Every change of
+/// `UserProfile.chips` goes through here, called while the users lock is
+/// held, so the ledger lock is always taken after it.
+fn log_transactions(data: &Data, entries: &[(&str, i64)], reason: &str, reference: Option<&str>) {
+    append_ledger(data, entries, reason, reference, false);
+}
+
+/// Same as `log_transactions`, for bank balances.
+fn log_bank_transactions(data: &Data, entries: &[(&str, i64)], reason: &str) {
+    append_ledger(data, entries, reason, None, true);
+}
+
+fn append_ledger(
+    data: &Data,
+    entries: &[(&str, i64)],
+    reason: &str,
+    reference: Option<&str>,
+    bank: bool,
+) {
+    let at = Utc::now().timestamp();
+    let added: Vec<Transaction> = entries
+        .iter()
+        .filter(|(_, d)| *d != 0)
+        .map(|(user_id, delta)| Transaction {
+            user_id: user_id.to_string(),
+            delta: *delta,
+            reason: reason.to_string(),
+            reference: reference.map(str::to_string),
+            at,
+            bank,
+        })
+        .collect();
+    if added.is_empty() {
+        return;
+    }
+    let mut log = safe_lock(&data.transactions);
+    write_ledger_lines(TRANSACTIONS_FILE, &added);
+    log.extend(added);
+}
+
+/// Appends entries to the ledger file, one JSON object per line, so a
+/// chip change never rewrites the history.
+fn write_ledger_lines(path: &str, entries: &[Transaction]) {
+    let mut lines = String::new();
+    for t in entries {
+        match serde_json::to_string(t) {
+            Ok(line) => {
+                lines.push_str(&line);
+                lines.push('\n');
+            }
+            Err(e) => error!("❌ Помилка серіалізації запису журналу: {}", e),
+        }
+    }
+    let written = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| f.write_all(lines.as_bytes()));
+    if let Err(e) = written {
+        error!("❌ Не вдалося дописати журнал {}: {}", path, e);
+    }
+}
+
+/// Parses the ledger file. A line that fails to parse (e.g. cut short by a
+/// crash mid-write) is skipped with a warning.
+fn parse_ledger_lines(text: &str) -> Vec<Transaction> {
+    text.lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| match serde_json::from_str(l) {
+            Ok(t) => Some(t),
+            Err(e) => {
+                warn!("⚠️ Пропущено пошкоджений запис журналу: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Loads the ledger, converting the old single-array file on first start.
+fn load_ledger() -> Vec<Transaction> {
+    match fs::read_to_string(TRANSACTIONS_FILE) {
+        Ok(text) => {
+            info!("✅ Завантажено файл: {}", TRANSACTIONS_FILE);
+            parse_ledger_lines(&text)
+        }
+        Err(_) => {
+            let legacy = load_json::<Vec<Transaction>>(LEGACY_TRANSACTIONS_FILE);
+            if !legacy.is_empty() {
+                write_ledger_lines(TRANSACTIONS_FILE, &legacy);
+                info!(
+                    "📒 Журнал перенесено з {} у {} ({} записів)",
+                    LEGACY_TRANSACTIONS_FILE,
+                    TRANSACTIONS_FILE,
+                    legacy.len()
+                );
+            }
+            legacy
+        }
+    }
+}
+
+/// Per-user wallet (or bank) balance implied by the ledger. Every wallet
+/// starts with the default chips and every bank at zero; `opening_balance`
+/// entries carry pre-ledger history.
+fn ledger_balances(log: &[Transaction], bank: bool) -> HashMap<String, i64> {
+    let start = if bank { 0 } else { default_chips() as i64 };
+    let mut balances = HashMap::new();
+    for t in log.iter().filter(|t| t.bank == bank) {
+        *balances.entry(t.user_id.clone()).or_insert(start) += t.delta;
+    }
+    balances
+}
+
+/// Balance that differs from the ledger: (user_id, account, actual, ledger).
+type LedgerDrift = (String, &'static str, u64, i64);
+
+/// Wallets and banks that differ from the ledger.
+fn ledger_mismatches(
+    users: &HashMap<String, UserProfile>,
+    log: &[Transaction],
+) -> Vec<LedgerDrift> {
+    let wallets = ledger_balances(log, false);
+    let banks = ledger_balances(log, true);
+    let mut drift: Vec<LedgerDrift> = Vec::new();
+    for (id, p) in users {
+        let wallet = wallets.get(id).copied().unwrap_or(default_chips() as i64);
+        if wallet != p.chips as i64 {
+            drift.push((id.clone(), "гаманець", p.chips, wallet));
+        }
+        let bank = banks.get(id).copied().unwrap_or(0);
+        if bank != p.bank as i64 {
+            drift.push((id.clone(), "банк", p.bank, bank));
+        }
+    }
+    drift.sort();
+    drift
+}
+
+/// Starts the ledger once: every existing profile gets an `opening_balance`
+/// entry that carries the history from before the ledger. `opened_at` is
+/// the persisted marker (`EconomyConfig.ledger_opened_at`), so later
+/// restarts never absorb drift into new opening entries, even if no profile
+/// existed on the first start. Returns the number of entries added.
+fn open_ledger(
+    users: &HashMap<String, UserProfile>,
+    log: &mut Vec<Transaction>,
+    opened_at: &mut i64,
+    now: i64,
+) -> usize {
+    if *opened_at != 0 {
+        return 0;
+    }
+    *opened_at = now;
+    // Журнал, відкритий до появи позначки
+    if log.iter().any(|t| t.reason == "opening_balance") {
+        return 0;
+    }
+    let balances = ledger_balances(log, false);
+    let at = now;
+    let before = log.len();
+    for (id, profile) in users {
+        let expected = balances.get(id).copied().unwrap_or(default_chips() as i64);
+        log.push(Transaction {
+            user_id: id.clone(),
+            delta: profile.chips as i64 - expected,
+            reason: "opening_balance".to_string(),
+            reference: None,
+            at,
+            bank: false,
+        });
+    }
+    log.len() - before
+}
+
+fn ledger_reason_label(reason: &str) -> &str {
+    match reason {
+        "opening_balance" => "Початковий баланс",
+        "daily" => "Щоденна нагорода",
+        "weekly" => "Тижнева нагорода",
+        "monthly" => "Місячна нагорода",
+        "admin" => "Зміна адміністратором",
+        "booster" => "Бустер XP",
+        "shop" => "Магазин",
+        "giveaway_fee" => "Внесок у розіграш",
+        "giveaway_refund" => "Повернення внеску",
+        "casino" => "Казино",
+        "blackjack" => "Блекджек",
+        "roulette" => "Рулетка",
+        "slots" => "Слоти",
+        "dice" => "Кубики",
+        "duel" => "Дуель",
+        "duel_refund" => "Повернення ставки дуелі",
+        "pay" => "Переказ",
+        "bank_deposit" => "Внесок у банк",
+        "bank_withdraw" => "Зняття з банку",
+        "bank_interest" => "Відсотки банку",
+        other => other,
+    }
+}
+
+fn ledger_line(t: &Transaction) -> String {
+    let reference = match (t.reason.as_str(), &t.reference) {
+        ("duel" | "admin" | "pay", Some(r)) => format!(" • <@{}>", r),
+        (_, Some(r)) => format!(" • `{}`", r),
+        _ => String::new(),
+    };
+    format!(
+        "`{:+}` {}{}{} • <t:{}:R>",
+        t.delta,
+        if t.bank { "🏦 " } else { "" },
+        ledger_reason_label(&t.reason),
+        reference,
+        t.at
+    )
+}
+
+/// A page of a user's ledger, newest first, with the total page count.
+fn ledger_page(log: &[Transaction], user_id: &str, page: usize) -> (Vec<String>, usize) {
+    let entries: Vec<&Transaction> = log.iter().rev().filter(|t| t.user_id == user_id).collect();
+    let pages = entries.len().div_ceil(LEDGER_PAGE_SIZE).max(1);
+    let lines = entries
+        .iter()
+        .skip(page.min(pages - 1) * LEDGER_PAGE_SIZE)
+        .take(LEDGER_PAGE_SIZE)
+        .map(|t| ledger_line(t))
+        .collect();
+    (lines, pages)
+}
+
+fn ledger_embed(title: String, lines: Vec<String>, page: usize, pages: usize) -> CreateEmbed {
+    CreateEmbed::new()
+        .title(title)
+        .colour(0xF1C40F)
+        .description(if lines.is_empty() {
+            "📭 Записів ще немає.".to_string()
+        } else {
+            lines.join("\n")
+        })
+        .footer(CreateEmbedFooter::new(format!(
+            "Сторінка {}/{}",
+            page.min(pages - 1) + 1,
+            pages
+        )))
+}
+
+/// 💰 Гаманець та історія гривень
+#[poise::command(slash_command, subcommands("balance_show", "balance_history"))]
+async fn balance(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Показати свій баланс
+#[poise::command(slash_command, rename = "show")]
+async fn balance_show(ctx: Context<'_>) -> Result<(), Error> {
+    let (chips, bank) = safe_lock(&ctx.data().users)
+        .get(&ctx.author().id.to_string())
+        .map_or((default_chips(), 0), |p| (p.chips, p.bank));
+    let (percent, cap) = {
+        let config = safe_lock(&ctx.data().economy_config);
+        (config.bank_interest_percent, config.bank_interest_cap)
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!(
+                "💰 У гаманці: **{} гривень**\n🏦 У банку: **{} гривень** (+{} на добу, {}% до {})",
+                chips,
+                bank,
+                bank_interest(bank, percent, cap),
+                percent,
+                cap
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Історія змін твого балансу
+#[poise::command(slash_command, rename = "history")]
+async fn balance_history(
+    ctx: Context<'_>,
+    #[description = "Сторінка"]
+    #[min = 1]
+    page: Option<usize>,
+) -> Result<(), Error> {
+    let page = page.unwrap_or(1).saturating_sub(1);
+    let (lines, pages) = ledger_page(
+        &safe_lock(&ctx.data().transactions),
+        &ctx.author().id.to_string(),
+        page,
+    );
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(ledger_embed(
+                "📜 Історія гривень".to_string(),
+                lines,
+                page,
+                pages,
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// [ADMIN] Економіка сервера
+#[poise::command(
+    slash_command,
+    subcommands(
+        "economy_audit",
+        "economy_check",
+        "economy_transfers",
+        "economy_flags",
+        "economy_stats_cmd",
+        "economy_config",
+        "economy_rewards"
+    ),
+    default_member_permissions = "ADMINISTRATOR"
+)]
+async fn economy(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// [ADMIN] Журнал гривень користувача
+#[poise::command(
+    slash_command,
+    rename = "audit",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn economy_audit(
+    ctx: Context<'_>,
+    #[description = "Користувач"] user: serenity::User,
+    #[description = "Сторінка"]
+    #[min = 1]
+    page: Option<usize>,
+) -> Result<(), Error> {
+    let user_id = user.id.to_string();
+    let page = page.unwrap_or(1).saturating_sub(1);
+    // Користувачі перед журналом — той самий порядок локів, що й при записі
+    let (chips, bank, drift, lines, pages) = {
+        let users = safe_lock(&ctx.data().users);
+        let log = safe_lock(&ctx.data().transactions);
+        let (chips, bank) = users
+            .get(&user_id)
+            .map_or((default_chips(), 0), |p| (p.chips, p.bank));
+        let drift: Vec<LedgerDrift> = ledger_mismatches(&users, &log)
+            .into_iter()
+            .filter(|d| d.0 == user_id)
+            .collect();
+        let (lines, pages) = ledger_page(&log, &user_id, page);
+        (chips, bank, drift, lines, pages)
+    };
+
+    let status = if drift.is_empty() {
+        "✅ Баланс збігається з журналом".to_string()
+    } else {
+        drift
+            .iter()
+            .map(|(_, account, actual, expected)| {
+                format!(
+                    "⚠️ {}: у журналі {}, різниця {:+}",
+                    account,
+                    expected,
+                    *actual as i64 - expected
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let embed = ledger_embed(format!("🔎 Аудит: {}", user.name), lines, page, pages)
+        .field("Гаманець", chips.to_string(), true)
+        .field("Банк", bank.to_string(), true)
+        .field("Перевірка", status, false);
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// [ADMIN] Звірити всі баланси з журналом
+#[poise::command(
+    slash_command,
+    rename = "check",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn economy_check(ctx: Context<'_>) -> Result<(), Error> {
+    let (checked, drift) = {
+        let users = safe_lock(&ctx.data().users);
+        let log = safe_lock(&ctx.data().transactions);
+        (users.len(), ledger_mismatches(&users, &log))
+    };
+
+    let text = if drift.is_empty() {
+        format!("✅ Усі {} балансів збігаються з журналом.", checked)
+    } else {
+        let lines: Vec<String> = drift
+            .iter()
+            .take(20)
+            .map(|(id, account, actual, expected)| {
+                format!(
+                    "<@{}> ({}): {} (у журналі {}, різниця {:+})",
+                    id,
+                    account,
+                    actual,
+                    expected,
+                    *actual as i64 - expected
+                )
+            })
+            .collect();
+        format!(
+            "⚠️ Розбіжностей: **{}** з {}\n{}",
+            drift.len(),
+            checked,
+            lines.join("\n")
+        )
+    };
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// [ADMIN] Правила переказів /pay
+#[poise::command(
+    slash_command,
+    rename = "transfers",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn economy_transfers(
+    ctx: Context<'_>,
+    #[description = "Комісія у відсотках (0-50)"]
+    #[min = 0.0]
+    #[max = 50.0]
+    fee_percent: Option<f64>,
+    #[description = "Денний ліміт переказів (0 — без ліміту)"] daily_cap: Option<u64>,
+    #[description = "Підтвердження від суми (0 — вимкнено)"] confirm_above: Option<u64>,
+    #[description = "Мінімальний рівень відправника"] min_level: Option<u64>,
+    #[description = "Мінімальний вік акаунта в днях"]
+    #[min = 0]
+    min_account_days: Option<i64>,
+) -> Result<(), Error> {
+    let config = {
+        let mut config = safe_lock(&ctx.data().economy_config);
+        if let Some(v) = fee_percent {
+            config.pay_fee_percent = v.clamp(0.0, 50.0);
+        }
+        if let Some(v) = daily_cap {
+            config.pay_daily_cap = v;
+        }
+        if let Some(v) = confirm_above {
+            config.pay_confirm_above = v;
+        }
+        if let Some(v) = min_level {
+            config.pay_min_level = v;
+        }
+        if let Some(v) = min_account_days {
+            config.pay_min_account_days = v.max(0);
+        }
+        save_json(ECONOMY_CONFIG_FILE, &*config);
+        config.clone()
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!(
+                "💸 Перекази: комісія {}%, ліміт {} на день, підтвердження від {}, рівень від {}, акаунт від {} дн.",
+                config.pay_fee_percent,
+                config.pay_daily_cap,
+                config.pay_confirm_above,
+                config.pay_min_level,
+                config.pay_min_account_days
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// [ADMIN] Підозрілі кругові перекази
+#[poise::command(
+    slash_command,
+    rename = "flags",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn economy_flags(ctx: Context<'_>) -> Result<(), Error> {
+    let lines: Vec<String> = safe_lock(&ctx.data().transfer_flags)
+        .iter()
+        .rev()
+        .take(15)
+        .map(|f| {
+            let chain: Vec<String> = f.chain.iter().map(|u| format!("<@{}>", u)).collect();
+            format!(
+                "<t:{}:R> • {} гривень: {}",
+                f.at,
+                f.amount,
+                chain.join(" → ")
+            )
+        })
+        .collect();
+    let text = if lines.is_empty() {
+        "✅ Підозрілих переказів немає.".to_string()
+    } else {
+        format!("🚩 **Кругові перекази:**\n{}", lines.join("\n"))
+    };
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Daily interest on a bank balance; only the part up to `cap` earns.
+fn bank_interest(balance: u64, percent: f64, cap: u64) -> u64 {
+    (balance.min(cap) as f64 * percent.max(0.0) / 100.0).floor() as u64
+}
+
+/// Adds interest for every full day since the last accrual to one
+/// profile, compounding daily. Returns the amount earned.
+fn accrue_profile_interest(profile: &mut UserProfile, config: &EconomyConfig, now: i64) -> u64 {
+    if profile.bank == 0 || profile.bank_interest_at == 0 {
+        return 0;
+    }
+    let days = (now - profile.bank_interest_at) / 86400;
+    if days <= 0 {
+        return 0;
+    }
+    let mut earned = 0u64;
+    for _ in 0..days {
+        let interest = bank_interest(
+            profile.bank + earned,
+            config.bank_interest_percent,
+            config.bank_interest_cap,
+        );
+        earned = earned.saturating_add(interest);
+    }
+    profile.bank_interest_at += days * 86400;
+    profile.bank = profile.bank.saturating_add(earned);
+    earned
+}
+
+/// Moves chips between the wallet and the bank of one profile. Interest owed
+/// for finished days must be settled first (`accrue_profile_interest`). A
+/// deposit restarts the interest clock, so new chips never earn for time
+/// they were not banked; emptying the bank stops it. Returns the available
+/// amount on failure.
+fn apply_bank_move(
+    profile: &mut UserProfile,
+    amount: u64,
+    deposit: bool,
+    now: i64,
+) -> Result<(), u64> {
+    let available = if deposit { profile.chips } else { profile.bank };
+    if amount == 0 || available < amount {
+        return Err(available);
+    }
+    if deposit {
+        profile.bank_interest_at = now;
+        profile.chips -= amount;
+        profile.bank += amount;
+    } else {
+        profile.bank -= amount;
+        profile.chips += amount;
+        if profile.bank == 0 {
+            profile.bank_interest_at = 0;
+        }
+    }
+    Ok(())
+}
+
+/// Pays interest for every full day since the last accrual, compounding
+/// daily. Runs hourly from `background_tasks`.
+fn accrue_bank_interest(data: &Data) {
+    let config = safe_lock(&data.economy_config).clone();
+    let now = Utc::now().timestamp();
+    let mut users = safe_lock(&data.users);
+    let mut changed = false;
+    for (id, profile) in users.iter_mut() {
+        let earned = accrue_profile_interest(profile, &config, now);
+        if earned == 0 {
+            continue;
+        }
+        log_bank_transactions(data, &[(id, earned as i64)], "bank_interest");
+        changed = true;
+    }
+    if changed {
+        save_json(USERS_FILE, &*users);
+    }
+}
+
+/// 🏦 Банк: відсотки на збереження
+#[poise::command(slash_command, subcommands("bank_deposit", "bank_withdraw"))]
+async fn bank(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Покласти гривні з гаманця в банк
+#[poise::command(slash_command, rename = "deposit")]
+async fn bank_deposit(
+    ctx: Context<'_>,
+    #[description = "Сума"]
+    #[min = 1]
+    amount: u64,
+) -> Result<(), Error> {
+    let text = move_bank_funds(ctx.data(), &ctx.author().id.to_string(), amount, true);
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Зняти гривні з банку в гаманець
+#[poise::command(slash_command, rename = "withdraw")]
+async fn bank_withdraw(
+    ctx: Context<'_>,
+    #[description = "Сума"]
+    #[min = 1]
+    amount: u64,
+) -> Result<(), Error> {
+    let text = move_bank_funds(ctx.data(), &ctx.author().id.to_string(), amount, false);
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Moves chips between the wallet and the bank under the users lock.
+fn move_bank_funds(data: &Data, user_id: &str, amount: u64, deposit: bool) -> String {
+    let config = safe_lock(&data.economy_config).clone();
+    let now = Utc::now().timestamp();
+    let mut users = safe_lock(&data.users);
+    let profile = users
+        .entry(user_id.to_string())
+        .or_insert(create_default_profile());
+    // Спершу виплачуємо відсотки за вже завершені дні на старий баланс
+    let earned = accrue_profile_interest(profile, &config, now);
+    if earned > 0 {
+        log_bank_transactions(data, &[(user_id, earned as i64)], "bank_interest");
+    }
+    if let Err(available) = apply_bank_move(profile, amount, deposit, now) {
+        if earned > 0 {
+            save_json(USERS_FILE, &*users);
+        }
+        return format!("❌ Недостатньо гривень (доступно {}).", available);
+    }
+
+    let delta = amount as i64;
+    if deposit {
+        log_transactions(data, &[(user_id, -delta)], "bank_deposit", None);
+        log_bank_transactions(data, &[(user_id, delta)], "bank_deposit");
+    } else {
+        log_bank_transactions(data, &[(user_id, -delta)], "bank_withdraw");
+        log_transactions(data, &[(user_id, delta)], "bank_withdraw", None);
+    }
+    let text = format!(
+        "{} **{} гривень**.\n💰 Гаманець: {} • 🏦 Банк: {}",
+        if deposit {
+            "🏦 Покладено в банк"
+        } else {
+            "💰 Знято з банку"
+        },
+        amount,
+        profile.chips,
+        profile.bank
+    );
+    save_json(USERS_FILE, &*users);
+    text
+}
+
+/// Snapshot of the economy for balancing rewards and prices.
+struct EconomyStats {
+    holders: usize,
+    wallets: u64,
+    banks: u64,
+    median: u64,
+    top: Vec<(String, u64)>,
+    // Скільки гривень за добу з'явилось (+) чи згоріло (-), за причиною
+    flows: Vec<(String, i64)>,
+}
+
+fn economy_stats(
+    users: &HashMap<String, UserProfile>,
+    log: &[Transaction],
+    since: i64,
+) -> EconomyStats {
+    let mut wealth: Vec<(String, u64)> = users
+        .iter()
+        .map(|(id, p)| (id.clone(), p.chips.saturating_add(p.bank)))
+        .collect();
+    wealth.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let median = match wealth.len() {
+        0 => 0,
+        n if n % 2 == 1 => wealth[n / 2].1,
+        n => (wealth[n / 2 - 1].1 + wealth[n / 2].1) / 2,
+    };
+
+    // Перекази між гаманцем і банком нічого не створюють і не спалюють
+    let mut flows: HashMap<String, i64> = HashMap::new();
+    for t in log
+        .iter()
+        .filter(|t| t.at >= since && t.reason != "bank_deposit" && t.reason != "bank_withdraw")
+    {
+        *flows.entry(t.reason.clone()).or_insert(0) += t.delta;
+    }
+    let mut flows: Vec<(String, i64)> = flows.into_iter().filter(|(_, d)| *d != 0).collect();
+    flows.sort_by(|a, b| b.1.abs().cmp(&a.1.abs()).then_with(|| a.0.cmp(&b.0)));
+
+    EconomyStats {
+        holders: users.len(),
+        wallets: users.values().map(|p| p.chips).sum(),
+        banks: users.values().map(|p| p.bank).sum(),
+        median,
+        top: wealth.into_iter().take(5).collect(),
+        flows,
+    }
+}
+
+fn economy_stats_embed(stats: &EconomyStats) -> CreateEmbed {
+    let top = stats
+        .top
+        .iter()
+        .enumerate()
+        .map(|(i, (id, wealth))| format!("{}. <@{}> — {}", i + 1, id, wealth))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let flows = stats
+        .flows
+        .iter()
+        .map(|(reason, delta)| format!("`{:+}` {}", delta, ledger_reason_label(reason)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    CreateEmbed::new()
+        .title("📈 Економіка сервера")
+        .colour(0x1ABC9C)
+        .field(
+            "Загальна маса",
+            format!(
+                "{} гривень\n💰 гаманці: {}\n🏦 банк: {}",
+                stats.wallets + stats.banks,
+                stats.wallets,
+                stats.banks
+            ),
+            true,
+        )
+        .field(
+            "Гравців",
+            format!("{}\nМедіана: {}", stats.holders, stats.median),
+            true,
+        )
+        .field(
+            "🏆 Найбагатші",
+            if top.is_empty() {
+                "—".to_string()
+            } else {
+                top
+            },
+            false,
+        )
+        .field(
+            "За добу",
+            if flows.is_empty() {
+                "—".to_string()
+            } else {
+                flows
+            },
+            false,
+        )
+}
+
+/// Posts the daily economy report to the configured channel. Runs hourly
+/// from `background_tasks`.
+async fn post_economy_stats(ctx: &serenity::Context, data: &Data) {
+    let now = Utc::now().timestamp();
+    let channel = {
+        let config = safe_lock(&data.economy_config);
+        if now - config.stats_posted_at < 86400 {
+            return;
+        }
+        config.stats_channel.clone()
+    };
+    let Some(channel) = channel.and_then(|c| c.parse::<u64>().ok()) else {
+        return;
+    };
+
+    let stats = {
+        let users = safe_lock(&data.users);
+        let log = safe_lock(&data.transactions);
+        economy_stats(&users, &log, now - 86400)
+    };
+    if ChannelId::new(channel)
+        .send_message(
+            &ctx.http,
+            CreateMessage::new().embed(economy_stats_embed(&stats)),
+        )
+        .await
+        .is_ok()
+    {
+        let mut config = safe_lock(&data.economy_config);
+        config.stats_posted_at = now;
+        save_json(ECONOMY_CONFIG_FILE, &*config);
+    }
+}
+
+/// [ADMIN] Статистика економіки
+#[poise::command(
+    slash_command,
+    rename = "stats",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn economy_stats_cmd(ctx: Context<'_>) -> Result<(), Error> {
+    let stats = {
+        let users = safe_lock(&ctx.data().users);
+        let log = safe_lock(&ctx.data().transactions);
+        economy_stats(&users, &log, Utc::now().timestamp() - 86400)
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(economy_stats_embed(&stats))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// [ADMIN] Глобальні параметри економіки
+#[poise::command(
+    slash_command,
+    rename = "config",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn economy_config(
+    ctx: Context<'_>,
+    #[description = "Мінімум щоденної нагороди"] daily_min: Option<u64>,
+    #[description = "Максимум щоденної нагороди"] daily_max: Option<u64>,
+    #[description = "Відсоток банку на добу (0-10)"]
+    #[min = 0.0]
+    #[max = 10.0]
+    interest_percent: Option<f64>,
+    #[description = "Сума в банку, з якої нараховуються відсотки"] interest_cap: Option<u64>,
+    #[description = "Канал для щоденного звіту"] stats_channel: Option<serenity::ChannelId>,
+    #[description = "Вимкнути щоденний звіт"] disable_stats: Option<bool>,
+) -> Result<(), Error> {
+    let result = {
+        let mut config = safe_lock(&ctx.data().economy_config);
+        let min = daily_min.unwrap_or(config.daily_min);
+        let max = daily_max.unwrap_or(config.daily_max);
+        if min > max {
+            Err("❌ Мінімум нагороди не може бути більшим за максимум.".to_string())
+        } else {
+            config.daily_min = min;
+            config.daily_max = max;
+            if let Some(v) = interest_percent {
+                config.bank_interest_percent = v.clamp(0.0, 10.0);
+            }
+            if let Some(v) = interest_cap {
+                config.bank_interest_cap = v;
+            }
+            if let Some(c) = stats_channel {
+                config.stats_channel = Some(c.to_string());
+            }
+            if disable_stats == Some(true) {
+                config.stats_channel = None;
+            }
+            save_json(ECONOMY_CONFIG_FILE, &*config);
+            Ok(config.clone())
+        }
+    };
+
+    let text = match result {
+        Err(e) => e,
+        Ok(c) => format!(
+            "⚙️ Щоденна нагорода: {}-{} • Банк: {}% на добу до {} гривень • Звіт: {}",
+            c.daily_min,
+            c.daily_max,
+            c.bank_interest_percent,
+            c.bank_interest_cap,
+            c.stats_channel
+                .map_or("вимкнено".to_string(), |id| format!("<#{}>", id))
+        ),
+    };
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// [ADMIN] Нагороди: серії, тижневі й місячні, множники
+#[poise::command(
+    slash_command,
+    rename = "rewards",
+    required_permissions = "ADMINISTRATOR"
+)]
+#[allow(clippy::too_many_arguments)]
+async fn economy_rewards(
+    ctx: Context<'_>,
+    #[description = "Тижнева нагорода"] weekly: Option<u64>,
+    #[description = "Місячна нагорода"] monthly: Option<u64>,
+    #[description = "Бонус серії за кожен день, %"]
+    #[min = 0.0]
+    streak_percent: Option<f64>,
+    #[description = "Максимум днів серії з бонусом"] streak_max_days: Option<u32>,
+    #[description = "Запас часу для серії, годин"]
+    #[min = 0]
+    #[max = 72]
+    grace_hours: Option<i64>,
+    #[description = "Множник для бустерів сервера"]
+    #[min = 1.0]
+    #[max = 5.0]
+    booster_multiplier: Option<f64>,
+    #[description = "Бонус за кожні 10 рівнів, %"]
+    #[min = 0.0]
+    level_bonus: Option<f64>,
+) -> Result<(), Error> {
+    let c = {
+        let mut config = safe_lock(&ctx.data().economy_config);
+        if let Some(v) = weekly {
+            config.weekly_reward = v;
+        }
+        if let Some(v) = monthly {
+            config.monthly_reward = v;
+        }
+        if let Some(v) = streak_percent {
+            config.streak_bonus_percent = v.max(0.0);
+        }
+        if let Some(v) = streak_max_days {
+            config.streak_max_days = v;
+        }
+        if let Some(v) = grace_hours {
+            config.streak_grace_hours = v.clamp(0, 72);
+        }
+        if let Some(v) = booster_multiplier {
+            config.booster_reward_multiplier = v.clamp(1.0, 5.0);
+        }
+        if let Some(v) = level_bonus {
+            config.level_bonus_percent = v.max(0.0);
+        }
+        save_json(ECONOMY_CONFIG_FILE, &*config);
+        config.clone()
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!(
+                "🎁 Тиждень: {} • Місяць: {} • Серія: +{}% на день до {} дн., запас {} год • Бустери: x{} • +{}% за 10 рівнів",
+                c.weekly_reward,
+                c.monthly_reward,
+                c.streak_bonus_percent,
+                c.streak_max_days,
+                c.streak_grace_hours,
+                c.booster_reward_multiplier,
+                c.level_bonus_percent
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+fn transfer_fee(amount: u64, percent: f64) -> u64 {
+    (amount as f64 * percent.clamp(0.0, 100.0) / 100.0).ceil() as u64
+}
+
+/// Chips the user has sent with `/pay` since `since`.
+fn pay_sent_since(log: &[Transaction], user_id: &str, since: i64) -> u64 {
+    log.iter()
+        .filter(|t| t.reason == "pay" && t.user_id == user_id && t.delta < 0 && t.at >= since)
+        .map(|t| t.delta.unsigned_abs())
+        .sum()
+}
+
+/// Looks for a chain of recent transfers leading from `to` back to `from`,
+/// which together with `from` → `to` closes a loop (alt-account farming).
+/// Returns the loop as user ids, starting and ending with `from`.
+fn transfer_cycle(log: &[Transaction], from: &str, to: &str, since: i64) -> Option<Vec<String>> {
+    let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
+    for t in log
+        .iter()
+        .filter(|t| t.reason == "pay" && t.delta < 0 && t.at >= since)
+    {
+        if let Some(r) = t.reference.as_deref() {
+            edges.entry(t.user_id.as_str()).or_default().push(r);
+        }
+    }
+
+    let mut parent: HashMap<&str, &str> = HashMap::new();
+    let mut frontier = vec![to];
+    for _ in 0..PAY_CYCLE_MAX_HOPS {
+        let mut next = Vec::new();
+        for node in frontier {
+            for &out in edges.get(node).into_iter().flatten() {
+                if out == to || parent.contains_key(out) {
+                    continue;
+                }
+                parent.insert(out, node);
+                if out == from {
+                    let mut back = vec![from.to_string()];
+                    let mut cur = from;
+                    while cur != to {
+                        cur = parent[cur];
+                        back.push(cur.to_string());
+                    }
+                    back.reverse();
+                    let mut chain = vec![from.to_string()];
+                    chain.extend(back);
+                    return Some(chain);
+                }
+                next.push(out);
+            }
+        }
+        frontier = next;
+    }
+    None
+}
+
+/// Result of a completed `/pay` transfer.
+struct TransferReceipt {
+    received: u64,
+    fee: u64,
+    balance: u64,
+    // Коло переказів, яке замкнув цей переказ
+    cycle: Option<Vec<String>>,
+}
+
+/// Moves chips between wallets in one step under the users lock: checks the
+/// sender's balance, level and daily cap, burns the fee and records both
+/// sides in the ledger.
+fn transfer_chips(
+    data: &Data,
+    config: &EconomyConfig,
+    from: &str,
+    to: &str,
+    amount: u64,
+) -> Result<TransferReceipt, String> {
+    let now = Utc::now().timestamp();
+    let mut users = safe_lock(&data.users);
+    let sender = users
+        .entry(from.to_string())
+        .or_insert(create_default_profile());
+    if sender.level < config.pay_min_level {
+        return Err(format!(
+            "⭐ Переказувати можна з рівня **{}** (у тебе {}).",
+            config.pay_min_level, sender.level
+        ));
+    }
+    if sender.chips < amount {
+        return Err(format!("❌ Недостатньо гривень (у тебе {}).", sender.chips));
+    }
+
+    let (sent_today, cycle) = {
+        let log = safe_lock(&data.transactions);
+        (
+            pay_sent_since(&log, from, now - 86400),
+            transfer_cycle(&log, from, to, now - PAY_CYCLE_WINDOW_SECS),
+        )
+    };
+    if config.pay_daily_cap > 0 && sent_today.saturating_add(amount) > config.pay_daily_cap {
+        return Err(format!(
+            "📊 Денний ліміт переказів: {} гривень. Сьогодні вже переказано {}.",
+            config.pay_daily_cap, sent_today
+        ));
+    }
+
+    let fee = transfer_fee(amount, config.pay_fee_percent).min(amount);
+    sender.chips -= amount;
+    let balance = sender.chips;
+    let recipient = users
+        .entry(to.to_string())
+        .or_insert(create_default_profile());
+    recipient.chips = recipient.chips.saturating_add(amount - fee);
+
+    log_transactions(data, &[(from, -(amount as i64))], "pay", Some(to));
+    log_transactions(data, &[(to, (amount - fee) as i64)], "pay", Some(from));
+    save_json(USERS_FILE, &*users);
+    Ok(TransferReceipt {
+        received: amount - fee,
+        fee,
+        balance,
+        cycle,
+    })
+}
+
+/// 💸 Переказати гривні іншому гравцю
+#[poise::command(slash_command, guild_only)]
+async fn pay(
+    ctx: Context<'_>,
+    #[description = "Кому переказати"] user: serenity::User,
+    #[description = "Сума"]
+    #[min = 1]
+    amount: u64,
+) -> Result<(), Error> {
+    let config = safe_lock(&ctx.data().economy_config).clone();
+    let account_days =
+        (Utc::now().timestamp() - ctx.author().created_at().unix_timestamp()) / 86400;
+
+    let blocked = if user.bot || user.id == ctx.author().id {
+        Some("❌ Обери іншого гравця.".to_string())
+    } else if amount == 0 {
+        Some("❌ Сума має бути більшою за 0.".to_string())
+    } else if account_days < config.pay_min_account_days {
+        Some(format!(
+            "🕒 Переказувати можна акаунтам, старшим за {} днів.",
+            config.pay_min_account_days
+        ))
+    } else {
+        None
+    };
+    if let Some(text) = blocked {
+        ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+
+    let fee = transfer_fee(amount, config.pay_fee_percent).min(amount);
+    if config.pay_confirm_above > 0 && amount >= config.pay_confirm_above {
+        let prefix = format!("pay_{}_", ctx.id());
+        let reply = ctx
+            .send(
+                poise::CreateReply::default()
+                    .content(format!(
+                        "⚠️ Переказати **{} гривень** для <@{}>? Комісія: {}, отримувач отримає {}.",
+                        amount,
+                        user.id,
+                        fee,
+                        amount - fee
+                    ))
+                    .components(vec![CreateActionRow::Buttons(vec![
+                        CreateButton::new(format!("{}yes", prefix))
+                            .label("Підтвердити")
+                            .style(ButtonStyle::Success),
+                        CreateButton::new(format!("{}no", prefix))
+                            .label("Скасувати")
+                            .style(ButtonStyle::Secondary),
+                    ])])
+                    .ephemeral(true),
+            )
+            .await?;
+
+        let answer = serenity::ComponentInteractionCollector::new(ctx)
+            .author_id(ctx.author().id)
+            .timeout(Duration::from_secs(30))
+            .filter({
+                let prefix = prefix.clone();
+                move |mci| mci.data.custom_id.starts_with(&prefix)
+            })
+            .await;
+        let confirmed = match &answer {
+            Some(mci) => {
+                mci.defer(&ctx.http()).await?;
+                mci.data.custom_id.ends_with("yes")
+            }
+            None => false,
+        };
+        if !confirmed {
+            reply
+                .edit(
+                    ctx,
+                    poise::CreateReply::default()
+                        .content("↩️ Переказ скасовано.")
+                        .components(vec![]),
+                )
+                .await?;
+            return Ok(());
+        }
+        reply
+            .edit(
+                ctx,
+                poise::CreateReply::default()
+                    .content("✅ Підтверджено.")
+                    .components(vec![]),
+            )
+            .await?;
+    }
+
+    let sender_id = ctx.author().id.to_string();
+    let recipient_id = user.id.to_string();
+    match transfer_chips(ctx.data(), &config, &sender_id, &recipient_id, amount) {
+        Err(text) => {
+            ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+                .await?;
+        }
+        Ok(receipt) => {
+            if let Some(chain) = receipt.cycle {
+                warn!("⚠️ Круговий переказ: {}", chain.join(" → "));
+                let mut flags = safe_lock(&ctx.data().transfer_flags);
+                flags.push(TransferFlag {
+                    chain,
+                    amount,
+                    at: Utc::now().timestamp(),
+                });
+                save_json(TRANSFER_FLAGS_FILE, &*flags);
+            }
+            let fee_note = if receipt.fee > 0 {
+                format!(" (комісія {})", receipt.fee)
+            } else {
+                String::new()
+            };
+            ctx.say(format!(
+                "💸 <@{}> переказав <@{}> **{} гривень**{}.\n💰 Залишок: {}",
+                sender_id, recipient_id, receipt.received, fee_note, receipt.balance
+            ))
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+fn check_bet(config: &CasinoConfig, amount: u64) -> Result<(), String> {
+    if amount < config.min_bet.max(1) || amount > config.max_bet {
+        return Err(format!(
+            "❌ Ставка має бути від {} до {} гривень.",
+            config.min_bet.max(1),
+            config.max_bet
+        ));
+    }
+    Ok(())
+}
+
+/// Settles a single-round house game in one step: takes the stake, pays the
+/// gross payout back and logs both. Returns the new balance.
+fn play_house_game(
+    data: &Data,
+    user_id: &str,
+    stake: u64,
+    gross: u64,
+    reason: &str,
+) -> Result<u64, String> {
+    let mut users = safe_lock(&data.users);
+    let profile = users
+        .entry(user_id.to_string())
+        .or_insert(create_default_profile());
+    if profile.chips < stake {
+        return Err(format!(
+            "❌ Недостатньо гривень (у тебе {}).",
+            profile.chips
+        ));
+    }
+    profile.chips = (profile.chips - stake).saturating_add(gross);
+    let balance = profile.chips;
+    log_transactions(
+        data,
+        &[(user_id, -(stake as i64)), (user_id, gross as i64)],
+        reason,
+        None,
+    );
+    save_json(USERS_FILE, &*users);
+    Ok(balance)
+}
+
+fn game_result_line(stake: u64, gross: u64, balance: u64) -> String {
+    let result = if gross > stake {
+        format!("🎉 Виграш: **+{}** гривень", gross - stake)
+    } else if gross == stake {
+        "🤝 Ставку повернено".to_string()
+    } else {
+        format!("📉 Програш: **-{}** гривень", stake - gross)
+    };
+    format!("{}\n💰 Баланс: {}", result, balance)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+enum RouletteKind {
+    #[name = "Число (35:1)"]
+    Number,
+    #[name = "Червоне (1:1)"]
+    Red,
+    #[name = "Чорне (1:1)"]
+    Black,
+    #[name = "Дюжина (2:1)"]
+    Dozen,
+}
+
+/// 🎡 Європейська рулетка
+#[poise::command(slash_command)]
+async fn roulette(
+    ctx: Context<'_>,
+    #[description = "Тип ставки"] kind: RouletteKind,
+    #[description = "Сума ставки"] amount: u64,
+    #[description = "Число 0-36 або дюжина 1-3"]
+    #[max = 36]
+    value: Option<u8>,
+) -> Result<(), Error> {
+    let bet = match (kind, value) {
+        (RouletteKind::Number, Some(n)) if n <= 36 => Ok(games::RouletteBet::Number(n)),
+        (RouletteKind::Dozen, Some(d)) if (1..=3).contains(&d) => Ok(games::RouletteBet::Dozen(d)),
+        (RouletteKind::Red, _) => Ok(games::RouletteBet::Red),
+        (RouletteKind::Black, _) => Ok(games::RouletteBet::Black),
+        (RouletteKind::Number, _) => Err("❌ Вкажи число від 0 до 36.".to_string()),
+        (RouletteKind::Dozen, _) => Err("❌ Вкажи дюжину від 1 до 3.".to_string()),
+    };
+
+    let config = safe_lock(&ctx.data().casino_config).clone();
+    let pocket = games::spin_roulette(&mut rand::thread_rng());
+    let played = bet.and_then(|bet| {
+        check_bet(&config, amount)?;
+        let gross = games::apply_edge(
+            amount,
+            games::roulette_payout(bet, amount, pocket),
+            config.house_edge,
+        );
+        let balance = play_house_game(
+            ctx.data(),
+            &ctx.author().id.to_string(),
+            amount,
+            gross,
+            "roulette",
+        )?;
+        Ok((gross, balance))
+    });
+
+    match played {
+        Err(text) => {
+            ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+                .await?;
+        }
+        Ok((gross, balance)) => {
+            ctx.say(format!(
+                "🎡 Випало **{} {}**\n{}",
+                games::roulette_emoji(pocket),
+                pocket,
+                game_result_line(amount, gross, balance)
+            ))
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// 🎰 Слот-машина
+#[poise::command(slash_command)]
+async fn slots(
+    ctx: Context<'_>, #[description = "Сума ставки"] amount: u64
+) -> Result<(), Error> {
+    let config = safe_lock(&ctx.data().casino_config).clone();
+    let spun = games::spin_slots(&config.slots, &mut rand::thread_rng())
+        .ok_or_else(|| "❌ Слоти не налаштовані.".to_string());
+    let played = spun.and_then(|reels| {
+        check_bet(&config, amount)?;
+        let gross = games::apply_edge(
+            amount,
+            games::slots_payout(&config.slots, reels, amount),
+            config.house_edge,
+        );
+        let balance = play_house_game(
+            ctx.data(),
+            &ctx.author().id.to_string(),
+            amount,
+            gross,
+            "slots",
+        )?;
+        Ok((reels, gross, balance))
+    });
+
+    match played {
+        Err(text) => {
+            ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+                .await?;
+        }
+        Ok((reels, gross, balance)) => {
+            let line = reels
+                .iter()
+                .map(|&i| config.slots[i].emoji.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            ctx.say(format!(
+                "🎰 | {} |\n{}",
+                line,
+                game_result_line(amount, gross, balance)
+            ))
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+enum DiceDirection {
+    #[name = "Більше"]
+    Over,
+    #[name = "Менше"]
+    Under,
+}
+
+/// 🎲 Кубики: вгадай, чи випаде більше або менше за число
+#[poise::command(slash_command)]
+async fn dice(
+    ctx: Context<'_>,
+    #[description = "Більше чи менше"] direction: DiceDirection,
+    #[description = "Поріг від 2 до 98"]
+    #[min = 2]
+    #[max = 98]
+    target: u8,
+    #[description = "Сума ставки"] amount: u64,
+) -> Result<(), Error> {
+    let call = match direction {
+        DiceDirection::Over => games::DiceCall::Over,
+        DiceDirection::Under => games::DiceCall::Under,
+    };
+    let target = target.clamp(2, 98);
+    let config = safe_lock(&ctx.data().casino_config).clone();
+    let roll = games::roll_dice(&mut rand::thread_rng());
+    let played = check_bet(&config, amount).and_then(|_| {
+        let gross = games::apply_edge(
+            amount,
+            games::dice_payout(call, target, amount, roll),
+            config.house_edge,
+        );
+        let balance = play_house_game(
+            ctx.data(),
+            &ctx.author().id.to_string(),
+            amount,
+            gross,
+            "dice",
+        )?;
+        Ok((gross, balance))
+    });
+
+    match played {
+        Err(text) => {
+            ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+                .await?;
+        }
+        Ok((gross, balance)) => {
+            ctx.say(format!(
+                "🎲 Випало **{}** (ставка: {} {}, шанс {}%)\n{}",
+                roll,
+                poise::ChoiceParameter::name(&direction).to_lowercase(),
+                target,
+                games::dice_chance(call, target),
+                game_result_line(amount, gross, balance)
+            ))
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+fn duel_embed(duel: &Duel, status: &str, color: u32) -> CreateEmbed {
+    CreateEmbed::new()
+        .title("⚔️ Дуель на монетку")
+        .colour(color)
+        .description(format!(
+            "<@{}> викликає <@{}> на **{} гривень**.\n\n{}",
+            duel.challenger_id, duel.opponent_id, duel.stake, status
+        ))
+}
+
+/// Removes a pending duel and refunds the challenger's escrowed stake.
+fn cancel_duel(data: &Data, challenger_id: &str) -> Option<Duel> {
+    let mut duels = safe_lock(&data.duels);
+    let duel = duels.remove(challenger_id)?;
+    let mut users = safe_lock(&data.users);
+    let profile = users
+        .entry(duel.challenger_id.clone())
+        .or_insert(create_default_profile());
+    profile.chips = profile.chips.saturating_add(duel.stake);
+    log_transactions(
+        data,
+        &[(&duel.challenger_id, duel.stake as i64)],
+        "duel_refund",
+        None,
+    );
+    save_json(USERS_FILE, &*users);
+    save_json(DUELS_FILE, &*duels);
+    Some(duel)
+}
+
+/// ⚔️ Дуель на монетку з іншим гравцем
+#[poise::command(slash_command, guild_only)]
+async fn duel(
+    ctx: Context<'_>,
+    #[description = "Суперник"] opponent: serenity::User,
+    #[description = "Ставка кожного гравця"] amount: u64,
+) -> Result<(), Error> {
+    let uid_str = ctx.author().id.to_string();
+    let config = safe_lock(&ctx.data().casino_config).clone();
+
+    let started: Result<Duel, String> = if opponent.bot || opponent.id == ctx.author().id {
+        Err("❌ Обери іншого гравця.".to_string())
+    } else {
+        check_bet(&config, amount).and_then(|_| {
+            // Лок дуелей перед локом користувачів: ставка списується в депозит
+            // разом зі створенням виклику.
+            let mut duels = safe_lock(&ctx.data().duels);
+            if duels.contains_key(&uid_str) {
+                return Err("❌ У тебе вже є активний виклик.".to_string());
+            }
+            let mut users = safe_lock(&ctx.data().users);
+            let profile = users
+                .entry(uid_str.clone())
+                .or_insert(create_default_profile());
+            if profile.chips < amount {
+                Err("❌ Недостатньо гривень.".to_string())
+            } else {
+                profile.chips -= amount;
+                let duel = Duel {
+                    challenger_id: uid_str.clone(),
+                    opponent_id: opponent.id.to_string(),
+                    channel_id: ctx.channel_id().to_string(),
+                    message_id: String::new(),
+                    stake: amount,
+                    created_at: Utc::now().timestamp(),
+                };
+                duels.insert(uid_str.clone(), duel.clone());
+                log_transactions(
+                    ctx.data(),
+                    &[(&uid_str, -(amount as i64))],
+                    "duel",
+                    Some(&duel.opponent_id),
+                );
+                save_json(USERS_FILE, &*users);
+                save_json(DUELS_FILE, &*duels);
+                Ok(duel)
+            }
+        })
+    };
+
+    let duel = match started {
+        Ok(d) => d,
+        Err(text) => {
+            ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let (prize, _) = games::duel_prize(duel.stake, config.house_edge);
+    let status = format!(
+        "Переможець забирає **{} гривень**. Виклик діє {} хв.",
+        prize,
+        DUEL_TIMEOUT_SECS / 60
+    );
+    let reply = ctx
+        .send(
+            poise::CreateReply::default()
+                .content(format!("<@{}>", duel.opponent_id))
+                .embed(duel_embed(&duel, &status, 0xE67E22))
+                .components(vec![CreateActionRow::Buttons(vec![
+                    CreateButton::new("duel_accept")
+                        .label("Прийняти")
+                        .style(ButtonStyle::Success),
+                    CreateButton::new("duel_decline")
+                        .label("Відмовитися")
+                        .style(ButtonStyle::Danger),
+                ])]),
+        )
+        .await;
+
+    let message_id = match reply {
+        Ok(handle) => handle.message().await.map(|m| m.id.to_string()),
+        Err(e) => Err(e),
+    };
+    match message_id {
+        Ok(id) => {
+            let mut duels = safe_lock(&ctx.data().duels);
+            if let Some(d) = duels.get_mut(&uid_str) {
+                d.message_id = id;
+                save_json(DUELS_FILE, &*duels);
+            }
+        }
+        Err(e) => {
+            // Повідомлення не надіслано — виклик без кнопок не має сенсу
+            cancel_duel(ctx.data(), &uid_str);
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+/// Handles the `duel_accept` / `duel_decline` buttons. Accepting escrows the
+/// opponent's stake, flips the coin and pays the winner in one step.
+async fn handle_duel_interaction(
+    ctx: &serenity::Context,
+    data: &Data,
+    interaction: &serenity::ComponentInteraction,
+) -> Result<(), Error> {
+    let msg_id = interaction.message.id.to_string();
+    let user_id = interaction.user.id.to_string();
+    let accept = interaction.data.custom_id == "duel_accept";
+    let edge = safe_lock(&data.casino_config).house_edge;
+
+    let challenger = safe_lock(&data.duels)
+        .values()
+        .find(|d| d.message_id == msg_id)
+        .map(|d| d.challenger_id.clone());
+
+    let result: Result<(Duel, String, u32), String> = match challenger {
+        None => Err("❌ Цей виклик уже неактивний.".to_string()),
+        Some(challenger) if !accept => {
+            let allowed = safe_lock(&data.duels)
+                .get(&challenger)
+                .is_some_and(|d| d.opponent_id == user_id || d.challenger_id == user_id);
+            if !allowed {
+                Err("❌ Цей виклик не для тебе.".to_string())
+            } else {
+                match cancel_duel(data, &challenger) {
+                    Some(d) => Ok((
+                        d,
+                        format!("🚫 <@{}> скасовує дуель. Ставку повернено.", user_id),
+                        0x95A5A6,
+                    )),
+                    None => Err("❌ Цей виклик уже неактивний.".to_string()),
+                }
+            }
+        }
+        Some(challenger) => {
+            // Лок дуелей перед локом користувачів, як і при створенні виклику
+            let mut duels = safe_lock(&data.duels);
+            match duels.get(&challenger).cloned() {
+                None => Err("❌ Цей виклик уже неактивний.".to_string()),
+                Some(d) if d.opponent_id != user_id => {
+                    Err("❌ Цей виклик не для тебе.".to_string())
+                }
+                Some(d) => {
+                    let mut users = safe_lock(&data.users);
+                    let opponent = users
+                        .entry(user_id.clone())
+                        .or_insert(create_default_profile());
+                    if opponent.chips < d.stake {
+                        Err(format!(
+                            "💸 Потрібно **{} гривень** (у тебе {}).",
+                            d.stake, opponent.chips
+                        ))
+                    } else {
+                        opponent.chips -= d.stake;
+                        let winner = if rand::thread_rng().gen_bool(0.5) {
+                            d.challenger_id.clone()
+                        } else {
+                            d.opponent_id.clone()
+                        };
+                        let (prize, _) = games::duel_prize(d.stake, edge);
+                        let profile = users
+                            .entry(winner.clone())
+                            .or_insert(create_default_profile());
+                        profile.chips = profile.chips.saturating_add(prize);
+                        let loser = if winner == d.challenger_id {
+                            &d.opponent_id
+                        } else {
+                            &d.challenger_id
+                        };
+                        log_transactions(
+                            data,
+                            &[(&user_id, -(d.stake as i64))],
+                            "duel",
+                            Some(&d.challenger_id),
+                        );
+                        log_transactions(data, &[(&winner, prize as i64)], "duel", Some(loser));
+                        duels.remove(&challenger);
+                        save_json(USERS_FILE, &*users);
+                        save_json(DUELS_FILE, &*duels);
+                        Ok((
+                            d,
+                            format!(
+                                "🪙 Монетка впала! <@{}> забирає **{} гривень**.",
+                                winner, prize
+                            ),
+                            0x2ECC71,
+                        ))
+                    }
+                }
+            }
+        }
+    };
+
+    let response = match result {
+        Ok((duel, status, color)) => serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .embed(duel_embed(&duel, &status, color))
+                .components(vec![]),
+        ),
+        Err(text) => serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::new()
+                .content(text)
+                .ephemeral(true),
+        ),
+    };
+    interaction.create_response(&ctx.http, response).await?;
+    Ok(())
+}
+
+/// Refunds duels nobody answered in time. Runs from `background_tasks`.
+async fn expire_duels(ctx: &serenity::Context, data: &Data) {
+    let cutoff = Utc::now().timestamp() - DUEL_TIMEOUT_SECS;
+    let stale: Vec<String> = safe_lock(&data.duels)
+        .values()
+        .filter(|d| d.created_at < cutoff)
+        .map(|d| d.challenger_id.clone())
+        .collect();
+
+    for challenger in stale {
+        let Some(duel) = cancel_duel(data, &challenger) else {
+            continue;
+        };
+        let (Ok(channel), Ok(msg)) = (
+            duel.channel_id.parse::<u64>(),
+            duel.message_id.parse::<u64>(),
+        ) else {
+            continue;
+        };
+        let _ = ChannelId::new(channel)
+            .edit_message(
+                &ctx.http,
+                serenity::MessageId::new(msg),
+                serenity::EditMessage::new()
+                    .embed(duel_embed(
+                        &duel,
+                        "⏱️ Виклик ніхто не прийняв. Ставку повернено.",
+                        0x95A5A6,
+                    ))
+                    .components(vec![]),
+            )
+            .await;
+    }
+}
+
+/// Applies a move to the user's game under the games→users locks. The extra
+/// stake for a double, split or insurance is checked before the move and
+/// charged only once the game accepts it; a finished round is paid out and removed. `None` stands on every hand (used
+/// on timeout). Returns the game and, once settled, the payout.
+fn blackjack_step(
+    data: &Data,
+    user_id: &str,
+    action: Option<blackjack::Action>,
+) -> Result<(BlackjackGame, Option<u64>), &'static str> {
+    let mut games = safe_lock(&data.blackjack_games);
+    let Some(entry) = games.get_mut(user_id) else {
+        return Err("❌ Гру вже завершено.");
+    };
+    let mut users = safe_lock(&data.users);
+    let profile = users
+        .entry(user_id.to_string())
+        .or_insert(create_default_profile());
+
+    match action {
+        Some(action) => {
+            if !entry.game.can(action) {
+                return Err("❌ Ця дія зараз недоступна.");
+            }
+            let extra = entry.game.extra_stake(action);
+            if profile.chips < extra {
+                return Err("❌ Недостатньо гривень для цієї дії.");
+            }
+            // Гроші списуються лише після того, як гра прийняла хід
+            entry.game.apply(action)?;
+            profile.chips -= extra;
+            log_transactions(data, &[(user_id, -(extra as i64))], "blackjack", None);
+        }
+        None => entry.game.stand_all(),
+    }
+    entry.last_action = Utc::now().timestamp();
+    let snap = entry.clone();
+
+    let payout = if snap.game.phase == blackjack::Phase::Finished {
+        games.remove(user_id);
+        let payout = snap.game.payout();
+        profile.chips = profile.chips.saturating_add(payout);
+        log_transactions(data, &[(user_id, payout as i64)], "blackjack", None);
+        Some(payout)
+    } else {
+        None
+    };
+    save_json(USERS_FILE, &*users);
+    save_json(BLACKJACK_FILE, &*games);
+    Ok((snap, payout))
+}
+
+/// Embed title and colour for the current state of a round.
+fn blackjack_title(game: &blackjack::Game) -> (String, u32) {
+    use blackjack::{Outcome, Phase};
+    if game.phase != Phase::Finished {
+        return ("🃏 Блекджек".to_string(), 0x3498DB);
+    }
+    let (payout, staked) = (game.payout(), game.total_staked());
+    if game.hands.len() == 1 && game.outcome(&game.hands[0]) == Outcome::Blackjack {
+        (
+            format!("🂡 Блекджек! +{} гривень (3:2)", payout - staked),
+            0xFFD700,
+        )
+    } else if payout > staked {
+        (
+            format!("🎉 Перемога! +{} гривень", payout - staked),
+            0x2ECC71,
+        )
+    } else if payout == staked {
+        ("🤝 Нічия".to_string(), 0xF1C40F)
+    } else {
+        (
+            format!("📉 Програш: -{} гривень", staked - payout),
+            0xE74C3C,
+        )
+    }
+}
+
+fn blackjack_embed(game: &blackjack::Game, title: &str, color: u32) -> CreateEmbed {
+    use blackjack::{format_cards, hand_value, Outcome, Phase};
+    let finished = game.phase == Phase::Finished;
+    let dealer = if finished {
+        format!(
+            "{} ({})",
+            format_cards(&game.dealer),
+            hand_value(&game.dealer).0
+        )
+    } else {
+        format!("{} 🂠", game.dealer[0])
+    };
+
+    let mut embed = CreateEmbed::new()
+        .title(title)
+        .colour(color)
+        .field("Дилер", dealer, false);
+    if game.phase == Phase::Insurance {
+        embed = embed.description(format!(
+            "У дилера туз. Страховка коштує {} гривень і платить 2:1.",
+            game.extra_stake(blackjack::Action::Insurance)
+        ));
+    }
+
+    for (i, hand) in game.hands.iter().enumerate() {
+        let name = if game.hands.len() == 1 {
+            "Твоя рука".to_string()
+        } else {
+            format!("Рука {}", i + 1)
+        };
+        let marker = if game.phase == Phase::Player && i == game.active {
+            " ◀"
+        } else {
+            ""
+        };
+        let status = if finished {
+            match game.outcome(hand) {
+                Outcome::Blackjack => "\n🂡 Блекджек",
+                Outcome::Win => "\n✅ Виграш",
+                Outcome::Push => "\n🤝 Нічия",
+                Outcome::Lose => "\n❌ Програш",
+                Outcome::Bust => "\n💥 Перебір",
+            }
+        } else if hand.is_bust() {
+            "\n💥 Перебір"
+        } else {
+            ""
+        };
+        embed = embed.field(
+            format!("{} ({}){}", name, hand.value(), marker),
+            format!(
+                "{}\nСтавка: {}{}{}",
+                format_cards(&hand.cards),
+                hand.bet,
+                if hand.doubled {
+                    " (подвоєно)"
+                } else {
+                    ""
+                },
+                status
+            ),
+            true,
+        );
+    }
+
+    let mut footer = format!(
+        "Колод: {} • Дилер {} на м'яких 17",
+        game.rules.decks,
+        if game.rules.dealer_hits_soft_17 {
+            "бере"
+        } else {
+            "стоїть"
+        }
+    );
+    if game.insurance > 0 {
+        footer.push_str(&format!(" • Страховка: {}", game.insurance));
+    }
+    embed.footer(CreateEmbedFooter::new(footer))
+}
+
+const BLACKJACK_ACTIONS: [(&str, blackjack::Action); 6] = [
+    ("h", blackjack::Action::Hit),
+    ("s", blackjack::Action::Stand),
+    ("d", blackjack::Action::Double),
+    ("p", blackjack::Action::Split),
+    ("i", blackjack::Action::Insurance),
+    ("n", blackjack::Action::DeclineInsurance),
+];
+
+/// Buttons for the moves of the current phase; unavailable ones are disabled.
+fn blackjack_buttons(prefix: &str, game: &blackjack::Game) -> Vec<CreateActionRow> {
+    use blackjack::{Action, Phase};
+    let button = |action: Action, label: String, style: ButtonStyle| {
+        let key = BLACKJACK_ACTIONS
+            .iter()
+            .find(|(_, a)| *a == action)
+            .map_or("", |(k, _)| *k);
+        CreateButton::new(format!("{}{}", prefix, key))
+            .label(label)
+            .style(style)
+            .disabled(!game.can(action))
+    };
+    let buttons = match game.phase {
+        Phase::Insurance => vec![
+            button(
+                Action::Insurance,
+                format!("Страховка ({})", game.extra_stake(Action::Insurance)),
+                ButtonStyle::Primary,
+            ),
+            button(
+                Action::DeclineInsurance,
+                "Без страховки".to_string(),
+                ButtonStyle::Secondary,
+            ),
+        ],
+        Phase::Player => vec![
+            button(Action::Hit, "Ще".to_string(), ButtonStyle::Success),
+            button(Action::Stand, "Все".to_string(), ButtonStyle::Primary),
+            button(
+                Action::Double,
+                "Подвоїти".to_string(),
+                ButtonStyle::Secondary,
+            ),
+            button(
+                Action::Split,
+                "Розділити".to_string(),
+                ButtonStyle::Secondary,
+            ),
+        ],
+        Phase::Finished => return vec![],
+    };
+    vec![CreateActionRow::Buttons(buttons)]
+}
+
+/// Auto-stands games abandoned longer than the timeout, e.g. ones whose
+/// button collector was lost in a restart. Runs from `background_tasks`.
+async fn expire_blackjack_games(ctx: &serenity::Context, data: &Data) {
+    let cutoff = Utc::now().timestamp() - BLACKJACK_TIMEOUT_SECS - 30;
+    let stale: Vec<String> = safe_lock(&data.blackjack_games)
+        .values()
+        .filter(|g| g.last_action < cutoff)
+        .map(|g| g.user_id.clone())
+        .collect();
+
+    for user_id in stale {
+        let Ok((game, _)) = blackjack_step(data, &user_id, None) else {
+            continue;
+        };
+        let (Ok(channel), Ok(msg)) = (
+            game.channel_id.parse::<u64>(),
+            game.message_id.parse::<u64>(),
+        ) else {
+            continue;
+        };
+        let (title, color) = blackjack_title(&game.game);
+        let _ = ChannelId::new(channel)
+            .edit_message(
+                &ctx.http,
+                serenity::MessageId::new(msg),
+                serenity::EditMessage::new()
+                    .embed(blackjack_embed(&game.game, &format!("⏱️ {}", title), color))
+                    .components(vec![]),
+            )
+            .await;
+    }
+}
+
+/// 🃏 Зіграй у блекджек
+#[poise::command(slash_command)]
+async fn blackjack(ctx: Context<'_>, bet: u64) -> Result<(), Error> {
+    let uid_str = ctx.author().id.to_string();
+    let config = safe_lock(&ctx.data().casino_config).clone();
+    let dealt = blackjack::Game::deal(config.blackjack, bet, &mut rand::thread_rng());
+
+    // Ставка списується в депозит одразу, під тим самим локом, що й
+    // перевірка активної гри, — дві гри на ті самі гроші неможливі.
+    // Натуральний блекджек на роздачі розраховується одразу.
+    let started: Result<blackjack::Game, String> = if let Err(e) = check_bet(&config, bet) {
+        Err(e)
+    } else {
+        let mut games = safe_lock(&ctx.data().blackjack_games);
+        if games.contains_key(&uid_str) {
+            Err("❌ У тебе вже є активна гра. Спершу заверши її.".to_string())
+        } else {
+            let mut users = safe_lock(&ctx.data().users);
+            match users.get_mut(&uid_str) {
+                Some(p) if p.chips >= bet => {
+                    p.chips -= bet;
+                    let mut moves = vec![(uid_str.as_str(), -(bet as i64))];
+                    if dealt.phase == blackjack::Phase::Finished {
+                        p.chips = p.chips.saturating_add(dealt.payout());
+                        moves.push((uid_str.as_str(), dealt.payout() as i64));
+                    } else {
+                        games.insert(
+                            uid_str.clone(),
+                            BlackjackGame {
+                                user_id: uid_str.clone(),
+                                channel_id: ctx.channel_id().to_string(),
+                                message_id: String::new(),
+                                game: dealt.clone(),
+                                last_action: Utc::now().timestamp(),
+                            },
+                        );
+                        save_json(BLACKJACK_FILE, &*games);
+                    }
+                    log_transactions(ctx.data(), &moves, "blackjack", None);
+                    save_json(USERS_FILE, &*users);
+                    Ok(dealt)
+                }
+                _ => Err("❌ Недостатньо гривень.".to_string()),
+            }
+        }
+    };
+
+    let game = match started {
+        Ok(g) => g,
+        Err(text) => {
+            ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let prefix = format!("{}bj", ctx.id());
+    let (title, color) = blackjack_title(&game);
+    let msg = ctx
+        .send(
+            poise::CreateReply::default()
+                .embed(blackjack_embed(&game, &title, color))
+                .components(blackjack_buttons(&prefix, &game)),
+        )
+        .await?;
+    if game.phase == blackjack::Phase::Finished {
+        return Ok(());
+    }
+    let message_id = msg.message().await?.id.to_string();
+    {
+        // Зберігаємо ID повідомлення, щоб після перезапуску
+        // expire_blackjack_games могла прибрати кнопки
+        let mut games = safe_lock(&ctx.data().blackjack_games);
+        if let Some(g) = games.get_mut(&uid_str) {
+            g.message_id = message_id;
+            save_json(BLACKJACK_FILE, &*games);
+        }
+    }
+
+    while let Some(m) = msg
+        .message()
+        .await?
+        .await_component_interaction(&ctx.serenity_context().shard)
+        .timeout(Duration::from_secs(BLACKJACK_TIMEOUT_SECS as u64))
+        .await
+    {
+        let action = m
+            .data
+            .custom_id
+            .strip_prefix(&prefix)
+            .and_then(|key| BLACKJACK_ACTIONS.iter().find(|(k, _)| *k == key))
+            .map(|(_, a)| *a);
+        let Some(action) = action.filter(|_| m.user.id == ctx.author().id) else {
+            m.defer(&ctx.http()).await?;
+            continue;
+        };
+
+        match blackjack_step(ctx.data(), &uid_str, Some(action)) {
+            Ok((game, payout)) => {
+                let (title, color) = blackjack_title(&game.game);
+                m.create_response(
+                    &ctx.http(),
+                    serenity::CreateInteractionResponse::UpdateMessage(
+                        serenity::CreateInteractionResponseMessage::new()
+                            .embed(blackjack_embed(&game.game, &title, color))
+                            .components(blackjack_buttons(&prefix, &game.game)),
+                    ),
+                )
+                .await?;
+                if payout.is_some() {
+                    return Ok(());
+                }
+            }
+            Err(text) => {
+                m.create_response(
+                    &ctx.http(),
+                    serenity::CreateInteractionResponse::Message(
+                        serenity::CreateInteractionResponseMessage::new()
+                            .content(text)
+                            .ephemeral(true),
+                    ),
+                )
+                .await?;
+            }
+        }
+    }
+
+    // Час вийшов — гра автоматично зупиняється на всіх руках
+    if let Ok((game, _)) = blackjack_step(ctx.data(), &uid_str, None) {
+        let (title, color) = blackjack_title(&game.game);
+        msg.edit(
+            ctx,
+            poise::CreateReply::default()
+                .embed(blackjack_embed(&game.game, &format!("⏱️ {}", title), color))
+                .components(vec![]),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// [ADMIN] Налаштування ігор казино
+#[poise::command(
+    slash_command,
+    rename = "games_config",
+    subcommands(
+        "games_config_show",
+        "games_config_limits",
+        "games_config_edge",
+        "games_config_slot",
+        "games_config_blackjack"
+    ),
+    default_member_permissions = "ADMINISTRATOR"
+)]
+async fn games_config(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// [ADMIN] Правила блекджеку: кількість колод і м'які 17
+#[poise::command(
+    slash_command,
+    rename = "blackjack",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn games_config_blackjack(
+    ctx: Context<'_>,
+    #[description = "Кількість колод у шузі (1-8)"]
+    #[min = 1]
+    #[max = 8]
+    decks: Option<u8>,
+    #[description = "Дилер бере карту на м'яких 17"] dealer_hits_soft_17: Option<bool>,
+) -> Result<(), Error> {
+    let rules = {
+        let mut config = safe_lock(&ctx.data().casino_config);
+        if let Some(decks) = decks {
+            config.blackjack.decks = decks.clamp(1, 8);
+        }
+        if let Some(hits) = dealer_hits_soft_17 {
+            config.blackjack.dealer_hits_soft_17 = hits;
+        }
+        if decks.is_some() || dealer_hits_soft_17.is_some() {
+            save_json(CASINO_CONFIG_FILE, &*config);
+        }
+        config.blackjack
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!(
+                "🃏 Блекджек: колод — {}, дилер {} на м'яких 17.",
+                rules.decks,
+                if rules.dealer_hits_soft_17 {
+                    "бере"
+                } else {
+                    "стоїть"
+                }
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// [ADMIN] Мінімальна й максимальна ставка в іграх
+#[poise::command(
+    slash_command,
+    rename = "limits",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn games_config_limits(
+    ctx: Context<'_>,
+    #[description = "Мінімальна ставка"] min: u64,
+    #[description = "Максимальна ставка"] max: u64,
+) -> Result<(), Error> {
+    let text = if min == 0 || min > max {
+        "❌ Мінімальна ставка має бути більшою за 0 і не більшою за максимальну.".to_string()
+    } else {
+        let mut config = safe_lock(&ctx.data().casino_config);
+        config.min_bet = min;
+        config.max_bet = max;
+        save_json(CASINO_CONFIG_FILE, &*config);
+        format!("✅ Ставки: від {} до {} гривень.", min, max)
+    };
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// [ADMIN] Перевага казино: відсоток, що лишається з виграшу
+#[poise::command(slash_command, rename = "edge", required_permissions = "ADMINISTRATOR")]
+async fn games_config_edge(
+    ctx: Context<'_>,
+    #[description = "Відсоток від 0 до 50"]
+    #[min = 0.0]
+    #[max = 50.0]
+    percent: f64,
+) -> Result<(), Error> {
+    let percent = percent.clamp(0.0, 50.0);
+    {
+        let mut config = safe_lock(&ctx.data().casino_config);
+        config.house_edge = percent;
+        save_json(CASINO_CONFIG_FILE, &*config);
+    }
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!("✅ Перевага казино: {}%.", percent))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// [ADMIN] Символ слотів (вага 0 — видалити)
+#[poise::command(slash_command, rename = "slot", required_permissions = "ADMINISTRATOR")]
+async fn games_config_slot(
+    ctx: Context<'_>,
+    #[description = "Емодзі символу"] emoji: String,
+    #[description = "Вага (частота) символу; 0 — видалити"] weight: u32,
+    #[description = "Множник за три однакові"] three: u64,
+    #[description = "Множник за два однакові на перших барабанах"] two: Option<u64>,
+) -> Result<(), Error> {
+    let emoji = emoji.trim().to_string();
+    let text = {
+        let mut config = safe_lock(&ctx.data().casino_config);
+        let existing = config.slots.iter().position(|s| s.emoji == emoji);
+        match (existing, weight) {
+            (Some(i), 0) => {
+                config.slots.remove(i);
+                save_json(CASINO_CONFIG_FILE, &*config);
+                format!("🗑️ Символ {} видалено.", emoji)
+            }
+            (None, 0) => "❌ Такого символу немає.".to_string(),
+            (existing, _) => {
+                let symbol = games::SlotSymbol {
+                    emoji: emoji.clone(),
+                    weight,
+                    three,
+                    two: two.unwrap_or(0),
+                };
+                match existing {
+                    Some(i) => config.slots[i] = symbol,
+                    None => config.slots.push(symbol),
+                }
+                save_json(CASINO_CONFIG_FILE, &*config);
+                format!(
+                    "✅ {}: вага {}, три — x{}, два — x{}.",
+                    emoji,
+                    weight,
+                    three,
+                    two.unwrap_or(0)
+                )
+            }
+        }
+    };
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// [ADMIN] Поточні налаштування ігор
+#[poise::command(slash_command, rename = "show", required_permissions = "ADMINISTRATOR")]
+async fn games_config_show(ctx: Context<'_>) -> Result<(), Error> {
+    let config = safe_lock(&ctx.data().casino_config).clone();
+    let total: u32 = config.slots.iter().map(|s| s.weight).sum();
+    let slots = config
+        .slots
+        .iter()
+        .map(|s| {
+            format!(
+                "{} — {:.1}% • три x{} • два x{}",
+                s.emoji,
+                s.weight as f64 * 100.0 / total.max(1) as f64,
+                s.three,
+                s.two
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let embed = CreateEmbed::new()
+        .title("🎲 Налаштування ігор")
+        .colour(0x9B59B6)
+        .field(
+            "Ставки",
+            format!("від {} до {} гривень", config.min_bet, config.max_bet),
+            true,
+        )
+        .field("Перевага казино", format!("{}%", config.house_edge), true)
+        .field(
+            "Блекджек",
+            format!(
+                "колод: {}, дилер {} на м'яких 17",
+                config.blackjack.decks,
+                if config.blackjack.dealer_hits_soft_17 {
+                    "бере"
+                } else {
+                    "стоїть"
+                }
+            ),
+            false,
+        )
+        .field(
+            "Слоти",
+            if slots.is_empty() {
+                "—".to_string()
+            } else {
+                slots
+            },
+            false,
+        );
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+fn format_duration_short(secs: i64) -> String {
+    if secs >= 86400 && secs % 86400 == 0 {
+        format!("{} дн", secs / 86400)
+    } else if secs >= 3600 && secs % 3600 == 0 {
+        format!("{} год", secs / 3600)
+    } else {
+        format!("{} хв", (secs / 60).max(1))
+    }
+}
+
+/// Items a guild's shop starts with: the boosters the old fixed shop sold.
+fn default_shop_items() -> Vec<ShopItem> {
+    [
+        (2, 2000, "Подвоює отримання XP"),
+        (5, 5000, "Збільшує отримання XP в 5 разів"),
+    ]
+    .into_iter()
+    .map(|(multiplier, price, description)| ShopItem {
+        name: format!("x{} XP бустер", multiplier),
+        description: description.to_string(),
+        price,
+        kind: ShopItemKind::XpBooster {
+            multiplier,
+            duration_secs: 86400,
+        },
+        stock: None,
+        min_level: 0,
+    })
+    .collect()
+}
+
+fn find_shop_item(items: &[ShopItem], name: &str) -> Option<usize> {
+    let name = name.trim().to_lowercase();
+    items.iter().position(|i| i.name.to_lowercase() == name)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+enum ShopKindChoice {
+    #[name = "Предмет"]
+    Consumable,
+    #[name = "Бустер XP"]
+    XpBooster,
+    #[name = "Тимчасова роль"]
+    TempRole,
+    #[name = "Роль"]
+    Role,
+    #[name = "Кольорова роль"]
+    ColorRole,
+    #[name = "Титул"]
+    Title,
+}
+
+/// Builds an item kind from the `/shop add` options, checking that the
+/// options the kind needs are present.
+fn build_shop_kind(
+    choice: ShopKindChoice,
+    role_id: Option<String>,
+    multiplier: Option<u64>,
+    duration_secs: Option<i64>,
+    title: Option<String>,
+) -> Result<ShopItemKind, String> {
+    let need_role = || role_id.clone().ok_or("❌ Вкажи роль.".to_string());
+    let need_duration = || {
+        duration_secs
+            .filter(|d| *d > 0)
+            .ok_or("❌ Вкажи тривалість, напр. `24h` або `7d`.".to_string())
+    };
+    Ok(match choice {
+        ShopKindChoice::Consumable => ShopItemKind::Consumable,
+        ShopKindChoice::XpBooster => ShopItemKind::XpBooster {
+            multiplier: multiplier
+                .filter(|m| *m >= 2)
+                .ok_or("❌ Вкажи множник від 2.".to_string())?,
+            duration_secs: need_duration()?,
+        },
+        ShopKindChoice::TempRole => ShopItemKind::TempRole {
+            role_id: need_role()?,
+            duration_secs: need_duration()?,
+        },
+        ShopKindChoice::Role => ShopItemKind::Role {
+            role_id: need_role()?,
+        },
+        ShopKindChoice::ColorRole => ShopItemKind::ColorRole {
+            role_id: need_role()?,
+        },
+        ShopKindChoice::Title => ShopItemKind::Title {
+            title: title
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty() && t.chars().count() <= 32)
+                .ok_or("❌ Вкажи титул до 32 символів.".to_string())?,
+        },
+    })
+}
+
+/// Checks level, stock and balance, then charges the user and moves one
+/// item into their inventory.
+fn purchase_item(
+    item: &mut ShopItem,
+    profile: &mut UserProfile,
+    guild_id: &str,
+) -> Result<(), String> {
+    charge_for_item(item, profile)?;
+    profile.add_to_inventory(guild_id, item);
+    Ok(())
+}
+
+/// Checks level, stock and balance, then takes the price and one unit of
+/// stock.
+fn charge_for_item(item: &mut ShopItem, profile: &mut UserProfile) -> Result<(), String> {
+    if profile.level < item.min_level {
+        return Err(format!(
+            "⭐ Потрібен рівень **{}** (у тебе {}).",
+            item.min_level, profile.level
+        ));
+    }
+    if item.stock == Some(0) {
+        return Err("📦 Товар закінчився.".to_string());
+    }
+    if profile.chips < item.price {
+        return Err(format!(
+            "❌ Недостатньо гривень! Потрібно 💰 {}, а у тебе {}",
+            item.price, profile.chips
+        ));
+    }
+    profile.chips -= item.price;
+    if let Some(stock) = item.stock.as_mut() {
+        *stock -= 1;
+    }
+    Ok(())
+}
+
+async fn autocomplete_shop_item(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Vec::new();
+    };
+    let partial = partial.to_lowercase();
+    let shops = safe_lock(&ctx.data().shop);
+    let items = shops
+        .get(&guild_id.to_string())
+        .cloned()
+        .unwrap_or_else(default_shop_items);
+    items
+        .into_iter()
+        .map(|i| i.name)
+        .filter(|n| n.to_lowercase().contains(&partial))
+        .take(25)
+        .collect()
+}
+
+async fn autocomplete_inventory_item(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Vec::new();
+    };
+    let (guild_id, partial) = (guild_id.to_string(), partial.to_lowercase());
+    safe_lock(&ctx.data().users)
+        .get(&ctx.author().id.to_string())
+        .map(|p| {
+            p.inventory
+                .iter()
+                .filter(|i| i.guild_id == guild_id)
+                .map(|i| i.item.name.clone())
+                .filter(|n| n.to_lowercase().contains(&partial))
+                .take(25)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 🛒 Магазин сервера
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("shop_list", "shop_buy", "shop_add", "shop_edit", "shop_remove")
+)]
+async fn shop(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Переглянути товари магазину
+#[poise::command(slash_command, rename = "list")]
+async fn shop_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().map(|g| g.to_string()).unwrap_or_default();
+    let items = safe_lock(&ctx.data().shop)
+        .get(&guild_id)
+        .cloned()
+        .unwrap_or_else(default_shop_items);
+    let chips = safe_lock(&ctx.data().users)
+        .get(&ctx.author().id.to_string())
+        .map_or(default_chips(), |p| p.chips);
+
+    let mut embed = CreateEmbed::new()
+        .title("🛒 Магазин")
+        .description(format!("**Твої гривні:** 💰 {}", chips))
+        .color(0xF1C40F)
+        .footer(CreateEmbedFooter::new(
+            "Купуй через /shop buy, використовуй через /use",
+        ));
+    if items.is_empty() {
+        embed = embed.field("Порожньо", "Адміністратори ще не додали товарів.", false);
+    }
+    for item in items.iter().take(25) {
+        let mut lines = vec![format!("**Ціна:** 💰 {}", item.price), item.kind.label()];
+        if !item.description.is_empty() {
+            lines.push(item.description.clone());
+        }
+        if let Some(stock) = item.stock {
+            lines.push(format!("📦 Залишилось: {}", stock));
+        }
+        if item.min_level > 0 {
+            lines.push(format!("⭐ З рівня {}", item.min_level));
+        }
+        embed = embed.field(&item.name, lines.join("\n"), true);
+    }
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Купити товар
+#[poise::command(slash_command, rename = "buy")]
+async fn shop_buy(
+    ctx: Context<'_>,
+    #[description = "Назва товару"]
+    #[autocomplete = "autocomplete_shop_item"]
+    item: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().map(|g| g.to_string()).unwrap_or_default();
+    let user_id = ctx.author().id.to_string();
+
+    // Лок магазину перед локом користувачів: залишок і гроші списуються разом
+    let result = {
+        let mut shops = safe_lock(&ctx.data().shop);
+        let items = shops
+            .entry(guild_id.clone())
+            .or_insert_with(default_shop_items);
+        match find_shop_item(items, &item) {
+            None => Err("❌ Такого товару немає.".to_string()),
+            Some(idx) => {
+                let mut users = safe_lock(&ctx.data().users);
+                let profile = users
+                    .entry(user_id.clone())
+                    .or_insert(create_default_profile());
+                let bought = purchase_item(&mut items[idx], profile, &guild_id)
+                    .map(|_| (items[idx].clone(), profile.chips));
+                if let Ok((bought, _)) = &bought {
+                    log_transactions(
+                        ctx.data(),
+                        &[(&user_id, -(bought.price as i64))],
+                        "shop",
+                        Some(&bought.name),
+                    );
+                    save_json(USERS_FILE, &*users);
+                    save_json(SHOP_FILE, &*shops);
+                }
+                bought
+            }
+        }
+    };
+
+    match result {
+        Err(text) => {
+            ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+                .await?;
+        }
+        Ok((item, balance)) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!(
+                        "✅ Куплено **{}** за {} гривень. Залишок: {}\nВикористай через `/use {}`.",
+                        item.name, item.price, balance, item.name
+                    ))
+                    .ephemeral(true),
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// [ADMIN] Додати товар
+#[poise::command(slash_command, rename = "add", required_permissions = "ADMINISTRATOR")]
+#[allow(clippy::too_many_arguments)]
+async fn shop_add(
+    ctx: Context<'_>,
+    #[description = "Назва товару"] name: String,
+    #[description = "Тип товару"] kind: ShopKindChoice,
+    #[description = "Ціна"] price: u64,
+    #[description = "Опис"] description: Option<String>,
+    #[description = "Роль (для рольових товарів)"] role: Option<serenity::Role>,
+    #[description = "Множник XP (для бустера)"] multiplier: Option<u64>,
+    #[description = "Тривалість, напр. 24h або 7d"] duration: Option<String>,
+    #[description = "Текст титулу"] title: Option<String>,
+    #[description = "Кількість у наявності (без — необмежено)"] stock: Option<u64>,
+    #[description = "Мінімальний рівень"] min_level: Option<u64>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().map(|g| g.to_string()).unwrap_or_default();
+    let name = name.trim().to_string();
+    let duration_secs = duration.as_deref().and_then(parse_duration);
+
+    let role_id = match &role {
+        Some(r) if r.managed || r.id.get() == ctx.guild_id().map_or(0, |g| g.get()) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content("❌ Цю роль не можна продавати.")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+        Some(r) => Some(r.id.to_string()),
+        None => None,
+    };
+
+    let result =
+        build_shop_kind(kind, role_id, multiplier, duration_secs, title).and_then(|kind| {
+            if name.is_empty() || name.chars().count() > 50 {
+                return Err("❌ Назва має бути від 1 до 50 символів.".to_string());
+            }
+            let mut shops = safe_lock(&ctx.data().shop);
+            let items = shops.entry(guild_id).or_insert_with(default_shop_items);
+            if find_shop_item(items, &name).is_some() {
+                return Err("❌ Товар з такою назвою вже є.".to_string());
+            }
+            if items.len() >= 25 {
+                return Err("❌ У магазині може бути не більше 25 товарів.".to_string());
+            }
+            let item = ShopItem {
+                name: name.clone(),
+                description: description.unwrap_or_default(),
+                price,
+                kind,
+                stock,
+                min_level: min_level.unwrap_or(0),
+            };
+            let label = item.kind.label();
+            items.push(item);
+            save_json(SHOP_FILE, &*shops);
+            Ok(format!(
+                "✅ Додано **{}** ({}) за {} гривень.",
+                name, label, price
+            ))
+        });
+
+    let text = result.unwrap_or_else(|e| e);
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// [ADMIN] Змінити товар
+#[poise::command(slash_command, rename = "edit", required_permissions = "ADMINISTRATOR")]
+async fn shop_edit(
+    ctx: Context<'_>,
+    #[description = "Назва товару"]
+    #[autocomplete = "autocomplete_shop_item"]
+    item: String,
+    #[description = "Нова ціна"] price: Option<u64>,
+    #[description = "Новий опис"] description: Option<String>,
+    #[description = "Нова кількість у наявності"] stock: Option<u64>,
+    #[description = "Зняти обмеження кількості"] unlimited: Option<bool>,
+    #[description = "Мінімальний рівень"] min_level: Option<u64>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().map(|g| g.to_string()).unwrap_or_default();
+    let text = {
+        let mut shops = safe_lock(&ctx.data().shop);
+        let items = shops.entry(guild_id).or_insert_with(default_shop_items);
+        match find_shop_item(items, &item) {
+            None => "❌ Такого товару немає.".to_string(),
+            Some(idx) => {
+                let entry = &mut items[idx];
+                if let Some(v) = price {
+                    entry.price = v;
+                }
+                if let Some(v) = description {
+                    entry.description = v;
+                }
+                if let Some(v) = stock {
+                    entry.stock = Some(v);
+                }
+                if unlimited == Some(true) {
+                    entry.stock = None;
+                }
+                if let Some(v) = min_level {
+                    entry.min_level = v;
+                }
+                let text = format!("✅ Товар **{}** оновлено.", entry.name);
+                save_json(SHOP_FILE, &*shops);
+                text
+            }
+        }
+    };
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// [ADMIN] Видалити товар (куплені лишаються в інвентарях)
+#[poise::command(
+    slash_command,
+    rename = "remove",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn shop_remove(
+    ctx: Context<'_>,
+    #[description = "Назва товару"]
+    #[autocomplete = "autocomplete_shop_item"]
+    item: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().map(|g| g.to_string()).unwrap_or_default();
+    let text = {
+        let mut shops = safe_lock(&ctx.data().shop);
+        let items = shops.entry(guild_id).or_insert_with(default_shop_items);
+        match find_shop_item(items, &item) {
+            None => "❌ Такого товару немає.".to_string(),
+            Some(idx) => {
+                let removed = items.remove(idx);
+                save_json(SHOP_FILE, &*shops);
+                format!("🗑️ Товар **{}** видалено.", removed.name)
+            }
+        }
+    };
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// [ADMIN] Множники XP: події сервера, ролі, правила
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "xp_boost",
+    subcommands(
+        "xp_boost_show",
+        "xp_boost_event",
+        "xp_boost_stop",
+        "xp_boost_role",
+        "xp_boost_stacking"
+    ),
+    default_member_permissions = "ADMINISTRATOR"
+)]
+async fn xp_boost(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// [ADMIN] Поточні множники XP сервера
+#[poise::command(slash_command, rename = "show", required_permissions = "ADMINISTRATOR")]
+async fn xp_boost_show(ctx: Context<'_>) -> Result<(), Error> {
+    let now = Utc::now().timestamp();
+    let settings = guild_xp_boosts(ctx.data(), ctx.guild_id()).unwrap_or_default();
+    let events: Vec<String> = settings
+        .server_boosts
+        .iter()
+        .filter(|b| b.until > now)
+        .map(|b| {
+            format!(
+                "🎉 **{}** {} до <t:{}:f> (<@{}>)",
+                b.name,
+                format_multiplier(b.multiplier),
+                b.until,
+                b.started_by
+            )
+        })
+        .collect();
+    let mut roles: Vec<String> = settings
+        .role_multipliers
+        .iter()
+        .map(|(role_id, m)| format!("<@&{}> — {}", role_id, format_multiplier(*m)))
+        .collect();
+    roles.sort();
+
+    let or_dash = |lines: Vec<String>| {
+        if lines.is_empty() {
+            "—".to_string()
+        } else {
+            lines.join("\n")
+        }
+    };
+    let embed = CreateEmbed::new()
+        .title("🚀 Множники XP")
+        .color(0x9B59B6)
+        .description(format!(
+            "**Поєднання:** {}\n**Максимум:** {}",
+            settings.stacking.label(),
+            format_multiplier(settings.max_multiplier)
+        ))
+        .field("Події сервера", or_dash(events), false)
+        .field("Ролі", or_dash(roles), false);
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// [ADMIN] Запустити бустер XP для всього сервера
+#[poise::command(
+    slash_command,
+    rename = "event",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn xp_boost_event(
+    ctx: Context<'_>,
+    #[description = "Множник, напр. 2 або 1.5"]
+    #[min = 1.1]
+    #[max = 10.0]
+    multiplier: f64,
+    #[description = "Тривалість, напр. 48h або 2d"] duration: String,
+    #[description = "Назва події"] name: Option<String>,
+) -> Result<(), Error> {
+    let Some(duration_secs) = parse_duration(&duration).filter(|d| *d > 0) else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Невірна тривалість. Приклади: `48h`, `2d`.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+    let guild_id = ctx.guild_id().map(|g| g.to_string()).unwrap_or_default();
+    let now = Utc::now().timestamp();
+    let boost = ServerBoost {
+        name: name
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| "Подія сервера".to_string()),
+        multiplier: multiplier.clamp(1.1, 10.0),
+        until: now + duration_secs,
+        started_by: ctx.author().id.to_string(),
+    };
+    {
+        let mut boosts = safe_lock(&ctx.data().xp_boosts);
+        let settings = boosts.entry(guild_id).or_default();
+        settings.server_boosts.retain(|b| b.until > now);
+        settings.server_boosts.push(boost.clone());
+        save_json(XP_BOOSTS_FILE, &*boosts);
+    }
+    ctx.say(format!(
+        "🎉 **{}**: {} XP для всіх до <t:{}:f>!",
+        boost.name,
+        format_multiplier(boost.multiplier),
+        boost.until
+    ))
+    .await?;
+    Ok(())
+}
+
+/// [ADMIN] Завершити всі бустери сервера
+#[poise::command(slash_command, rename = "stop", required_permissions = "ADMINISTRATOR")]
+async fn xp_boost_stop(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().map(|g| g.to_string()).unwrap_or_default();
+    let now = Utc::now().timestamp();
+    let stopped = {
+        let mut boosts = safe_lock(&ctx.data().xp_boosts);
+        let settings = boosts.entry(guild_id).or_default();
+        let stopped = settings
+            .server_boosts
+            .iter()
+            .filter(|b| b.until > now)
+            .count();
+        settings.server_boosts.clear();
+        save_json(XP_BOOSTS_FILE, &*boosts);
+        stopped
+    };
+    let text = if stopped == 0 {
+        "ℹ️ Активних подій немає.".to_string()
+    } else {
+        format!("⏹️ Завершено подій: {}.", stopped)
+    };
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// [ADMIN] Множник XP для ролі (1 — прибрати)
+#[poise::command(slash_command, rename = "role", required_permissions = "ADMINISTRATOR")]
+async fn xp_boost_role(
+    ctx: Context<'_>,
+    #[description = "Роль"] role: serenity::Role,
+    #[description = "Множник, напр. 1.25; 1 — прибрати"]
+    #[min = 1.0]
+    #[max = 10.0]
+    multiplier: f64,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().map(|g| g.to_string()).unwrap_or_default();
+    let role_id = role.id.to_string();
+    let text = {
+        let mut boosts = safe_lock(&ctx.data().xp_boosts);
+        let settings = boosts.entry(guild_id).or_default();
+        let text = if multiplier <= 1.0 {
+            settings.role_multipliers.remove(&role_id);
+            format!("🗑️ Множник для <@&{}> прибрано.", role_id)
+        } else {
+            let multiplier = multiplier.min(10.0);
+            settings
+                .role_multipliers
+                .insert(role_id.clone(), multiplier);
+            format!(
+                "✅ Роль <@&{}> дає {} XP.",
+                role_id,
+                format_multiplier(multiplier)
+            )
+        };
+        save_json(XP_BOOSTS_FILE, &*boosts);
+        text
+    };
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// [ADMIN] Як поєднувати множники і максимальний множник
+#[poise::command(
+    slash_command,
+    rename = "stacking",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn xp_boost_stacking(
+    ctx: Context<'_>,
+    #[description = "Правило поєднання"] mode: BoostStacking,
+    #[description = "Максимальний загальний множник"]
+    #[min = 1.0]
+    #[max = 100.0]
+    max: Option<f64>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().map(|g| g.to_string()).unwrap_or_default();
+    let max = {
+        let mut boosts = safe_lock(&ctx.data().xp_boosts);
+        let settings = boosts.entry(guild_id).or_default();
+        settings.stacking = mode;
+        if let Some(max) = max {
+            settings.max_multiplier = max.clamp(1.0, 100.0);
+        }
+        let max = settings.max_multiplier;
+        save_json(XP_BOOSTS_FILE, &*boosts);
+        max
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!(
+                "✅ Множники: {}, максимум {}.",
+                mode.label(),
+                format_multiplier(max)
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// 🎒 Твій інвентар
+#[poise::command(slash_command, guild_only)]
+async fn inventory(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().map(|g| g.to_string()).unwrap_or_default();
+    let user_id = ctx.author().id.to_string();
+    let now = Utc::now().timestamp();
+    let (items, title, boosters) = {
+        let users = safe_lock(&ctx.data().users);
+        match users.get(&user_id) {
+            Some(p) => (
+                p.inventory
+                    .iter()
+                    .filter(|i| i.guild_id == guild_id)
+                    .map(|i| {
+                        format!(
+                            "**{}** ×{} — {}",
+                            i.item.name,
+                            i.quantity,
+                            i.item.kind.label()
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+                p.title.clone(),
+                p.xp_boosters
+                    .iter()
+                    .filter(|b| b.until > now)
+                    .map(|b| format!("🚀 x{} до <t:{}:R>", b.multiplier, b.until))
+                    .collect::<Vec<_>>(),
+            ),
+            None => (Vec::new(), None, Vec::new()),
+        }
+    };
+    let roles: Vec<String> = safe_lock(&ctx.data().role_grants)
+        .iter()
+        .filter(|g| g.guild_id == guild_id && g.user_id == user_id)
+        .map(|g| format!("<@&{}> до <t:{}:R>", g.role_id, g.expires_at))
+        .collect();
+
+    let or_dash = |lines: Vec<String>| {
+        if lines.is_empty() {
+            "—".to_string()
+        } else {
+            lines.join("\n")
+        }
+    };
+    let embed = CreateEmbed::new()
+        .title(format!("🎒 Інвентар {}", ctx.author().name))
+        .color(0x9B59B6)
+        .description(if items.is_empty() {
+            "Порожньо. Заглянь у `/shop list`!".to_string()
+        } else {
+            items.join("\n")
+        })
+        .field("🏷️ Титул", title.unwrap_or_else(|| "—".to_string()), true)
+        .field("🚀 Бустери", or_dash(boosters), true)
+        .field("⏳ Тимчасові ролі", or_dash(roles), false);
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Applies a used item. Role items go through the same role HTTP calls as
+/// the birthday role; on failure the caller returns the item.
+async fn apply_shop_item(
+    ctx: Context<'_>,
+    guild_id: serenity::GuildId,
+    item: &ShopItem,
+) -> Result<String, String> {
+    let user = ctx.author();
+    let user_id = user.id.to_string();
+    let now = Utc::now().timestamp();
+    let role_failed = |e: serenity::Error| {
+        warn!("Не вдалося змінити роль за товар магазину: {}", e);
+        "❌ Не вдалося видати роль. Перевір права бота.".to_string()
+    };
+    let parse_role = |id: &str| {
+        id.parse::<u64>()
+            .map(serenity::RoleId::new)
+            .map_err(|_| "❌ Роль товару недійсна.".to_string())
+    };
+
+    match &item.kind {
+        ShopItemKind::Consumable => Ok(format!("🎟️ <@{}> використав **{}**!", user_id, item.name)),
+        ShopItemKind::XpBooster {
+            multiplier,
+            duration_secs,
+        } => {
+            let mut users = safe_lock(&ctx.data().users);
+            let profile = users
+                .entry(user_id.clone())
+                .or_insert(create_default_profile());
+            let until = profile.add_xp_boost(*multiplier, *duration_secs, now);
+            save_json(USERS_FILE, &*users);
+            Ok(format!(
+                "🚀 <@{}> активував **x{} XP бустер** на {} (діє до <t:{}:f>)!",
+                user_id,
+                multiplier,
+                format_duration_short(*duration_secs),
+                until
+            ))
+        }
+        ShopItemKind::Title { title } => {
+            let mut users = safe_lock(&ctx.data().users);
+            let profile = users
+                .entry(user_id.clone())
+                .or_insert(create_default_profile());
+            profile.title = Some(title.clone());
+            save_json(USERS_FILE, &*users);
+            Ok(format!("🏷️ Тепер твій титул — «{}».", title))
+        }
+        ShopItemKind::Role { role_id } => {
+            let role = parse_role(role_id)?;
+            ctx.http()
+                .add_member_role(guild_id, user.id, role, Some("Покупка в магазині"))
+                .await
+                .map_err(role_failed)?;
+            Ok(format!("🎭 <@{}> отримав роль <@&{}>!", user_id, role_id))
+        }
+        ShopItemKind::ColorRole { role_id } => {
+            let role = parse_role(role_id)?;
+            let other_colors: Vec<serenity::RoleId> = safe_lock(&ctx.data().shop)
+                .get(&guild_id.to_string())
+                .into_iter()
+                .flatten()
+                .filter_map(|i| match &i.kind {
+                    ShopItemKind::ColorRole { role_id } => role_id.parse::<u64>().ok(),
+                    _ => None,
+                })
+                .map(serenity::RoleId::new)
+                .filter(|r| *r != role)
+                .collect();
+            let current = ctx
+                .author_member()
+                .await
+                .map(|m| m.roles.clone())
+                .unwrap_or_default();
+            for old in other_colors.iter().filter(|r| current.contains(r)) {
+                let _ = ctx
+                    .http()
+                    .remove_member_role(guild_id, user.id, *old, Some("Зміна кольору"))
+                    .await;
+            }
+            ctx.http()
+                .add_member_role(guild_id, user.id, role, Some("Покупка в магазині"))
+                .await
+                .map_err(role_failed)?;
+            Ok(format!(
+                "🎨 <@{}> змінив колір на <@&{}>!",
+                user_id, role_id
+            ))
+        }
+        ShopItemKind::TempRole {
+            role_id,
+            duration_secs,
+        } => {
+            let role = parse_role(role_id)?;
+            ctx.http()
+                .add_member_role(guild_id, user.id, role, Some("Покупка в магазині"))
+                .await
+                .map_err(role_failed)?;
+            let expires_at = {
+                let mut grants = safe_lock(&ctx.data().role_grants);
+                let expires_at = extend_role_grant(
+                    &mut grants,
+                    &guild_id.to_string(),
+                    &user_id,
+                    role_id,
+                    &item.name,
+                    *duration_secs,
+                    now,
+                );
+                save_json(ROLE_GRANTS_FILE, &*grants);
+                expires_at
+            };
+            Ok(format!(
+                "⏳ <@{}> отримав роль <@&{}> до <t:{}:f>!",
+                user_id, role_id, expires_at
+            ))
+        }
+    }
+}
+
+/// Adds a temporary role grant, or extends an active one from its current
+/// expiry. Returns the new expiry.
+#[allow(clippy::too_many_arguments)]
+fn extend_role_grant(
+    grants: &mut Vec<RoleGrant>,
+    guild_id: &str,
+    user_id: &str,
+    role_id: &str,
+    item_name: &str,
+    duration_secs: i64,
+    now: i64,
+) -> i64 {
+    match grants
+        .iter_mut()
+        .find(|g| g.guild_id == guild_id && g.user_id == user_id && g.role_id == role_id)
+    {
+        Some(grant) => {
+            grant.expires_at = grant.expires_at.max(now) + duration_secs;
+            grant.item_name = item_name.to_string();
+            grant.duration_secs = duration_secs;
+            grant.reminded = false;
+            grant.expires_at
+        }
+        None => {
+            grants.push(RoleGrant {
+                guild_id: guild_id.to_string(),
+                user_id: user_id.to_string(),
+                role_id: role_id.to_string(),
+                expires_at: now + duration_secs,
+                item_name: item_name.to_string(),
+                duration_secs,
+                reminded: false,
+            });
+            now + duration_secs
+        }
+    }
+}
+
+/// Whether the expiry reminder for a grant is due: a day before the end,
+/// or in the last quarter of the term for roles bought for less than that.
+fn role_grant_reminder_due(grant: &RoleGrant, now: i64) -> bool {
+    let window = if grant.duration_secs > 0 {
+        ROLE_RENEW_REMINDER_SECS.min(grant.duration_secs / 4)
+    } else {
+        ROLE_RENEW_REMINDER_SECS
+    };
+    !grant.reminded && grant.expires_at > now && grant.expires_at - now <= window
+}
+
+/// The shop item a grant can be renewed with: it must still be sold and
+/// still give the same role. Returns the item's index and duration.
+fn renewal_item(items: &[ShopItem], grant: &RoleGrant) -> Option<(usize, i64)> {
+    let idx = find_shop_item(items, &grant.item_name)?;
+    match &items[idx].kind {
+        ShopItemKind::TempRole {
+            role_id,
+            duration_secs,
+        } if *role_id == grant.role_id => Some((idx, *duration_secs)),
+        _ => None,
+    }
+}
+
+/// DMs owners of temporary roles that are about to expire, with a button to
+/// renew at the current shop price.
+async fn remind_role_grants(ctx: &serenity::Context, data: &Data) {
+    let now = Utc::now().timestamp();
+    let due: Vec<RoleGrant> = {
+        let mut grants = safe_lock(&data.role_grants);
+        let due: Vec<RoleGrant> = grants
+            .iter()
+            .filter(|g| role_grant_reminder_due(g, now))
+            .cloned()
+            .collect();
+        if !due.is_empty() {
+            // Позначаємо одразу, щоб не надсилати повторно, навіть якщо DM закриті
+            for grant in grants
+                .iter_mut()
+                .filter(|g| role_grant_reminder_due(g, now))
+            {
+                grant.reminded = true;
+            }
+            save_json(ROLE_GRANTS_FILE, &*grants);
+        }
+        due
+    };
+
+    for grant in due {
+        let Ok(user) = grant.user_id.parse::<u64>() else {
+            continue;
+        };
+        let price = safe_lock(&data.shop)
+            .get(&grant.guild_id)
+            .and_then(|items| {
+                renewal_item(items, &grant).map(|(idx, duration)| (items[idx].price, duration))
+            });
+        let guild_name = grant
+            .guild_id
+            .parse::<u64>()
+            .ok()
+            .and_then(|g| serenity::GuildId::new(g).name(&ctx.cache))
+            .unwrap_or_else(|| "сервер".to_string());
+        let text = format!(
+            "⏳ Твоя роль **{}** на сервері **{}** закінчиться <t:{}:R>.",
+            grant.item_name, guild_name, grant.expires_at
+        );
+        let mut message = serenity::CreateMessage::new();
+        message = match price {
+            Some((price, duration)) => message
+                .content(format!(
+                    "{}\nПродовжити ще на {} за 💰 {}?",
+                    text,
+                    format_duration_short(duration),
+                    price
+                ))
+                .components(vec![serenity::CreateActionRow::Buttons(vec![
+                    CreateButton::new(format!("role_renew:{}:{}", grant.guild_id, grant.role_id))
+                        .label("Продовжити")
+                        .emoji('🔁')
+                        .style(serenity::ButtonStyle::Success),
+                ])]),
+            None => message.content(format!("{}\nЦей товар більше не продається.", text)),
+        };
+        if let Ok(dm) = serenity::UserId::new(user)
+            .create_dm_channel(&ctx.http)
+            .await
+        {
+            if let Err(e) = dm.send_message(&ctx.http, message).await {
+                warn!(
+                    "⚠️ Не вдалося нагадати про роль <@{}>: {}",
+                    grant.user_id, e
+                );
+            }
+        }
+    }
+}
+
+/// Handles the `role_renew:<guild>:<role>` button from an expiry reminder:
+/// charges the current shop price and extends the grant. The role is still
+/// on the member, so no Discord call is needed.
+async fn handle_role_renew_interaction(
+    ctx: &serenity::Context,
+    data: &Data,
+    interaction: &serenity::ComponentInteraction,
+) -> Result<(), Error> {
+    let mut parts = interaction.data.custom_id.splitn(3, ':').skip(1);
+    let (guild_id, role_id) = (
+        parts.next().unwrap_or_default().to_string(),
+        parts.next().unwrap_or_default().to_string(),
+    );
+    let user_id = interaction.user.id.to_string();
+    let now = Utc::now().timestamp();
+
+    // Лок ролей перед локом магазину і користувачів: роль не може зникнути
+    // між перевіркою і продовженням
+    let result: Result<(String, u64, i64), String> = {
+        let mut grants = safe_lock(&data.role_grants);
+        let grant = grants
+            .iter()
+            .find(|g| {
+                g.guild_id == guild_id
+                    && g.user_id == user_id
+                    && g.role_id == role_id
+                    && g.expires_at > now
+            })
+            .cloned();
+        match grant {
+            None => Err("⌛ Термін ролі вже минув — купи її знову через `/shop buy`.".to_string()),
+            Some(grant) => {
+                let mut shops = safe_lock(&data.shop);
+                let items = shops.entry(guild_id.clone()).or_default();
+                match renewal_item(items, &grant) {
+                    None => Err("❌ Цей товар більше не продається.".to_string()),
+                    Some((idx, duration)) => {
+                        let item = &mut items[idx];
+                        let mut users = safe_lock(&data.users);
+                        let profile = users
+                            .entry(user_id.clone())
+                            .or_insert(create_default_profile());
+                        let renewed = charge_for_item(item, profile).map(|_| {
+                            log_transactions(
+                                data,
+                                &[(&user_id, -(item.price as i64))],
+                                "shop",
+                                Some(&item.name),
+                            );
+                            let expires_at = extend_role_grant(
+                                &mut grants,
+                                &guild_id,
+                                &user_id,
+                                &role_id,
+                                &item.name,
+                                duration,
+                                now,
+                            );
+                            (item.name.clone(), item.price, expires_at)
+                        });
+                        if renewed.is_ok() {
+                            save_json(USERS_FILE, &*users);
+                            save_json(SHOP_FILE, &*shops);
+                            save_json(ROLE_GRANTS_FILE, &*grants);
+                        }
+                        renewed
+                    }
+                }
+            }
+        }
+    };
+
+    let response = match result {
+        Ok((name, price, expires_at)) => serenity::CreateInteractionResponse::UpdateMessage(
+            serenity::CreateInteractionResponseMessage::new()
+                .content(format!(
+                    "✅ Роль **{}** продовжено до <t:{}:f>. Списано 💰 {}.",
+                    name, expires_at, price
+                ))
+                .components(vec![]),
+        ),
+        Err(text) => serenity::CreateInteractionResponse::Message(
+            serenity::CreateInteractionResponseMessage::new()
+                .content(text)
+                .ephemeral(true),
+        ),
+    };
+    interaction.create_response(&ctx.http, response).await?;
+    Ok(())
+}
+
+/// Takes temporary shop roles away once they expire. Runs from
+/// `background_tasks`; a grant stays queued if Discord fails transiently.
+async fn expire_role_grants(ctx: &serenity::Context, data: &Data) {
+    let now = Utc::now().timestamp();
+    let due: Vec<RoleGrant> = safe_lock(&data.role_grants)
+        .iter()
+        .filter(|g| g.expires_at <= now)
+        .cloned()
+        .collect();
+
+    for grant in due {
+        let (Ok(guild), Ok(user), Ok(role)) = (
+            grant.guild_id.parse::<u64>(),
+            grant.user_id.parse::<u64>(),
+            grant.role_id.parse::<u64>(),
+        ) else {
+            continue;
+        };
+        let (guild, user, role) = (
+            serenity::GuildId::new(guild),
+            serenity::UserId::new(user),
+            serenity::RoleId::new(role),
+        );
+        // Роль могли продовжити через /use, поки оброблялися попередні
+        if role_grant_extended(data, &grant, now) {
+            continue;
+        }
+        let removed = ctx
+            .http
+            .remove_member_role(guild, user, role, Some("Термін ролі з магазину минув"))
+            .await;
+        let done = match &removed {
+            Ok(()) => true,
+            Err(e) => match discord_error_code(e) {
+                Some(UNKNOWN_MEMBER) | Some(UNKNOWN_ROLE) | Some(UNKNOWN_GUILD) => true,
+                // Роль вища за роль бота або немає права — повтор не допоможе
+                Some(MISSING_PERMISSIONS) => {
+                    warn!(
+                        "⚠️ Немає прав зняти роль {} з <@{}>, тимчасову роль видалено зі списку",
+                        grant.role_id, grant.user_id
+                    );
+                    true
+                }
+                _ => false,
+            },
+        };
+        if !done {
+            continue;
+        }
+        // Продовження між перевіркою і зняттям: повертаємо роль
+        if role_grant_extended(data, &grant, now) {
+            if removed.is_ok() {
+                let _ = ctx
+                    .http
+                    .add_member_role(guild, user, role, Some("Тимчасову роль продовжено"))
+                    .await;
+            }
+            continue;
+        }
+        {
+            let mut grants = safe_lock(&data.role_grants);
+            grants.retain(|g| {
+                !(g.guild_id == grant.guild_id
+                    && g.user_id == grant.user_id
+                    && g.role_id == grant.role_id
+                    && g.expires_at <= now)
+            });
+            save_json(ROLE_GRANTS_FILE, &*grants);
+        }
+        if removed.is_ok() {
+            if let Ok(dm) = user.create_dm_channel(&ctx.http).await {
+                let name = if grant.item_name.is_empty() {
+                    "з магазину"
+                } else {
+                    &grant.item_name
+                };
+                let _ = dm
+                    .say(
+                        &ctx.http,
+                        format!(
+                            "⌛ Термін ролі **{}** минув. Купити знову можна через `/shop buy`.",
+                            name
+                        ),
+                    )
+                    .await;
+            }
+        }
+    }
+}
+
+/// Whether a grant that was due at `now` has since been extended.
+fn role_grant_extended(data: &Data, grant: &RoleGrant, now: i64) -> bool {
+    safe_lock(&data.role_grants).iter().any(|g| {
+        g.guild_id == grant.guild_id
+            && g.user_id == grant.user_id
+            && g.role_id == grant.role_id
+            && g.expires_at > now
+    })
+}
+
+/// 🎒 Використати предмет з інвентаря
+#[poise::command(slash_command, guild_only, rename = "use")]
+async fn use_item(
+    ctx: Context<'_>,
+    #[description = "Назва предмета"]
+    #[autocomplete = "autocomplete_inventory_item"]
+    item: String,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let user_id = ctx.author().id.to_string();
+
+    let taken = {
+        let mut users = safe_lock(&ctx.data().users);
+        let taken = users
+            .get_mut(&user_id)
+            .and_then(|p| p.take_from_inventory(&guild_id.to_string(), &item));
+        if taken.is_some() {
+            save_json(USERS_FILE, &*users);
+        }
+        taken
+    };
+    let Some(taken) = taken else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ У тебе немає такого предмета.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    match apply_shop_item(ctx, guild_id, &taken).await {
+        Ok(text) => {
+            ctx.say(text).await?;
+        }
+        Err(text) => {
+            // Предмет повертається, якщо його не вдалося застосувати
+            {
+                let mut users = safe_lock(&ctx.data().users);
+                let profile = users.entry(user_id).or_insert(create_default_profile());
+                profile.add_to_inventory(&guild_id.to_string(), &taken);
+                save_json(USERS_FILE, &*users);
+            }
+            ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// 🎂 Встановити свій день народження
+#[poise::command(slash_command)]
+async fn set_birthday(ctx: Context<'_>, day: u32, month: u32) -> Result<(), Error> {
+    if NaiveDate::from_ymd_opt(2000, month, day).is_none() {
+        ctx.say("❌ Дата не існує.").await?;
+        return Ok(());
+    }
+    let d = format!("{:02}.{:02}", day, month);
+    {
+        let mut b = safe_lock(&ctx.data().birthdays);
+        b.insert(ctx.author().id.to_string(), d.clone());
+        save_json(BIRTHDAY_FILE, &*b);
+    }
+    ctx.say(format!("✅ ДН встановлено: {}", d)).await?;
+    Ok(())
+}
+
+/// 📅 Переглянути список днів народження
+#[poise::command(slash_command)]
+async fn birthdays(ctx: Context<'_>) -> Result<(), Error> {
+    let birthdays_data = {
+        let b = safe_lock(&ctx.data().birthdays);
+        b.clone()
+    };
+
+    if birthdays_data.is_empty() {
+        ctx.say("📅 Поки що немає збережених днів народження.")
+            .await?;
+        return Ok(());
+    }
+
+    // Сортуємо дні народження по даті (місяць.день)
+    let mut sorted: Vec<_> = birthdays_data.iter().collect();
+    sorted.sort_by(|a, b| {
+        let parse_date = |s: &str| -> (u32, u32) {
+            let parts: Vec<&str> = s.split('.').collect();
+            if parts.len() == 2 {
+                let day = parts[0].parse::<u32>().unwrap_or(0);
+                let month = parts[1].parse::<u32>().unwrap_or(0);
+                (month, day)
+            } else {
+                (0, 0)
+            }
+        };
+
+        let (month_a, day_a) = parse_date(a.1);
+        let (month_b, day_b) = parse_date(b.1);
+
+        month_a.cmp(&month_b).then(day_a.cmp(&day_b))
+    });
+
+    let mut description = String::new();
+    let months = [
+        "Січ", "Лют", "Бер", "Кві", "Тра", "Чер", "Лип", "Сер", "Вер", "Жов", "Лис", "Гру",
+    ];
+    let total_count = sorted.len();
+
+    for (user_id, date) in &sorted {
+        let parts: Vec<&str> = date.split('.').collect();
+        if parts.len() == 2 {
+            let day = parts[0];
+            let month_num = parts[1].parse::<usize>().unwrap_or(1);
+            let month_name = if month_num > 0 {
+                months.get(month_num - 1).unwrap_or(&"???")
+            } else {
+                &"???"
+            };
+            description.push_str(&format!("🎂 **{} {}** — <@{}>\n", day, month_name, user_id));
+        }
+    }
 
-/// 📚 Показати всі доступні команди
-#[poise::command(slash_command)]
-async fn help(ctx: Context<'_>) -> Result<(), Error> {
     let embed = CreateEmbed::new()
-        .title("📚 Довідка по боту StarostaBot")
-        .description("**Привіт! Я — твій сільський помічник з автоматичним управлінням! 🌾**")
-        .color(0x2ECC71)
-        .field(
-            "👤 **Профіль і прогрес**",
-            "`/rank` — Твоя картка з рівнем і XP\n\
-             `/leaderboard` — Топ учасників сервера\n\
-             `/daily` — Отримай щоденну винагороду",
-            false,
+        .title("📅 Календар днів народження")
+        .description(description)
+        .color(0xFF69B4)
+        .footer(CreateEmbedFooter::new(format!(
+            "Всього іменинників: {}",
+            total_count
+        )));
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// [ADMIN] Додати день народження користувачу
+#[poise::command(
+    slash_command,
+    default_member_permissions = "ADMINISTRATOR",
+    rename = "admin_add_birthday"
+)]
+async fn admin_add_birthday(
+    ctx: Context<'_>,
+    user: serenity::User,
+    day: u32,
+    month: u32,
+) -> Result<(), Error> {
+    if NaiveDate::from_ymd_opt(2000, month, day).is_none() {
+        ctx.say("❌ Дата не існує.").await?;
+        return Ok(());
+    }
+
+    let date = format!("{:02}.{:02}", day, month);
+    {
+        let mut b = safe_lock(&ctx.data().birthdays);
+        b.insert(user.id.to_string(), date.clone());
+        save_json(BIRTHDAY_FILE, &*b);
+    }
+
+    ctx.say(format!(
+        "✅ Адмін встановив ДН **{}** для користувача <@{}>",
+        date, user.id
+    ))
+    .await?;
+    Ok(())
+}
+
+/// [ADMIN] Видалити день народження користувача
+#[poise::command(
+    slash_command,
+    default_member_permissions = "ADMINISTRATOR",
+    rename = "admin_remove_birthday"
+)]
+async fn admin_remove_birthday(ctx: Context<'_>, user: serenity::User) -> Result<(), Error> {
+    let removed = {
+        let mut b = safe_lock(&ctx.data().birthdays);
+        let result = b.remove(&user.id.to_string());
+        if result.is_some() {
+            save_json(BIRTHDAY_FILE, &*b);
+        }
+        result.is_some()
+    };
+
+    if removed {
+        ctx.say(format!(
+            "✅ День народження користувача <@{}> видалено!",
+            user.id
+        ))
+        .await?;
+    } else {
+        ctx.say(format!(
+            "❌ У користувача <@{}> немає збереженого дня народження.",
+            user.id
+        ))
+        .await?;
+    }
+    Ok(())
+}
+
+// --- СИСТЕМА ТІКЕТІВ ---
+
+// --- СИСТЕМА ІДЕЙ ---
+
+/// 💡 [ADMIN] Встановити канал для ідей
+#[poise::command(
+    slash_command,
+    default_member_permissions = "ADMINISTRATOR",
+    rename = "suggest"
+)]
+async fn setup_suggestions_channel(
+    ctx: Context<'_>,
+    #[description = "Приховувати авторів ідей (персонал їх бачить)"] anonymous: Option<bool>,
+) -> Result<(), Error> {
+    let channel_id = ctx.channel_id().to_string();
+
+    {
+        let mut channels = safe_lock(&ctx.data().suggestions_channels);
+        if !channels.contains(&channel_id) {
+            channels.push(channel_id.clone());
+            save_json(SUGGESTIONS_CHANNELS_FILE, &*channels);
+        }
+    }
+    let anonymous = {
+        let mut configs = safe_lock(&ctx.data().suggestions_config);
+        match anonymous {
+            Some(a) => {
+                configs.entry(channel_id.clone()).or_default().anonymous = a;
+                save_json(SUGGESTIONS_CONFIG_FILE, &*configs);
+                a
+            }
+            None => configs.get(&channel_id).is_some_and(|c| c.anonymous),
+        }
+    };
+
+    ctx.send(poise::CreateReply::default()
+        .content(format!(
+            "✅ Цей канал тепер використовується для ідей!\nУсі повідомлення будуть автоматично перетворюватися на ідеї.{}",
+            if anonymous { "\n🕶️ Анонімний режим: автори приховані." } else { "" }
+        ))
+        .ephemeral(true)).await?;
+
+    Ok(())
+}
+
+/// 🚫 [ADMIN] Відключити канал для ідей
+#[poise::command(
+    slash_command,
+    default_member_permissions = "ADMINISTRATOR",
+    rename = "unsuggest"
+)]
+async fn remove_suggestions_channel(ctx: Context<'_>) -> Result<(), Error> {
+    let channel_id = ctx.channel_id().to_string();
+
+    let removed = {
+        let mut channels = safe_lock(&ctx.data().suggestions_channels);
+        let initial_len = channels.len();
+        channels.retain(|c| c != &channel_id);
+        let removed = initial_len != channels.len();
+        if removed {
+            save_json(SUGGESTIONS_CHANNELS_FILE, &*channels);
+        }
+        removed
+    };
+
+    if removed {
+        ctx.defer_ephemeral().await?;
+
+        // Ідеї каналу архівуються: голосування зупиняється, дані лишаються
+        // для історії, а `/suggest` + `/idea rebuild` їх відновлять.
+        let archived: Vec<String> = {
+            let mut suggestions = safe_lock(&ctx.data().suggestions_data);
+            let ids: Vec<String> = suggestions
+                .values_mut()
+                .filter(|s| s.channel_id == channel_id && !s.archived)
+                .map(|s| {
+                    s.archived = true;
+                    s.message_id.clone()
+                })
+                .collect();
+            if !ids.is_empty() {
+                save_json(SUGGESTIONS_DATA_FILE, &*suggestions);
+            }
+            ids
+        };
+        for id in &archived {
+            if let Ok(msg) = id.parse::<u64>() {
+                let _ = ctx
+                    .channel_id()
+                    .edit_message(
+                        ctx.http(),
+                        serenity::MessageId::new(msg),
+                        serenity::EditMessage::new().components(vec![]),
+                    )
+                    .await;
+            }
+        }
+
+        ctx.send(
+            poise::CreateReply::default()
+                .content(format!(
+                    "✅ Цей канал більше не використовується для ідей.\nАрхівовано ідей: **{}**.",
+                    archived.len()
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+    } else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Цей канал не був налаштований для ідей.")
+                .ephemeral(true),
         )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Stable upload name for the `index`-th attachment of an idea, so the
+/// embed can reference it as `attachment://<name>`.
+fn idea_attachment_name(index: usize, original: &str) -> String {
+    let ext: String = original
+        .rsplit_once('.')
+        .map(|(_, e)| e.chars().filter(|c| c.is_ascii_alphanumeric()).collect())
+        .unwrap_or_default();
+    if ext.is_empty() {
+        format!("idea_{}", index)
+    } else {
+        format!("idea_{}.{}", index, ext.to_lowercase())
+    }
+}
+
+fn is_image_filename(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    [".png", ".jpg", ".jpeg", ".gif", ".webp"]
+        .iter()
+        .any(|ext| lower.ends_with(ext))
+}
+
+/// Records the CDN links of the files the bot uploaded with an idea.
+fn idea_attachments_from(message: &serenity::Message) -> Vec<IdeaAttachment> {
+    message
+        .attachments
+        .iter()
+        .map(|a| IdeaAttachment {
+            filename: a.filename.clone(),
+            url: a.url.clone(),
+        })
+        .collect()
+}
+
+/// Theme of the suggestion channel, or the default one.
+fn suggestion_theme(data: &Data, channel_id: &str) -> SuggestionTheme {
+    safe_lock(&data.suggestions_config)
+        .get(channel_id)
+        .map(|c| c.theme.clone())
+        .unwrap_or_default()
+}
+
+/// The single renderer for the public embed of an idea.
+fn render_suggestion_embed(suggestion: &SuggestionData, theme: &SuggestionTheme) -> CreateEmbed {
+    let mut status_text = suggestion.status.label().to_string();
+    if let Some(original) = &suggestion.duplicate_of {
+        status_text.push_str(&format!("\nОригінал: {}", original));
+    }
+
+    let mut embed = CreateEmbed::new()
+        .title(theme.title_for(suggestion.public_author()))
+        .description(format!("**Ідея**\n{}", suggestion.content))
+        .color(theme.color(suggestion.status))
         .field(
-            "🎰 **Розваги**",
-            "`/casino <сума>` — Випробуй удачу!\n\
-             `/blackjack <ставка>` — Зіграй в блекджек\n\
-             `/poll <питання>` — Створи голосування",
+            format!(
+                "За: {} | Проти: {} | Процентів за: {}%",
+                suggestion.votes_for(),
+                suggestion.votes_against(),
+                suggestion.approval_percent()
+            ),
+            "",
             false,
         )
-        .field(
-            "🎂 **Дні народження**",
-            "`/set_birthday <день> <місяць>` — Вкажи свій ДН\n\
-             `/birthdays` — Календар іменинників",
-            false,
+        .field("Статус", status_text, false);
+    if let Some(response) = &suggestion.staff_response {
+        embed = embed.field("💬 Відповідь команди", response, false);
+    }
+    if let Some(image) = suggestion
+        .attachments
+        .iter()
+        .find(|a| is_image_filename(&a.filename))
+    {
+        embed = embed.image(format!("attachment://{}", image.filename));
+    }
+    embed.footer(CreateEmbedFooter::new(theme.footer()))
+}
+
+fn suggestion_embed(data: &Data, suggestion: &SuggestionData) -> CreateEmbed {
+    render_suggestion_embed(suggestion, &suggestion_theme(data, &suggestion.channel_id))
+}
+
+fn suggestion_buttons(theme: &SuggestionTheme, timestamp: i64) -> Vec<CreateActionRow> {
+    vec![
+        CreateActionRow::Buttons(vec![
+            CreateButton::new(format!("idea_like_{}", timestamp))
+                .label(theme.like_label())
+                .style(ButtonStyle::Success)
+                .emoji('👍'),
+            CreateButton::new(format!("idea_dislike_{}", timestamp))
+                .label(theme.dislike_label())
+                .style(ButtonStyle::Danger)
+                .emoji('👎'),
+        ]),
+        CreateActionRow::Buttons(vec![
+            CreateButton::new(format!("idea_approve_{}", timestamp))
+                .label("Прийняти ідею")
+                .style(ButtonStyle::Primary)
+                .emoji('✅'),
+            CreateButton::new(format!("idea_reject_{}", timestamp))
+                .label("Відхилити ідею")
+                .style(ButtonStyle::Danger)
+                .emoji('❌'),
+            CreateButton::new(format!("idea_edit_{}", timestamp))
+                .label("Змінити")
+                .style(ButtonStyle::Secondary)
+                .emoji('✏'),
+        ]),
+    ]
+}
+
+fn suggestion_link(guild_id: Option<serenity::GuildId>, suggestion: &SuggestionData) -> String {
+    format!(
+        "https://discord.com/channels/{}/{}/{}",
+        guild_id.map(|g| g.to_string()).unwrap_or("@me".to_string()),
+        suggestion.channel_id,
+        suggestion.message_id
+    )
+}
+
+/// Whether the idea stored under `msg_id` was posted in `guild_id`. Ideas
+/// are keyed by a global message id, so guild commands check this first.
+async fn suggestion_in_guild(
+    ctx: &serenity::Context,
+    data: &Data,
+    msg_id: &str,
+    guild_id: serenity::GuildId,
+) -> bool {
+    let Some(channel) = safe_lock(&data.suggestions_data)
+        .get(msg_id)
+        .and_then(|s| s.channel_id.parse::<u64>().ok())
+    else {
+        return false;
+    };
+    let channel = ChannelId::new(channel);
+    if let Some(g) = guild_of_channel(ctx, channel) {
+        return g == guild_id;
+    }
+    channel
+        .to_channel(ctx)
+        .await
+        .ok()
+        .and_then(|c| c.guild())
+        .is_some_and(|c| c.guild_id == guild_id)
+}
+
+/// Changes the status of an idea, refreshes its message and tells the author
+/// by DM. Returns `None` if the idea is unknown or belongs to another server
+/// than `guild_id`.
+#[allow(clippy::too_many_arguments)]
+async fn change_suggestion_status(
+    ctx: &serenity::Context,
+    data: &Data,
+    msg_id: &str,
+    guild_id: Option<serenity::GuildId>,
+    status: SuggestionStatus,
+    actor: &serenity::User,
+    response: Option<String>,
+    duplicate_of: Option<String>,
+) -> Option<SuggestionData> {
+    if let Some(g) = guild_id {
+        if !suggestion_in_guild(ctx, data, msg_id, g).await {
+            return None;
+        }
+    }
+    let suggestion = {
+        let mut suggestions = safe_lock(&data.suggestions_data);
+        let s = suggestions.get_mut(msg_id)?;
+        s.set_status(
+            status,
+            (&actor.id.to_string(), &actor.name),
+            response,
+            duplicate_of,
+            Utc::now().timestamp(),
+        );
+        let snap = s.clone();
+        save_json(SUGGESTIONS_DATA_FILE, &*suggestions);
+        snap
+    };
+
+    if let (Ok(channel_num), Ok(msg_num)) = (
+        suggestion.channel_id.parse::<u64>(),
+        suggestion.message_id.parse::<u64>(),
+    ) {
+        let components = if status.is_open() {
+            suggestion_buttons(
+                &suggestion_theme(data, &suggestion.channel_id),
+                suggestion.timestamp,
+            )
+        } else {
+            vec![]
+        };
+        let _ = ChannelId::new(channel_num)
+            .edit_message(
+                &ctx.http,
+                serenity::MessageId::new(msg_num),
+                serenity::EditMessage::new()
+                    .embed(suggestion_embed(data, &suggestion))
+                    .components(components),
+            )
+            .await;
+    }
+
+    // Повідомляємо автора, якщо статус змінив не він сам
+    if suggestion.author_id != actor.id.to_string() {
+        if let Ok(author) = suggestion.author_id.parse::<u64>() {
+            let preview: String = suggestion.content.chars().take(200).collect();
+            let mut embed = CreateEmbed::new()
+                .title("💡 Статус вашої ідеї змінено")
+                .description(format!(
+                    "{}\n\n**Ідея:** {}\n[Перейти до ідеї]({})",
+                    status.label(),
+                    preview,
+                    suggestion_link(guild_id, &suggestion)
+                ))
+                .color(status.color());
+            if let Some(response) = &suggestion.staff_response {
+                embed = embed.field("💬 Відповідь команди", response, false);
+            }
+            if let Ok(dm) = serenity::UserId::new(author)
+                .create_dm_channel(&ctx.http)
+                .await
+            {
+                let _ = dm
+                    .send_message(&ctx.http, CreateMessage::new().embed(embed))
+                    .await;
+            }
+        }
+    }
+
+    Some(suggestion)
+}
+
+/// 💡 Керування ідеями
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands(
+        "idea_status",
+        "idea_withdraw",
+        "idea_history",
+        "idea_rules",
+        "idea_theme",
+        "idea_repost",
+        "idea_rebuild"
+    )
+)]
+async fn idea(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// [ADMIN] Змінити статус ідеї з відповіддю
+#[poise::command(
+    slash_command,
+    rename = "status",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn idea_status(
+    ctx: Context<'_>,
+    #[description = "Посилання на ідею або ID повідомлення"] idea: String,
+    #[description = "Новий статус"] status: SuggestionStatus,
+    #[description = "Відповідь команди (показується на ідеї)"] response: Option<String>,
+    #[description = "Посилання на оригінал (для дублікатів)"] original: Option<String>,
+) -> Result<(), Error> {
+    if status == SuggestionStatus::Duplicate && original.is_none() {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Для дубліката вкажіть посилання на оригінальну ідею.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let key = message_id_from_input(&idea);
+    let changed = change_suggestion_status(
+        ctx.serenity_context(),
+        ctx.data(),
+        &key,
+        ctx.guild_id(),
+        status,
+        ctx.author(),
+        response,
+        original,
+    )
+    .await;
+
+    let text = match changed {
+        Some(_) => format!("✅ Новий статус: {}", status.label()),
+        None => "❌ Ідею не знайдено.".to_string(),
+    };
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Відкликати власну ідею
+#[poise::command(slash_command, rename = "withdraw")]
+async fn idea_withdraw(
+    ctx: Context<'_>,
+    #[description = "Посилання на ідею або ID повідомлення"] idea: String,
+) -> Result<(), Error> {
+    let key = message_id_from_input(&idea);
+    let author_id = ctx.author().id.to_string();
+    let own = safe_lock(&ctx.data().suggestions_data)
+        .get(&key)
+        .map(|s| s.author_id == author_id && s.status.is_open());
+
+    let text = match own {
+        None => "❌ Ідею не знайдено.",
+        Some(false) => "❌ Відкликати можна лише власну ідею, яка ще на розгляді.",
+        Some(true) => match change_suggestion_status(
+            ctx.serenity_context(),
+            ctx.data(),
+            &key,
+            ctx.guild_id(),
+            SuggestionStatus::Withdrawn,
+            ctx.author(),
+            None,
+            None,
+        )
+        .await
+        {
+            Some(_) => "🗑️ Ідею відкликано.",
+            None => "❌ Ідею не знайдено.",
+        },
+    };
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Історія статусів ідеї
+#[poise::command(slash_command, rename = "history")]
+async fn idea_history(
+    ctx: Context<'_>,
+    #[description = "Посилання на ідею або ID повідомлення"] idea: String,
+) -> Result<(), Error> {
+    let key = message_id_from_input(&idea);
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?;
+    let in_guild = suggestion_in_guild(ctx.serenity_context(), ctx.data(), &key, guild_id).await;
+    let found = safe_lock(&ctx.data().suggestions_data)
+        .get(&key)
+        .cloned()
+        .filter(|_| in_guild);
+    let Some(suggestion) = found else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Ідею не знайдено.")
+                .ephemeral(true),
         )
-        .field(
-            "🛒 **Магазин і бустери**",
-            "`/shop` — Магазин бустерів XP\n\
-             `/buy_booster <тип>` — Купити бустер (x2 або x5)",
-            false,
+        .await?;
+        return Ok(());
+    };
+
+    // Права перевіряються в тому ж сервері, якому належить ідея
+    let is_staff = ctx
+        .author_member()
+        .await
+        .and_then(|m| m.permissions)
+        .map(|p| p.administrator() || p.manage_guild())
+        .unwrap_or(false);
+    let description: String = suggestion
+        .history_lines(&ctx.author().id.to_string(), is_staff)
+        .join("\n")
+        .chars()
+        .take(4000)
+        .collect();
+
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .title("📜 Історія ідеї")
+                    .description(description)
+                    .color(suggestion.status.color()),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Drops every idea posted in `channel_id`. Returns how many were removed.
+fn remove_channel_ideas(ideas: &mut HashMap<String, SuggestionData>, channel_id: &str) -> usize {
+    let before = ideas.len();
+    ideas.retain(|_, s| s.channel_id != channel_id);
+    before - ideas.len()
+}
+
+/// Marks the idea posted as `message_id` as archived (its message is gone).
+fn archive_suggestion(data: &Data, message_id: &str) -> bool {
+    let mut suggestions = safe_lock(&data.suggestions_data);
+    match suggestions.get_mut(message_id) {
+        Some(s) if !s.archived => {
+            s.archived = true;
+            save_json(SUGGESTIONS_DATA_FILE, &*suggestions);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Forgets a deleted suggestion channel together with its ideas and rules.
+fn forget_suggestion_channel(data: &Data, channel_id: &str) -> usize {
+    let removed = {
+        let mut suggestions = safe_lock(&data.suggestions_data);
+        let removed = remove_channel_ideas(&mut suggestions, channel_id);
+        if removed > 0 {
+            save_json(SUGGESTIONS_DATA_FILE, &*suggestions);
+        }
+        removed
+    };
+    {
+        let mut channels = safe_lock(&data.suggestions_channels);
+        let before = channels.len();
+        channels.retain(|c| c != channel_id);
+        if channels.len() != before {
+            save_json(SUGGESTIONS_CHANNELS_FILE, &*channels);
+        }
+    }
+    {
+        let mut configs = safe_lock(&data.suggestions_config);
+        if configs.remove(channel_id).is_some() {
+            save_json(SUGGESTIONS_CONFIG_FILE, &*configs);
+        }
+    }
+    removed
+}
+
+/// Discord JSON error code of a failed HTTP request (e.g. 10008 Unknown Message).
+fn discord_error_code(e: &serenity::Error) -> Option<isize> {
+    match e {
+        serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(resp)) => {
+            Some(resp.error.code)
+        }
+        _ => None,
+    }
+}
+
+const UNKNOWN_CHANNEL: isize = 10003;
+const UNKNOWN_GUILD: isize = 10004;
+const UNKNOWN_MEMBER: isize = 10007;
+const UNKNOWN_MESSAGE: isize = 10008;
+const UNKNOWN_ROLE: isize = 10011;
+const MISSING_PERMISSIONS: isize = 50013;
+
+/// Checks every live idea against Discord once at startup: ideas whose
+/// message was deleted while the bot was offline are archived, ideas from
+/// deleted channels are removed.
+async fn reconcile_suggestions(ctx: &serenity::Context, data: &Data) {
+    let ideas: Vec<(String, String)> = safe_lock(&data.suggestions_data)
+        .values()
+        .filter(|s| !s.archived)
+        .map(|s| (s.channel_id.clone(), s.message_id.clone()))
+        .collect();
+
+    let mut archived = 0;
+    let mut gone_channels: Vec<String> = Vec::new();
+    for (channel_id, message_id) in ideas {
+        if gone_channels.contains(&channel_id) {
+            continue;
+        }
+        let (Ok(c), Ok(m)) = (channel_id.parse::<u64>(), message_id.parse::<u64>()) else {
+            continue;
+        };
+        if let Err(e) = ChannelId::new(c)
+            .message(&ctx.http, serenity::MessageId::new(m))
+            .await
+        {
+            match discord_error_code(&e) {
+                Some(UNKNOWN_MESSAGE) if archive_suggestion(data, &message_id) => archived += 1,
+                Some(UNKNOWN_CHANNEL) => gone_channels.push(channel_id),
+                _ => {}
+            }
+        }
+    }
+
+    let mut removed = 0;
+    for channel_id in &gone_channels {
+        removed += forget_suggestion_channel(data, channel_id);
+    }
+    if archived > 0 || removed > 0 {
+        info!(
+            "💡 Звірка ідей: архівовано {}, видалено {} (каналів: {})",
+            archived,
+            removed,
+            gone_channels.len()
+        );
+    }
+}
+
+/// Sends the idea as a new message, re-keys the record to it and removes the
+/// old message if it still exists.
+async fn repost_suggestion(
+    ctx: &serenity::Context,
+    data: &Data,
+    message_id: &str,
+) -> Result<SuggestionData, Error> {
+    let suggestion = safe_lock(&data.suggestions_data)
+        .get(message_id)
+        .cloned()
+        .ok_or("Ідею не знайдено")?;
+    let channel = ChannelId::new(suggestion.channel_id.parse()?);
+    let theme = suggestion_theme(data, &suggestion.channel_id);
+
+    let mut message = CreateMessage::new().embed(render_suggestion_embed(&suggestion, &theme));
+    if suggestion.status.is_open() {
+        message = message.components(suggestion_buttons(&theme, suggestion.timestamp));
+    }
+    for a in &suggestion.attachments {
+        match CreateAttachment::url(&ctx.http, &a.url).await {
+            Ok(mut file) => {
+                file.filename = a.filename.clone();
+                message = message.add_file(file);
+            }
+            Err(e) => warn!("⚠️ Не вдалося завантажити вкладення {}: {}", a.filename, e),
+        }
+    }
+    let sent = channel.send_message(&ctx.http, message).await?;
+
+    if let Ok(old) = message_id.parse::<u64>() {
+        let _ = channel
+            .delete_message(&ctx.http, serenity::MessageId::new(old))
+            .await;
+    }
+
+    let mut suggestions = safe_lock(&data.suggestions_data);
+    let mut moved = suggestions.remove(message_id).unwrap_or(suggestion);
+    moved.message_id = sent.id.to_string();
+    moved.attachments = idea_attachments_from(&sent);
+    moved.archived = false;
+    suggestions.insert(moved.message_id.clone(), moved.clone());
+    save_json(SUGGESTIONS_DATA_FILE, &*suggestions);
+    Ok(moved)
+}
+
+/// [ADMIN] Надіслати ідею заново
+#[poise::command(
+    slash_command,
+    rename = "repost",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn idea_repost(
+    ctx: Context<'_>,
+    #[description = "Посилання на ідею або ID повідомлення"] idea: String,
+) -> Result<(), Error> {
+    let key = message_id_from_input(&idea);
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?;
+    if !suggestion_in_guild(ctx.serenity_context(), ctx.data(), &key, guild_id).await {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Ідею не знайдено.")
+                .ephemeral(true),
         )
-        .field(
-            "💬 **Комунікація**",
-            "`/suggest <ідея>` — Запропонувати ідею",
-            false,
+        .await?;
+        return Ok(());
+    }
+    let text = match repost_suggestion(ctx.serenity_context(), ctx.data(), &key).await {
+        Ok(s) => format!(
+            "✅ Ідею надіслано заново: {}",
+            suggestion_link(ctx.guild_id(), &s)
+        ),
+        Err(e) => format!("❌ Не вдалося надіслати ідею: {}", e),
+    };
+    ctx.send(poise::CreateReply::default().content(text).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// [ADMIN] Перебудувати embed-и ідей каналу
+#[poise::command(
+    slash_command,
+    rename = "rebuild",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn idea_rebuild(
+    ctx: Context<'_>,
+    #[description = "Канал ідей (за замовчуванням — поточний)"] channel: Option<ChannelId>,
+    #[description = "Надіслати заново ідеї, чиї повідомлення видалено"] repost_missing: Option<
+        bool,
+    >,
+) -> Result<(), Error> {
+    let channel = channel.unwrap_or(ctx.channel_id());
+    let channel_key = channel.to_string();
+    ctx.defer_ephemeral().await?;
+
+    let ideas: Vec<SuggestionData> = safe_lock(&ctx.data().suggestions_data)
+        .values()
+        .filter(|s| s.channel_id == channel_key)
+        .cloned()
+        .collect();
+    let theme = suggestion_theme(ctx.data(), &channel_key);
+
+    let (mut rebuilt, mut reposted, mut archived) = (0, 0, 0);
+    for idea in ideas {
+        let Ok(msg) = idea.message_id.parse::<u64>() else {
+            continue;
+        };
+        let components = if idea.status.is_open() {
+            suggestion_buttons(&theme, idea.timestamp)
+        } else {
+            vec![]
+        };
+        let result = channel
+            .edit_message(
+                ctx.http(),
+                serenity::MessageId::new(msg),
+                serenity::EditMessage::new()
+                    .embed(render_suggestion_embed(&idea, &theme))
+                    .components(components),
+            )
+            .await;
+        match result {
+            Ok(_) => {
+                rebuilt += 1;
+                if idea.archived {
+                    let mut suggestions = safe_lock(&ctx.data().suggestions_data);
+                    if let Some(s) = suggestions.get_mut(&idea.message_id) {
+                        s.archived = false;
+                        save_json(SUGGESTIONS_DATA_FILE, &*suggestions);
+                    }
+                }
+            }
+            Err(e) if discord_error_code(&e) == Some(UNKNOWN_MESSAGE) => {
+                if repost_missing.unwrap_or(false) {
+                    if repost_suggestion(ctx.serenity_context(), ctx.data(), &idea.message_id)
+                        .await
+                        .is_ok()
+                    {
+                        reposted += 1;
+                    }
+                } else if archive_suggestion(ctx.data(), &idea.message_id) {
+                    archived += 1;
+                }
+            }
+            Err(e) => warn!("Не вдалося оновити ідею {}: {}", idea.message_id, e),
+        }
+    }
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!(
+                "🔧 Оновлено: **{}**, надіслано заново: **{}**, архівовано: **{}**.",
+                rebuilt, reposted, archived
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// [ADMIN] Оформлення ідей у цьому каналі
+#[poise::command(
+    slash_command,
+    rename = "theme",
+    required_permissions = "ADMINISTRATOR"
+)]
+#[allow(clippy::too_many_arguments)]
+async fn idea_theme(
+    ctx: Context<'_>,
+    #[description = "Заголовок; {author} — ім'я автора"] title: Option<String>,
+    #[description = "Статус, для якого змінити колір"] status: Option<SuggestionStatus>,
+    #[description = "Колір для статусу, напр. #2ECC71"] color: Option<String>,
+    #[description = "Текст кнопки «за»"]
+    #[max_length = 80]
+    like_label: Option<String>,
+    #[description = "Текст кнопки «проти»"]
+    #[max_length = 80]
+    dislike_label: Option<String>,
+    #[description = "Текст футера"] footer: Option<String>,
+    #[description = "Повернути стандартне оформлення"] reset: Option<bool>,
+) -> Result<(), Error> {
+    let channel_id = ctx.channel_id().to_string();
+    if !safe_lock(&ctx.data().suggestions_channels).contains(&channel_id) {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Цей канал не налаштований для ідей (`/suggest`).")
+                .ephemeral(true),
         )
-        .field(
-            "🛠️ **Утиліти**",
-            "`/avatar [@користувач]` — Показати аватар\n\
-             `/info` — Інформація про бота",
-            false,
+        .await?;
+        return Ok(());
+    }
+
+    let color = match color.as_deref().map(parse_hex_color) {
+        Some(None) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content("❌ Невірний колір. Приклад: `#2ECC71`.")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+        Some(Some(_)) if status.is_none() => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content("❌ Вкажіть статус, для якого змінюється колір.")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+        other => other.flatten(),
+    };
+
+    let theme = {
+        let mut configs = safe_lock(&ctx.data().suggestions_config);
+        let cfg = configs.entry(channel_id).or_default();
+        if reset.unwrap_or(false) {
+            cfg.theme = SuggestionTheme::default();
+        }
+        let non_empty = |v: String| Some(v.trim().to_string()).filter(|v| !v.is_empty());
+        if let Some(t) = title {
+            cfg.theme.title = non_empty(t);
+        }
+        if let (Some(st), Some(c)) = (status, color) {
+            cfg.theme.colors.insert(st, c);
+        }
+        if let Some(l) = like_label {
+            cfg.theme.like_label = non_empty(l);
+        }
+        if let Some(l) = dislike_label {
+            cfg.theme.dislike_label = non_empty(l);
+        }
+        if let Some(f) = footer {
+            cfg.theme.footer = non_empty(f);
+        }
+        let snap = cfg.theme.clone();
+        save_json(SUGGESTIONS_CONFIG_FILE, &*configs);
+        snap
+    };
+
+    // Попередній перегляд на прикладі ідеї
+    let sample = SuggestionData {
+        author_name: ctx.author().name.clone(),
+        content: "Приклад ідеї".to_string(),
+        ..Default::default()
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .content("✅ Оформлення збережено. Так виглядатимуть нові ідеї:")
+            .embed(render_suggestion_embed(&sample, &theme))
+            .components(
+                suggestion_buttons(&theme, 0)
+                    .into_iter()
+                    .take(1)
+                    .collect::<Vec<_>>(),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// [ADMIN] Правила авто-модерації ідей у цьому каналі
+#[poise::command(
+    slash_command,
+    rename = "rules",
+    required_permissions = "ADMINISTRATOR"
+)]
+#[allow(clippy::too_many_arguments)]
+async fn idea_rules(
+    ctx: Context<'_>,
+    #[description = "Канал персоналу для позначок і нагадувань"] staff_channel: Option<ChannelId>,
+    #[description = "Поріг: чистих голосів «за»"] net_votes: Option<i64>,
+    #[description = "Поріг: відсоток схвалення"]
+    #[max = 100]
+    percent: Option<u32>,
+    #[description = "Мінімум голосів для відсоткових правил"] min_voters: Option<u32>,
+    #[description = "Ставити статус «Популярна» замість позначки персоналу"] auto_popular: Option<
+        bool,
+    >,
+    #[description = "Закривати, якщо схвалення не вище X%"]
+    #[max = 100]
+    negative_percent: Option<u32>,
+    #[description = "…але не раніше ніж через стільки годин"] negative_hours: Option<u32>,
+    #[description = "Нагадувати про ідеї без рішення старші за N днів"] stale_days: Option<u32>,
+    #[description = "Скинути всі правила каналу"] reset: Option<bool>,
+) -> Result<(), Error> {
+    let channel_id = ctx.channel_id().to_string();
+    if !safe_lock(&ctx.data().suggestions_channels).contains(&channel_id) {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Цей канал не налаштований для ідей (`/suggest`).")
+                .ephemeral(true),
         )
-        .field(
-            "👮 **Адмін: Основне**",
-            "`/setup_roles` — Налаштувати ролі\n\
-             `/admin_set_level/xp/chips` — Встановити рівень/XP/гривні\n\
-             `/admin_mute/unmute` — Мут/розмут (текст/голос/всюди)\n\
-             `/admin_add/remove_birthday` — Керувати ДН\n\
-             `/suggest` — Встановлення каналу для ідей\n\
-             `/purge` — Видалити повідомлення\n\
-             `/clean` — Видалити повідомлення бота\n\
-             `/admin_announce` — Оголошення",
-            false,
+        .await?;
+        return Ok(());
+    }
+
+    let cfg = {
+        let mut configs = safe_lock(&ctx.data().suggestions_config);
+        if reset.unwrap_or(false) {
+            configs.remove(&channel_id);
+        }
+        let cfg = configs.entry(channel_id).or_default();
+        if let Some(c) = staff_channel {
+            cfg.staff_channel = Some(c.to_string());
+        }
+        // 0 вимикає відповідне правило
+        if let Some(n) = net_votes {
+            cfg.popular_net_votes = Some(n).filter(|n| *n > 0);
+        }
+        if let Some(p) = percent {
+            cfg.popular_percent = Some(p).filter(|p| *p > 0);
+        }
+        if let Some(m) = min_voters {
+            cfg.min_voters = m;
+        }
+        if let Some(a) = auto_popular {
+            cfg.auto_popular = a;
+        }
+        if let Some(p) = negative_percent {
+            cfg.negative_percent = Some(p).filter(|p| *p > 0);
+        }
+        if let Some(h) = negative_hours {
+            cfg.negative_after_hours = h;
+        }
+        if let Some(d) = stale_days {
+            cfg.stale_days = Some(d).filter(|d| *d > 0);
+        }
+        let snap = cfg.clone();
+        save_json(SUGGESTIONS_CONFIG_FILE, &*configs);
+        snap
+    };
+
+    let off = "вимкнено".to_string();
+    let description = format!(
+        "**Канал персоналу:** {}\n\
+         **Популярна:** {} чистих голосів або {} (мін. голосів: {})\n\
+         **Дія:** {}\n\
+         **Авто-закриття:** {} після {} год\n\
+         **Нагадування:** {}",
+        cfg.staff_channel
+            .as_ref()
+            .map(|c| format!("<#{}>", c))
+            .unwrap_or(off.clone()),
+        cfg.popular_net_votes
+            .map(|n| n.to_string())
+            .unwrap_or(off.clone()),
+        cfg.popular_percent
+            .map(|p| format!("{}%", p))
+            .unwrap_or(off.clone()),
+        cfg.min_voters.max(1),
+        if cfg.auto_popular {
+            "статус «Популярна»"
+        } else {
+            "позначка для персоналу"
+        },
+        cfg.negative_percent
+            .map(|p| format!("≤{}%", p))
+            .unwrap_or(off.clone()),
+        cfg.negative_after_hours,
+        cfg.stale_days
+            .map(|d| format!("кожні {} дн.", d))
+            .unwrap_or(off),
+    );
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .title("⚙️ Правила ідей для каналу")
+                    .description(description)
+                    .color(0x3498DB),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Decides what the background idea moderator should do with `idea` under
+/// the channel rules `cfg`, if anything.
+fn evaluate_idea(
+    cfg: &SuggestionChannelConfig,
+    idea: &SuggestionData,
+    now: i64,
+) -> Option<IdeaAction> {
+    if idea.archived {
+        return None;
+    }
+    let voters = idea.votes.len() as u32;
+    let min_voters = cfg.min_voters.max(1);
+    let age = now - idea.timestamp;
+
+    if idea.status == SuggestionStatus::Pending {
+        if let Some(limit) = cfg.negative_percent {
+            if voters >= min_voters
+                && age >= cfg.negative_after_hours as i64 * 3600
+                && idea.approval_percent() <= limit
+            {
+                return Some(IdeaAction::AutoClose);
+            }
+        }
+
+        let popular = cfg.popular_net_votes.is_some_and(|n| idea.net_votes() >= n)
+            || cfg
+                .popular_percent
+                .is_some_and(|p| voters >= min_voters && idea.approval_percent() >= p);
+        if popular {
+            if cfg.auto_popular {
+                return Some(IdeaAction::MarkPopular);
+            }
+            if !idea.flagged && cfg.staff_channel.is_some() {
+                return Some(IdeaAction::Flag);
+            }
+        }
+    }
+
+    if let (Some(days), Some(_)) = (cfg.stale_days, &cfg.staff_channel) {
+        let period = days.max(1) as i64 * 86400;
+        if matches!(
+            idea.status,
+            SuggestionStatus::Pending | SuggestionStatus::Popular
+        ) && age >= period
+            && now - idea.stale_reminded_at >= period
+        {
+            return Some(IdeaAction::StaleReminder);
+        }
+    }
+    None
+}
+
+fn guild_of_channel(ctx: &serenity::Context, channel: ChannelId) -> Option<serenity::GuildId> {
+    ctx.cache.guilds().into_iter().find(|g| {
+        g.to_guild_cached(&ctx.cache)
+            .is_some_and(|guild| guild.channels.contains_key(&channel))
+    })
+}
+
+/// Applies the per-channel idea rules. Runs from `background_tasks`.
+async fn moderate_ideas(ctx: &serenity::Context, data: &Data) {
+    let configs = safe_lock(&data.suggestions_config).clone();
+    if configs.is_empty() {
+        return;
+    }
+    let now = Utc::now().timestamp();
+
+    let actions: Vec<(SuggestionData, IdeaAction)> = {
+        let suggestions = safe_lock(&data.suggestions_data);
+        suggestions
+            .values()
+            .filter_map(|s| {
+                let cfg = configs.get(&s.channel_id)?;
+                evaluate_idea(cfg, s, now).map(|a| (s.clone(), a))
+            })
+            .collect()
+    };
+    if actions.is_empty() {
+        return;
+    }
+
+    let bot = ctx.cache.current_user().clone();
+    let mut stale: HashMap<String, Vec<String>> = HashMap::new(); // staff channel -> рядки
+
+    for (idea, action) in actions {
+        let Some(cfg) = configs.get(&idea.channel_id) else {
+            continue;
+        };
+        let guild_id = idea
+            .channel_id
+            .parse::<u64>()
+            .ok()
+            .and_then(|c| guild_of_channel(ctx, ChannelId::new(c)));
+        let link = suggestion_link(guild_id, &idea);
+
+        match action {
+            IdeaAction::MarkPopular => {
+                change_suggestion_status(
+                    ctx,
+                    data,
+                    &idea.message_id,
+                    guild_id,
+                    SuggestionStatus::Popular,
+                    &bot,
+                    None,
+                    None,
+                )
+                .await;
+            }
+            IdeaAction::AutoClose => {
+                let response = format!(
+                    "Автоматично закрито: спільнота проти ({}% за, голосів: {}).",
+                    idea.approval_percent(),
+                    idea.votes.len()
+                );
+                change_suggestion_status(
+                    ctx,
+                    data,
+                    &idea.message_id,
+                    guild_id,
+                    SuggestionStatus::Rejected,
+                    &bot,
+                    Some(response),
+                    None,
+                )
+                .await;
+            }
+            IdeaAction::Flag => {
+                {
+                    let mut suggestions = safe_lock(&data.suggestions_data);
+                    if let Some(s) = suggestions.get_mut(&idea.message_id) {
+                        s.flagged = true;
+                        save_json(SUGGESTIONS_DATA_FILE, &*suggestions);
+                    }
+                }
+                if let Some(staff) = cfg
+                    .staff_channel
+                    .as_ref()
+                    .and_then(|c| c.parse::<u64>().ok())
+                {
+                    let preview: String = idea.content.chars().take(300).collect();
+                    let embed = CreateEmbed::new()
+                        .title("🚩 Ідея набрала поріг голосів")
+                        .description(format!(
+                            "{}\n\nЗа: {} | Проти: {} | {}%\n[Перейти до ідеї]({})",
+                            preview,
+                            idea.votes_for(),
+                            idea.votes_against(),
+                            idea.approval_percent(),
+                            link
+                        ))
+                        .color(0xE67E22)
+                        .footer(CreateEmbedFooter::new(format!(
+                            "Автор: {}",
+                            idea.author_name
+                        )));
+                    let _ = ChannelId::new(staff)
+                        .send_message(&ctx.http, CreateMessage::new().embed(embed))
+                        .await;
+                }
+            }
+            IdeaAction::StaleReminder => {
+                {
+                    let mut suggestions = safe_lock(&data.suggestions_data);
+                    if let Some(s) = suggestions.get_mut(&idea.message_id) {
+                        s.stale_reminded_at = now;
+                        save_json(SUGGESTIONS_DATA_FILE, &*suggestions);
+                    }
+                }
+                if let Some(staff) = &cfg.staff_channel {
+                    stale.entry(staff.clone()).or_default().push(format!(
+                        "• [{}](<{}>) — подано <t:{}:R>",
+                        idea.content
+                            .chars()
+                            .take(60)
+                            .collect::<String>()
+                            .replace(['\n', '[', ']'], " "),
+                        link,
+                        idea.timestamp
+                    ));
+                }
+            }
+        }
+    }
+
+    for (staff, lines) in stale {
+        let Ok(staff) = staff.parse::<u64>() else {
+            continue;
+        };
+        let description: String = lines.join("\n").chars().take(4000).collect();
+        let embed = CreateEmbed::new()
+            .title("⏰ Ідеї чекають на рішення")
+            .description(description)
+            .color(0x95A5A6);
+        let _ = ChannelId::new(staff)
+            .send_message(&ctx.http, CreateMessage::new().embed(embed))
+            .await;
+    }
+}
+
+const IDEAS_PAGE_SIZE: usize = 10;
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+enum IdeaSort {
+    #[name = "Чистими голосами"]
+    Net,
+    #[name = "Відсотком схвалення"]
+    Approval,
+}
+
+/// Ideas posted in channels of the current guild, newest first.
+fn guild_ideas(ctx: Context<'_>) -> Vec<SuggestionData> {
+    let channels: Vec<String> = ctx
+        .guild()
+        .map(|g| g.channels.keys().map(|c| c.to_string()).collect())
+        .unwrap_or_default();
+    let mut ideas: Vec<SuggestionData> = safe_lock(&ctx.data().suggestions_data)
+        .values()
+        .filter(|s| !s.archived && channels.contains(&s.channel_id))
+        .cloned()
+        .collect();
+    ideas.sort_by_key(|s| std::cmp::Reverse(s.timestamp));
+    ideas
+}
+
+fn sort_ideas(ideas: &mut [SuggestionData], sort: IdeaSort) {
+    match sort {
+        IdeaSort::Net => ideas.sort_by_key(|s| std::cmp::Reverse(s.net_votes())),
+        IdeaSort::Approval => ideas.sort_by(|a, b| {
+            b.approval_percent()
+                .cmp(&a.approval_percent())
+                .then(b.votes.len().cmp(&a.votes.len()))
+        }),
+    }
+}
+
+fn idea_list_line(guild_id: Option<serenity::GuildId>, s: &SuggestionData) -> String {
+    let preview: String = s.content.chars().take(60).collect();
+    let preview = preview.replace(['\n', '[', ']'], " ");
+    let author = if s.anonymous {
+        "анонім".to_string()
+    } else {
+        format!("<@{}>", s.author_id)
+    };
+    format!(
+        "{} `{:+}` [{}{}]({}) — {}",
+        s.status.emoji(),
+        s.net_votes(),
+        preview,
+        if s.content.chars().count() > 60 {
+            "…"
+        } else {
+            ""
+        },
+        suggestion_link(guild_id, s),
+        author
+    )
+}
+
+/// Shows ideas as an ephemeral embed with ◀/▶ buttons to flip pages.
+async fn paginate_ideas(
+    ctx: Context<'_>,
+    title: &str,
+    ideas: Vec<SuggestionData>,
+) -> Result<(), Error> {
+    if ideas.is_empty() {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("📭 Ідей не знайдено.")
+                .ephemeral(true),
         )
-        .field(
-            "🤖 **Адмін: Автоматизація**",
-            "`/setup_autorole` — Авто-роль для новачків\n\
-             `/remove_autorole` — Видалити авто-роль\n\
-             `/cleanup_inactive` — Очистити неактивних\n\
-             `/add_banned_word` — Додати заборонене слово\n\
-             `/remove_banned_word` — Видалити заборонене слово\n\
-             `/list_banned_words` — Список заборонених слів",
-            false,
+        .await?;
+        return Ok(());
+    }
+
+    let guild_id = ctx.guild_id();
+    let pages = ideas.len().div_ceil(IDEAS_PAGE_SIZE);
+    let prefix = format!("ideas_{}_", ctx.id());
+    let render = |page: usize| {
+        let lines: Vec<String> = ideas
+            .iter()
+            .skip(page * IDEAS_PAGE_SIZE)
+            .take(IDEAS_PAGE_SIZE)
+            .map(|s| idea_list_line(guild_id, s))
+            .collect();
+        CreateEmbed::new()
+            .title(title)
+            .description(lines.join("\n"))
+            .color(0xF1C40F)
+            .footer(CreateEmbedFooter::new(format!(
+                "Сторінка {}/{} | Всього ідей: {}",
+                page + 1,
+                pages,
+                ideas.len()
+            )))
+    };
+    let buttons = |page: usize| {
+        vec![CreateActionRow::Buttons(vec![
+            CreateButton::new(format!("{}prev", prefix))
+                .emoji('◀')
+                .style(ButtonStyle::Secondary)
+                .disabled(page == 0),
+            CreateButton::new(format!("{}next", prefix))
+                .emoji('▶')
+                .style(ButtonStyle::Secondary)
+                .disabled(page + 1 >= pages),
+        ])]
+    };
+
+    let mut page = 0;
+    let mut reply = poise::CreateReply::default()
+        .embed(render(page))
+        .ephemeral(true);
+    if pages > 1 {
+        reply = reply.components(buttons(page));
+    }
+    let handle = ctx.send(reply).await?;
+    if pages == 1 {
+        return Ok(());
+    }
+
+    while let Some(mci) = serenity::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .timeout(Duration::from_secs(300))
+        .filter({
+            let prefix = prefix.clone();
+            move |mci| mci.data.custom_id.starts_with(&prefix)
+        })
+        .await
+    {
+        if mci.data.custom_id.ends_with("next") {
+            page = (page + 1).min(pages - 1);
+        } else {
+            page = page.saturating_sub(1);
+        }
+        mci.create_response(
+            ctx.http(),
+            serenity::CreateInteractionResponse::UpdateMessage(
+                serenity::CreateInteractionResponseMessage::new()
+                    .embed(render(page))
+                    .components(buttons(page)),
+            ),
         )
-        .field(
-            "✨ **Автоматичні функції**",
-            "• 🎭 Авто-роль для нових учасників\n\
-             • 👋 Привітання новачків\n\
-             • 🚫 Автоматична модерація лайок\n\
-             • 🔄 Анті-спам система\n\
-             • 🚀 Бустери XP для прискорення прогресу",
-            false,
+        .await?;
+    }
+
+    let _ = handle
+        .edit(
+            ctx,
+            poise::CreateReply::default()
+                .embed(render(page))
+                .components(vec![]),
         )
-        .footer(CreateEmbedFooter::new(
-            "💡 Пиши в голосових каналах для XP!",
-        ))
-        .thumbnail("https://cdn.discordapp.com/emojis/1234567890.png");
+        .await;
+    Ok(())
+}
 
-    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+/// 📋 Пошук і перегляд ідей
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("ideas_list", "ideas_top", "ideas_mine", "ideas_search")
+)]
+async fn ideas(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Список ідей (за замовчуванням — ті, що на розгляді)
+#[poise::command(slash_command, rename = "list")]
+async fn ideas_list(
+    ctx: Context<'_>,
+    #[description = "Фільтр за статусом"] status: Option<SuggestionStatus>,
+) -> Result<(), Error> {
+    let list: Vec<SuggestionData> = guild_ideas(ctx)
+        .into_iter()
+        .filter(|s| match status {
+            Some(st) => s.status == st,
+            None => s.status.is_open(),
+        })
+        .collect();
+    let title = match status {
+        Some(st) => format!("📋 Ідеї: {}", poise::ChoiceParameter::name(&st)),
+        None => "📋 Ідеї на розгляді".to_string(),
+    };
+    paginate_ideas(ctx, &title, list).await
+}
+
+/// Найпопулярніші ідеї
+#[poise::command(slash_command, rename = "top")]
+async fn ideas_top(
+    ctx: Context<'_>,
+    #[description = "Сортувати за"] sort: Option<IdeaSort>,
+    #[description = "Лише відкриті ідеї"] open_only: Option<bool>,
+) -> Result<(), Error> {
+    let mut list: Vec<SuggestionData> = guild_ideas(ctx)
+        .into_iter()
+        .filter(|s| !open_only.unwrap_or(false) || s.status.is_open())
+        .collect();
+    sort_ideas(&mut list, sort.unwrap_or(IdeaSort::Net));
+    paginate_ideas(ctx, "🏆 Топ ідей", list).await
+}
+
+/// Мої ідеї
+#[poise::command(slash_command, rename = "mine")]
+async fn ideas_mine(ctx: Context<'_>) -> Result<(), Error> {
+    let author_id = ctx.author().id.to_string();
+    let list: Vec<SuggestionData> = guild_ideas(ctx)
+        .into_iter()
+        .filter(|s| s.author_id == author_id)
+        .collect();
+    paginate_ideas(ctx, "🙋 Мої ідеї", list).await
+}
+
+/// Пошук ідей за текстом
+#[poise::command(slash_command, rename = "search")]
+async fn ideas_search(
+    ctx: Context<'_>,
+    #[description = "Текст для пошуку"] text: String,
+) -> Result<(), Error> {
+    let needle = text.to_lowercase();
+    let list: Vec<SuggestionData> = guild_ideas(ctx)
+        .into_iter()
+        .filter(|s| s.content.to_lowercase().contains(&needle))
+        .collect();
+    // Заголовок ембеда обмежено 256 символами
+    let shown: String = text.chars().take(240).collect();
+    paginate_ideas(ctx, &format!("🔎 Пошук: {}", shown), list).await
+}
+
+// --- АВТОМАТИЧНЕ УПРАВЛІННЯ РОЛЯМИ ---
+
+/// 🎭 [ADMIN] Налаштувати авто-ролі для нових користувачів
+#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
+async fn setup_autorole(ctx: Context<'_>, role: serenity::Role) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?;
+
+    let auto_role = AutoRole {
+        guild_id: guild_id.to_string(),
+        role_id: role.id.to_string(),
+    };
+
+    {
+        let mut roles = safe_lock(&ctx.data().auto_roles);
+        // Видаляємо стару роль для цього серверу
+        roles.retain(|r| r.guild_id != guild_id.to_string());
+        // Додаємо нову
+        roles.push(auto_role);
+        save_json(AUTO_ROLES_FILE, &*roles);
+    }
+
+    ctx.say(format!(
+        "✅ Авто-роль встановлено: **{}**\nНові користувачі автоматично отримають цю роль!",
+        role.name
+    ))
+    .await?;
+    Ok(())
+}
+
+/// 🗑️ [ADMIN] Відключити авто-ролі
+#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
+async fn remove_autorole(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?;
+
+    {
+        let mut roles = safe_lock(&ctx.data().auto_roles);
+        roles.retain(|r| r.guild_id != guild_id.to_string());
+        save_json(AUTO_ROLES_FILE, &*roles);
+    }
+
+    ctx.say("✅ Авто-ролі відключено для цього сервера.")
         .await?;
     Ok(())
 }
 
-/// ℹ️ Інформація про бота
-#[poise::command(slash_command)]
-async fn info(ctx: Context<'_>) -> Result<(), Error> {
-    let guild_count = ctx.serenity_context().cache.guilds().len();
-    let user_count = {
-        let users = safe_lock(&ctx.data().users);
-        users.len()
+// --- АВТОМАТИЧНА МОДЕРАЦІЯ ---
+
+/// 🚫 [ADMIN] Додати заборонене слово
+#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
+async fn add_banned_word(ctx: Context<'_>, word: String) -> Result<(), Error> {
+    {
+        let mut words = safe_lock(&ctx.data().banned_words);
+        let word_lower = word.to_lowercase();
+        if !words.contains(&word_lower) {
+            words.push(word_lower);
+            save_json(BANNED_WORDS_FILE, &*words);
+        }
+    }
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content("✅ Слово додано до чорного списку!".to_string())
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// 📋 [ADMIN] Список заборонених слів
+#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
+async fn list_banned_words(ctx: Context<'_>) -> Result<(), Error> {
+    let words = {
+        let w = safe_lock(&ctx.data().banned_words);
+        w.clone()
+    };
+
+    if words.is_empty() {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("📋 Чорний список порожній.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let list = words.join(", ");
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!("🚫 **Заборонені слова:**\n{}", list))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// 🗑️ [ADMIN] Видалити заборонене слово
+#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
+async fn remove_banned_word(ctx: Context<'_>, word: String) -> Result<(), Error> {
+    let removed = {
+        let mut words = safe_lock(&ctx.data().banned_words);
+        let word_lower = word.to_lowercase();
+        let len_before = words.len();
+        words.retain(|w| w != &word_lower);
+        let removed = words.len() < len_before;
+        if removed {
+            save_json(BANNED_WORDS_FILE, &*words);
+        }
+        removed
     };
 
-    let embed = CreateEmbed::new()
-        .title("ℹ️ Інформація про StarostaBot")
-        .description("**Сільський бот для Discord серверів** 🌾")
-        .color(0x3498DB)
-        .field(
-            "📊 Статистика",
-            format!(
-                "Серверів: **{}**\nКористувачів: **{}**",
-                guild_count, user_count
-            ),
-            true,
-        )
-        .field(
-            "⚙️ Технології",
-            "Rust 🦀\nSerenity + Poise\nHosted on Discloud",
-            true,
+    if removed {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("✅ Слово видалено з чорного списку!")
+                .ephemeral(true),
         )
-        .field(
-            "🎯 Можливості",
-            "• Система рівнів і ролей\n\
-             • XP за повідомлення та голос\n\
-             • Ігри та казино\n\
-             • Привітання з ДН\n\
-             • Інструменти модерації",
-            false,
+        .await?;
+    } else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Слово не знайдено в списку.")
+                .ephemeral(true),
         )
-        .footer(CreateEmbedFooter::new("Створено з ❤️ для твоєї спільноти"))
-        .timestamp(Timestamp::now());
-
-    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        .await?;
+    }
     Ok(())
 }
 
-/// 🏆 Таблиця лідерів
-#[poise::command(slash_command)]
-async fn leaderboard(ctx: Context<'_>) -> Result<(), Error> {
+// --- АВТОМАТИЧНЕ ОЧИЩЕННЯ НЕАКТИВНИХ РОЛЕЙ ---
+
+/// 🧹 [ADMIN] Видалити ролі з неактивних користувачів
+#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
+async fn cleanup_inactive(ctx: Context<'_>, days: u64) -> Result<(), Error> {
     ctx.defer().await?;
 
-    let mut leaders: Vec<(String, u64, u64, u64)> = {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?;
+    let threshold = Utc::now().timestamp() - (days as i64 * 86400);
+
+    let inactive_users: Vec<String> = {
         let users = safe_lock(&ctx.data().users);
         users
             .iter()
-            .map(|(id, p)| (id.clone(), p.level, p.xp, p.minutes))
+            .filter(|(_, p)| p.last_msg_time != 0 && p.last_msg_time < threshold * 1000)
+            .map(|(id, _)| id.clone())
             .collect()
     };
 
-    // Сортуємо по рівню (спадаюче), потім по XP
-    leaders.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)));
+    // Fetch the guild's roles once up front instead of re-fetching them inside
+    // the per-user / per-role loop (which hammered the API and risked rate
+    // limits on large servers).
+    let all_roles = guild_id.roles(&ctx.http()).await?;
+    let config = get_roles_config();
+    let level_role_ids: Vec<serenity::RoleId> = config
+        .iter()
+        .filter_map(|(_, role_name, _)| {
+            all_roles
+                .values()
+                .find(|r| r.name == *role_name)
+                .map(|r| r.id)
+        })
+        .collect();
 
-    let mut description = String::new();
-    let medals = ["🥇", "🥈", "🥉"];
+    let mut removed_count = 0;
 
-    for (i, (user_id, level, xp, minutes)) in leaders.iter().take(10).enumerate() {
-        let medal = if i < 3 { medals[i] } else { "🏅" };
-        let role_name = get_role_for_level(*level).unwrap_or("Новачок");
-        description.push_str(&format!(
-            "{}**{}. <@{}>**\n└ Рівень: **{}** | XP: **{}** | Голос: **{}** год\n└ Звання: *{}*\n\n",
-            medal, i + 1, user_id, level, xp, minutes / 60, role_name
-        ));
+    for user_id_str in inactive_users {
+        if let Ok(user_id_num) = user_id_str.parse::<u64>() {
+            let user_id = serenity::UserId::new(user_id_num);
+            if let Ok(member) = guild_id.member(&ctx.http(), user_id).await {
+                for role_id in &level_role_ids {
+                    if member.roles.contains(role_id) {
+                        let _ = member.remove_role(&ctx.http(), *role_id).await;
+                        removed_count += 1;
+                    }
+                }
+            }
+        }
     }
 
-    if description.is_empty() {
-        description = "Поки що немає активних користувачів 😔".to_string();
-    }
+    ctx.say(format!(
+        "🧹 Очищено ролі з {} неактивних користувачів (неактивні > {} днів).",
+        removed_count, days
+    ))
+    .await?;
+    Ok(())
+}
 
-    let embed = CreateEmbed::new()
-        .title("🏆 Таблиця лідерів")
-        .description(description)
-        .color(0xFFD700)
-        .footer(CreateEmbedFooter::new(
-            "💪 Продовжуй працювати, щоб потрапити в топ!",
-        ));
+// --- МОДМЕЙЛ ---
 
-    ctx.send(poise::CreateReply::default().embed(embed)).await?;
-    Ok(())
+/// Returns the open modmail conversation whose staff thread is `channel_id`.
+fn find_modmail_thread_by_channel(
+    data: &Data,
+    channel_id: serenity::ChannelId,
+) -> Option<ModmailThread> {
+    let threads = safe_lock(&data.modmail_threads);
+    let channel_id = channel_id.to_string();
+    threads
+        .values()
+        .find(|t| t.thread_id == channel_id)
+        .cloned()
 }
 
-/// [ADMIN] Налаштувати всі ролі на сервері
-#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
-async fn setup_roles(ctx: Context<'_>) -> Result<(), Error> {
-    ctx.defer().await?;
-    let guild = ctx.guild_id().ok_or("Not in a guild")?;
-    let existing_roles = guild.roles(&ctx.http()).await?;
+/// Appends an entry to an open conversation's transcript and persists it.
+fn record_modmail_entry(data: &Data, user_id: &str, entry: ModmailEntry) {
+    let mut threads = safe_lock(&data.modmail_threads);
+    if let Some(thread) = threads.get_mut(user_id) {
+        thread.transcript.push(entry);
+        save_json(MODMAIL_THREADS_FILE, &*threads);
+    }
+}
 
-    let config = get_roles_config();
+/// Re-downloads message attachments so they can be re-uploaded elsewhere.
+/// Attachments that fail to download are skipped rather than failing the relay.
+async fn download_attachments(
+    http: &serenity::Http,
+    attachments: &[serenity::Attachment],
+) -> Vec<CreateAttachment> {
+    let mut files = Vec::new();
+    for a in attachments {
+        match CreateAttachment::url(http, &a.url).await {
+            Ok(file) => files.push(file),
+            Err(e) => warn!("⚠️ Не вдалося завантажити вкладення {}: {}", a.filename, e),
+        }
+    }
+    files
+}
 
-    for (level, name, color_hex) in config {
-        // Determine safe permissions based on level tier
-        let permissions = if level < 10 {
-            // Levels 0-9 (Basic): Basic chat and voice permissions
-            serenity::Permissions::VIEW_CHANNEL
-                | serenity::Permissions::SEND_MESSAGES
-                | serenity::Permissions::READ_MESSAGE_HISTORY
-                | serenity::Permissions::CONNECT
-                | serenity::Permissions::SPEAK
-                | serenity::Permissions::USE_VAD
-        } else if level < 30 {
-            // Levels 10-29 (Trusted): Basic + enhanced interaction permissions
-            serenity::Permissions::VIEW_CHANNEL
-                | serenity::Permissions::SEND_MESSAGES
-                | serenity::Permissions::READ_MESSAGE_HISTORY
-                | serenity::Permissions::CONNECT
-                | serenity::Permissions::SPEAK
-                | serenity::Permissions::USE_VAD
-                | serenity::Permissions::CHANGE_NICKNAME
-                | serenity::Permissions::ATTACH_FILES
-                | serenity::Permissions::USE_EXTERNAL_EMOJIS
-                | serenity::Permissions::ADD_REACTIONS
+/// Renders a closed conversation as a plain-text transcript.
+fn format_modmail_transcript(thread: &ModmailThread) -> String {
+    let fmt_time = |ts: i64| {
+        chrono::DateTime::from_timestamp(ts, 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_default()
+    };
+
+    let mut out = format!(
+        "Модмейл: {} ({})\nВідкрито: {}\n\n",
+        thread.user_name,
+        thread.user_id,
+        fmt_time(thread.opened_at)
+    );
+    for e in &thread.transcript {
+        let who = if !e.from_staff {
+            format!("[Користувач] {}", e.author_name)
+        } else if e.anonymous {
+            format!("[Персонал, анонімно] {}", e.author_name)
         } else {
-            // Levels 30-50 (Elite): Trusted + advanced community features
-            serenity::Permissions::VIEW_CHANNEL
-                | serenity::Permissions::SEND_MESSAGES
-                | serenity::Permissions::READ_MESSAGE_HISTORY
-                | serenity::Permissions::CONNECT
-                | serenity::Permissions::SPEAK
-                | serenity::Permissions::USE_VAD
-                | serenity::Permissions::CHANGE_NICKNAME
-                | serenity::Permissions::ATTACH_FILES
-                | serenity::Permissions::USE_EXTERNAL_EMOJIS
-                | serenity::Permissions::ADD_REACTIONS
-                | serenity::Permissions::USE_SOUNDBOARD
-                | serenity::Permissions::PRIORITY_SPEAKER
-                | serenity::Permissions::CREATE_PUBLIC_THREADS
-                | serenity::Permissions::SEND_MESSAGES_IN_THREADS
+            format!("[Персонал] {}", e.author_name)
         };
+        out.push_str(&format!(
+            "[{}] {}: {}\n",
+            fmt_time(e.timestamp),
+            who,
+            e.content
+        ));
+        for url in &e.attachments {
+            out.push_str(&format!("    📎 {}\n", url));
+        }
+    }
+    out
+}
 
-        if let Some(role) = existing_roles.values().find(|r| r.name == name) {
-            // Update existing role with color, hoist, and safe permissions
-            let _ = guild
-                .edit_role(
-                    &ctx.http(),
-                    role.id,
-                    EditRole::new()
-                        .colour(Color::from(color_hex))
-                        .hoist(true)
-                        .permissions(permissions),
-                )
-                .await;
-        } else {
-            // Create new role with color, hoist, and safe permissions
-            let _ = guild
-                .create_role(
-                    &ctx.http(),
-                    EditRole::new()
-                        .name(name)
-                        .colour(Color::from(color_hex))
-                        .hoist(true)
-                        .permissions(permissions),
-                )
-                .await;
+/// Picks the modmail config for a DM author: the guild of their open
+/// conversation if there is one, otherwise the first configured guild they
+/// are a member of.
+async fn resolve_modmail_config(
+    ctx: &serenity::Context,
+    data: &Data,
+    user_id: serenity::UserId,
+) -> Option<ModmailConfig> {
+    let open_guild = {
+        let threads = safe_lock(&data.modmail_threads);
+        threads
+            .get(&user_id.to_string())
+            .map(|t| t.guild_id.clone())
+    };
+    let configs = safe_lock(&data.modmail_config).clone();
+
+    if let Some(guild_id) = open_guild {
+        if let Some(c) = configs.iter().find(|c| c.guild_id == guild_id) {
+            return Some(c.clone());
         }
     }
 
-    // Create birthday role with basic permissions (no admin/moderation rights)
-    if !existing_roles
-        .values()
-        .any(|r| r.name == BIRTHDAY_ROLE_NAME)
+    for c in configs {
+        let Ok(guild_num) = c.guild_id.parse::<u64>() else {
+            continue;
+        };
+        if serenity::GuildId::new(guild_num)
+            .member(&ctx.http, user_id)
+            .await
+            .is_ok()
+        {
+            return Some(c);
+        }
+    }
+    None
+}
+
+/// Creates a new staff thread for a user's conversation and records it.
+async fn open_modmail_thread(
+    ctx: &serenity::Context,
+    data: &Data,
+    config: &ModmailConfig,
+    user: &serenity::User,
+) -> Result<serenity::ChannelId, Error> {
+    let staff_channel = serenity::ChannelId::new(config.channel_id.parse::<u64>()?);
+
+    let embed = CreateEmbed::new()
+        .title("📨 Нове звернення")
+        .description(format!("Користувач <@{}> написав боту.", user.id))
+        .color(0x3498DB)
+        .thumbnail(user.face())
+        .field("Користувач", format!("{} (`{}`)", user.name, user.id), true)
+        .field(
+            "Акаунт створено",
+            format!("<t:{}:R>", user.created_at().unix_timestamp()),
+            true,
+        )
+        .footer(CreateEmbedFooter::new(
+            "Пишіть у треді, щоб відповісти. Повідомлення з // не пересилаються. /modmail close — закрити.",
+        ));
+
+    let starter = staff_channel
+        .send_message(&ctx.http, CreateMessage::new().embed(embed))
+        .await?;
+    let thread = staff_channel
+        .create_thread_from_message(
+            &ctx.http,
+            starter.id,
+            serenity::CreateThread::new(format!("Модмейл: {}", user.name))
+                .auto_archive_duration(serenity::AutoArchiveDuration::OneWeek),
+        )
+        .await?;
+
+    let record = ModmailThread {
+        user_id: user.id.to_string(),
+        user_name: user.name.clone(),
+        guild_id: config.guild_id.clone(),
+        thread_id: thread.id.to_string(),
+        opened_at: Utc::now().timestamp(),
+        transcript: Vec::new(),
+    };
     {
-        let birthday_perms = serenity::Permissions::VIEW_CHANNEL
-            | serenity::Permissions::SEND_MESSAGES
-            | serenity::Permissions::READ_MESSAGE_HISTORY
-            | serenity::Permissions::CONNECT
-            | serenity::Permissions::SPEAK
-            | serenity::Permissions::USE_VAD;
+        let mut threads = safe_lock(&data.modmail_threads);
+        threads.insert(user.id.to_string(), record);
+        save_json(MODMAIL_THREADS_FILE, &*threads);
+    }
+    info!("📨 Відкрито модмейл для {}", user.name);
+    Ok(thread.id)
+}
+
+/// Forwards a DM to the staff thread, opening one if needed.
+/// Returns `false` when modmail isn't configured for this user, so the caller
+/// can fall back to the usual message handling.
+async fn handle_modmail_dm(
+    ctx: &serenity::Context,
+    data: &Data,
+    msg: &serenity::Message,
+) -> Result<bool, Error> {
+    let Some(config) = resolve_modmail_config(ctx, data, msg.author.id).await else {
+        return Ok(false);
+    };
 
-        let _ = guild
-            .create_role(
-                &ctx.http(),
-                EditRole::new()
-                    .name(BIRTHDAY_ROLE_NAME)
-                    .colour(0xFF69B4)
-                    .hoist(true)
-                    .permissions(birthday_perms),
-            )
+    if config.blocked_users.contains(&msg.author.id.to_string()) {
+        let _ = msg
+            .channel_id
+            .say(&ctx.http, "🚫 Ви не можете писати персоналу через бота.")
             .await;
+        return Ok(true);
     }
 
-    ctx.say("✅ Всі ролі створено та пофарбовано з безпечними правами!")
-        .await?;
-    Ok(())
-}
+    let existing = {
+        let threads = safe_lock(&data.modmail_threads);
+        threads
+            .get(&msg.author.id.to_string())
+            .and_then(|t| t.thread_id.parse::<u64>().ok())
+            .map(serenity::ChannelId::new)
+    };
 
-/// [ADMIN] Встановити рівень користувачу
-#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
-async fn admin_set_level(ctx: Context<'_>, user: serenity::User, level: u64) -> Result<(), Error> {
-    let user_id = user.id.to_string();
-    {
-        let mut users = safe_lock(&ctx.data().users);
-        let profile = users.entry(user_id).or_insert(create_default_profile());
-        profile.level = level;
-        save_json(USERS_FILE, &*users);
+    let embed = CreateEmbed::new()
+        .author(serenity::CreateEmbedAuthor::new(&msg.author.name).icon_url(msg.author.face()))
+        .description(if msg.content.is_empty() {
+            "*(без тексту)*".to_string()
+        } else {
+            msg.content.clone()
+        })
+        .color(0x3498DB)
+        .timestamp(Timestamp::now());
+    let files = download_attachments(&ctx.http, &msg.attachments).await;
+
+    let delivered = match existing {
+        Some(thread_id) => thread_id
+            .send_message(
+                &ctx.http,
+                CreateMessage::new()
+                    .embed(embed.clone())
+                    .add_files(files.clone()),
+            )
+            .await
+            .is_ok(),
+        None => false,
+    };
+
+    if !delivered {
+        // Розмови ще немає або тред видалили — відкриваємо новий.
+        let thread_id = open_modmail_thread(ctx, data, &config, &msg.author).await?;
+        thread_id
+            .send_message(
+                &ctx.http,
+                CreateMessage::new().embed(embed).add_files(files),
+            )
+            .await?;
     }
 
-    if let Some(guild_id) = ctx.guild_id() {
-        assign_role(ctx.serenity_context(), guild_id, user.id, level).await;
+    record_modmail_entry(
+        data,
+        &msg.author.id.to_string(),
+        ModmailEntry {
+            author_id: msg.author.id.to_string(),
+            author_name: msg.author.name.clone(),
+            content: msg.content.clone(),
+            attachments: msg.attachments.iter().map(|a| a.url.clone()).collect(),
+            from_staff: false,
+            anonymous: false,
+            timestamp: Utc::now().timestamp(),
+        },
+    );
+
+    if !delivered {
+        let _ = msg
+            .channel_id
+            .say(
+                &ctx.http,
+                "📨 Ваше повідомлення передано персоналу. Усі наступні повідомлення сюди теж отримає персонал.",
+            )
+            .await;
+    } else {
+        let _ = msg.react(&ctx.http, '✅').await;
     }
+    Ok(true)
+}
 
-    ctx.say(format!(
-        "👮‍♂️ Адмін встановив рівень **{}** для користувача <@{}>.",
-        level, user.id
-    ))
+/// Sends a staff reply from the thread to the user's DMs and records it.
+async fn relay_modmail_reply(
+    ctx: &serenity::Context,
+    data: &Data,
+    thread: &ModmailThread,
+    staff: &serenity::User,
+    content: &str,
+    attachments: &[serenity::Attachment],
+    anonymous: bool,
+) -> Result<(), Error> {
+    let user_id = serenity::UserId::new(thread.user_id.parse::<u64>()?);
+    let author = if anonymous {
+        serenity::CreateEmbedAuthor::new("Персонал сервера")
+    } else {
+        serenity::CreateEmbedAuthor::new(&staff.name).icon_url(staff.face())
+    };
+    let embed = CreateEmbed::new()
+        .author(author)
+        .description(content)
+        .color(0x2ECC71)
+        .timestamp(Timestamp::now());
+    let files = download_attachments(&ctx.http, attachments).await;
+
+    let dm = user_id.create_dm_channel(&ctx.http).await?;
+    dm.send_message(
+        &ctx.http,
+        CreateMessage::new().embed(embed).add_files(files),
+    )
     .await?;
+
+    record_modmail_entry(
+        data,
+        &thread.user_id,
+        ModmailEntry {
+            author_id: staff.id.to_string(),
+            author_name: staff.name.clone(),
+            content: content.to_string(),
+            attachments: attachments.iter().map(|a| a.url.clone()).collect(),
+            from_staff: true,
+            anonymous,
+            timestamp: Utc::now().timestamp(),
+        },
+    );
     Ok(())
 }
 
-/// [ADMIN] Змінити XP користувачу
+/// 📨 [ADMIN] Модмейл: звернення користувачів до персоналу
 #[poise::command(
     slash_command,
-    default_member_permissions = "ADMINISTRATOR",
-    rename = "admin_set_xp"
+    default_member_permissions = "MODERATE_MEMBERS",
+    subcommands(
+        "modmail_setup",
+        "modmail_reply",
+        "modmail_close",
+        "modmail_block",
+        "modmail_unblock"
+    )
 )]
-async fn admin_set_xp(ctx: Context<'_>, user: serenity::User, xp: u64) -> Result<(), Error> {
-    let user_id = user.id.to_string();
-    {
-        let mut users = safe_lock(&ctx.data().users);
-        let profile = users.entry(user_id).or_insert(create_default_profile());
-        profile.xp = xp;
-        save_json(USERS_FILE, &*users);
-    }
-
-    ctx.say(format!(
-        "👮‍♂️ Адмін встановив **{} XP** для користувача <@{}>.",
-        xp, user.id
-    ))
-    .await?;
+async fn modmail(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
-/// [ADMIN] Змінити гривні користувачу
+/// Зробити цей канал каналом персоналу для модмейлу
 #[poise::command(
     slash_command,
-    default_member_permissions = "ADMINISTRATOR",
-    rename = "admin_set_chips"
+    rename = "setup",
+    required_permissions = "ADMINISTRATOR"
 )]
-async fn admin_set_chips(ctx: Context<'_>, user: serenity::User, chips: u64) -> Result<(), Error> {
-    let user_id = user.id.to_string();
+async fn modmail_setup(
+    ctx: Context<'_>,
+    #[description = "Приховувати імена персоналу у відповідях"] anonymous: Option<bool>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
+    let anonymous = anonymous.unwrap_or(false);
+
     {
-        let mut users = safe_lock(&ctx.data().users);
-        let profile = users.entry(user_id).or_insert(create_default_profile());
-        profile.chips = chips;
-        save_json(USERS_FILE, &*users);
+        let mut configs = safe_lock(&ctx.data().modmail_config);
+        let blocked = configs
+            .iter()
+            .find(|c| c.guild_id == guild_id)
+            .map(|c| c.blocked_users.clone())
+            .unwrap_or_default();
+        configs.retain(|c| c.guild_id != guild_id);
+        configs.push(ModmailConfig {
+            guild_id,
+            channel_id: ctx.channel_id().to_string(),
+            anonymous,
+            blocked_users: blocked,
+        });
+        save_json(MODMAIL_CONFIG_FILE, &*configs);
     }
 
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!(
+                "✅ Модмейл налаштовано! Особисті повідомлення боту відкриватимуть треди в цьому каналі.\nВідповіді персоналу: **{}**",
+                if anonymous { "анонімні" } else { "з іменем" }
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Відповісти користувачу (в треді модмейлу)
+#[poise::command(slash_command, rename = "reply")]
+async fn modmail_reply(
+    ctx: Context<'_>,
+    #[description = "Текст відповіді"] text: String,
+    #[description = "Не показувати ваше ім'я"] anonymous: Option<bool>,
+) -> Result<(), Error> {
+    let Some(thread) = find_modmail_thread_by_channel(ctx.data(), ctx.channel_id()) else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Це не тред модмейлу.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let anonymous = anonymous.unwrap_or(false);
+    relay_modmail_reply(
+        ctx.serenity_context(),
+        ctx.data(),
+        &thread,
+        ctx.author(),
+        &text,
+        &[],
+        anonymous,
+    )
+    .await?;
+
+    let shown = if anonymous {
+        "анонімно"
+    } else {
+        "з іменем"
+    };
     ctx.say(format!(
-        "👮‍♂️ Адмін встановив **{} гривень** 💰 для користувача <@{}>.",
-        chips, user.id
+        "📨 **{}** ({}): {}",
+        ctx.author().name,
+        shown,
+        text
     ))
     .await?;
     Ok(())
 }
 
-/// [ADMIN] Замутити користувача
-#[poise::command(slash_command, default_member_permissions = "MODERATE_MEMBERS")]
-async fn admin_mute(
+/// Закрити розмову і зберегти стенограму
+#[poise::command(slash_command, rename = "close")]
+async fn modmail_close(
     ctx: Context<'_>,
-    user: serenity::User,
-    minutes: i64,
-    #[description = "Тип мута: text (текст), voice (голос), all (всюди)"] mute_type: Option<String>,
-    reason: Option<String>,
+    #[description = "Причина закриття (побачить користувач)"] reason: Option<String>,
 ) -> Result<(), Error> {
-    let guild_id = ctx.guild_id().ok_or("Not in a guild")?;
-    let mute_mode = mute_type.unwrap_or("all".to_string()).to_lowercase();
+    ctx.defer().await?;
 
-    let _member = guild_id.member(&ctx.http(), user.id).await?;
+    let Some(found) = find_modmail_thread_by_channel(ctx.data(), ctx.channel_id()) else {
+        ctx.say("❌ Це не тред модмейлу.").await?;
+        return Ok(());
+    };
+    let thread = {
+        let mut threads = safe_lock(&ctx.data().modmail_threads);
+        let removed = threads.remove(&found.user_id);
+        save_json(MODMAIL_THREADS_FILE, &*threads);
+        removed.unwrap_or(found)
+    };
 
-    match mute_mode.as_str() {
-        "text" | "текст" => {
-            // Мут тільки в текстових каналах
-            let time_until =
-                Timestamp::from_unix_timestamp(Utc::now().timestamp() + (minutes * 60))?;
-            guild_id
-                .edit_member(
-                    &ctx.http(),
-                    user.id,
-                    EditMember::new().disable_communication_until(time_until.to_string()),
-                )
-                .await?;
-            ctx.say(format!(
-                "🔇 Користувача <@{}> замучено в **текстових каналах** на {} хв.\nПричина: {}",
-                user.id,
-                minutes,
-                reason.unwrap_or("Не вказана".to_string())
-            ))
-            .await?;
+    let transcript = format_modmail_transcript(&thread);
+    let file_name = format!("modmail_{}_{}.txt", thread.user_id, Utc::now().timestamp());
+
+    if let Err(e) = fs::create_dir_all(MODMAIL_TRANSCRIPTS_DIR).and_then(|_| {
+        fs::write(
+            format!("{}/{}", MODMAIL_TRANSCRIPTS_DIR, file_name),
+            &transcript,
+        )
+    }) {
+        error!("❌ Не вдалося зберегти стенограму модмейлу: {}", e);
+    }
+
+    let staff_channel = {
+        let configs = safe_lock(&ctx.data().modmail_config);
+        configs
+            .iter()
+            .find(|c| c.guild_id == thread.guild_id)
+            .and_then(|c| c.channel_id.parse::<u64>().ok())
+            .map(serenity::ChannelId::new)
+    };
+    if let Some(chan) = staff_channel {
+        let _ = chan
+            .send_message(
+                ctx.http(),
+                CreateMessage::new()
+                    .content(format!(
+                        "📁 Модмейл з <@{}> закрив <@{}>. Повідомлень: {}",
+                        thread.user_id,
+                        ctx.author().id,
+                        thread.transcript.len()
+                    ))
+                    .add_file(CreateAttachment::bytes(transcript.into_bytes(), file_name)),
+            )
+            .await;
+    }
+
+    if let Ok(uid) = thread.user_id.parse::<u64>() {
+        if let Ok(dm) = serenity::UserId::new(uid)
+            .create_dm_channel(ctx.http())
+            .await
+        {
+            let text = match &reason {
+                Some(r) => format!("📪 Персонал закрив вашу розмову.\nПричина: {}\n\nЩоб почати нову — просто напишіть сюди.", r),
+                None => "📪 Персонал закрив вашу розмову. Щоб почати нову — просто напишіть сюди.".to_string(),
+            };
+            let _ = dm.say(ctx.http(), text).await;
         }
-        "voice" | "голос" => {
-            // Мут тільки в голосових каналах
-            guild_id
-                .edit_member(&ctx.http(), user.id, EditMember::new().mute(true))
-                .await?;
-            ctx.say(format!("🔇 Користувача <@{}> замучено в **голосових каналах** на {} хв.\nПричина: {}\n\n⚠️ Потрібно вручну розмутити після закінчення часу.", 
-                user.id, minutes, reason.clone().unwrap_or("Не вказана".to_string()))).await?;
+    }
+
+    ctx.say("🔒 Розмову закрито, стенограму збережено.").await?;
+    let _ = ctx
+        .channel_id()
+        .edit_thread(
+            ctx.http(),
+            serenity::EditThread::new().archived(true).locked(true),
+        )
+        .await;
+    Ok(())
+}
+
+/// Заблокувати користувачу доступ до модмейлу
+#[poise::command(slash_command, rename = "block")]
+async fn modmail_block(ctx: Context<'_>, user: serenity::User) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
+    let user_id = user.id.to_string();
+
+    let configured = {
+        let mut configs = safe_lock(&ctx.data().modmail_config);
+        match configs.iter_mut().find(|c| c.guild_id == guild_id) {
+            Some(c) => {
+                if !c.blocked_users.contains(&user_id) {
+                    c.blocked_users.push(user_id);
+                }
+                save_json(MODMAIL_CONFIG_FILE, &*configs);
+                true
+            }
+            None => false,
         }
-        _ => {
-            // Мут всюди (текст + голос)
-            let time_until =
-                Timestamp::from_unix_timestamp(Utc::now().timestamp() + (minutes * 60))?;
-            guild_id
-                .edit_member(
-                    &ctx.http(),
-                    user.id,
-                    EditMember::new()
-                        .disable_communication_until(time_until.to_string())
-                        .mute(true),
-                )
-                .await?;
-            ctx.say(format!("🔇 Користувача <@{}> замучено **всюди** на {} хв.\nПричина: {}\n\n⚠️ Текстовий мут автоматичний, голосовий потрібно зняти вручну.", 
-                user.id, minutes, reason.unwrap_or("Не вказана".to_string()))).await?;
+    };
+
+    let msg = if configured {
+        format!("🚫 <@{}> більше не може писати в модмейл.", user.id)
+    } else {
+        "❌ Модмейл не налаштовано. Використай `/modmail setup`.".to_string()
+    };
+    ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Розблокувати користувачу доступ до модмейлу
+#[poise::command(slash_command, rename = "unblock")]
+async fn modmail_unblock(ctx: Context<'_>, user: serenity::User) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
+    let user_id = user.id.to_string();
+
+    let removed = {
+        let mut configs = safe_lock(&ctx.data().modmail_config);
+        match configs.iter_mut().find(|c| c.guild_id == guild_id) {
+            Some(c) => {
+                let before = c.blocked_users.len();
+                c.blocked_users.retain(|u| u != &user_id);
+                let removed = c.blocked_users.len() != before;
+                if removed {
+                    save_json(MODMAIL_CONFIG_FILE, &*configs);
+                }
+                removed
+            }
+            None => false,
         }
-    }
+    };
 
+    let msg = if removed {
+        format!("✅ <@{}> знову може писати в модмейл.", user.id)
+    } else {
+        format!("❌ <@{}> не був заблокований.", user.id)
+    };
+    ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
+        .await?;
     Ok(())
 }
 
-/// [ADMIN] Розмутити користувача
-#[poise::command(slash_command, default_member_permissions = "MODERATE_MEMBERS")]
-async fn admin_unmute(ctx: Context<'_>, user: serenity::User) -> Result<(), Error> {
+// --- ТЕГИ (КАСТОМНІ КОМАНДИ) ---
+
+/// Values substituted into tag placeholders.
+struct TagVars<'a> {
+    user_id: u64,
+    user_name: &'a str,
+    server: &'a str,
+    channel_id: u64,
+    args: &'a str,
+    uses: u64,
+}
+
+/// Fills tag placeholders: `{user}` (mention), `{user.name}`, `{user.id}`,
+/// `{server}`, `{channel}`, `{args}` and `{uses}`.
+fn render_tag_template(template: &str, vars: &TagVars) -> String {
+    template
+        .replace("{user}", &format!("<@{}>", vars.user_id))
+        .replace("{user.name}", vars.user_name)
+        .replace("{user.id}", &vars.user_id.to_string())
+        .replace("{server}", vars.server)
+        .replace("{channel}", &format!("<#{}>", vars.channel_id))
+        .replace("{args}", vars.args)
+        .replace("{uses}", &vars.uses.to_string())
+}
+
+/// Parses a `#RRGGBB` / `RRGGBB` hex color.
+fn parse_hex_color(s: &str) -> Option<u32> {
+    let hex = s.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
+
+/// Normalizes a tag name: lowercase, no whitespace, at most 32 characters.
+fn normalize_tag_name(name: &str) -> Option<String> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() || name.chars().count() > 32 || name.chars().any(char::is_whitespace) {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+/// Looks up a tag and bumps its usage counter under a single lock.
+fn use_tag(data: &Data, guild_id: &str, name: &str) -> Option<Tag> {
+    let mut all = safe_lock(&data.tags);
+    let tag = all.get_mut(guild_id)?.tags.get_mut(name)?;
+    tag.uses += 1;
+    let snap = tag.clone();
+    save_json(TAGS_FILE, &*all);
+    Some(snap)
+}
+
+/// `{args}` comes from the caller, so a rendered tag may only ping the
+/// caller (`{user}`), never @everyone, roles or other members.
+fn tag_allowed_mentions(vars: &TagVars) -> serenity::CreateAllowedMentions {
+    serenity::CreateAllowedMentions::new().users(vec![serenity::UserId::new(vars.user_id)])
+}
+
+/// Builds a tag response as either plain content or an embed.
+fn render_tag(tag: &Tag, vars: &TagVars) -> (Option<String>, Option<CreateEmbed>) {
+    let text = render_tag_template(&tag.content, vars);
+    if tag.embed {
+        let mut embed = CreateEmbed::new()
+            .description(text)
+            .color(tag.color.unwrap_or(0x3498DB));
+        if let Some(title) = &tag.title {
+            embed = embed.title(render_tag_template(title, vars));
+        }
+        (None, Some(embed))
+    } else {
+        (Some(text), None)
+    }
+}
+
+/// Whether the invoking member may create tags: admins and managers always,
+/// plus holders of the guild's configured creator role.
+async fn can_create_tags(ctx: Context<'_>) -> bool {
+    let Some(guild_id) = ctx.guild_id() else {
+        return false;
+    };
+    let Some(member) = ctx.author_member().await else {
+        return false;
+    };
+    if member
+        .permissions
+        .map(|p| p.administrator() || p.manage_guild())
+        .unwrap_or(false)
+    {
+        return true;
+    }
+    let creator_role = {
+        let all = safe_lock(&ctx.data().tags);
+        all.get(&guild_id.to_string())
+            .and_then(|g| g.creator_role.clone())
+    };
+    match creator_role.and_then(|r| r.parse::<u64>().ok()) {
+        Some(role_id) => member.roles.contains(&serenity::RoleId::new(role_id)),
+        None => false,
+    }
+}
+
+async fn autocomplete_tag(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Vec::new();
+    };
+    let partial = partial.to_lowercase();
+    let all = safe_lock(&ctx.data().tags);
+    let mut names: Vec<String> = all
+        .get(&guild_id.to_string())
+        .map(|g| {
+            g.tags
+                .keys()
+                .filter(|n| n.contains(&partial))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names.truncate(25);
+    names
+}
+
+/// 🏷️ Викликати тег сервера
+#[poise::command(slash_command, guild_only)]
+async fn tag(
+    ctx: Context<'_>,
+    #[description = "Назва тегу"]
+    #[autocomplete = "autocomplete_tag"]
+    name: String,
+    #[description = "Текст для {args}"] args: Option<String>,
+) -> Result<(), Error> {
     let guild_id = ctx.guild_id().ok_or("Not in a guild")?;
-    guild_id
-        .edit_member(
-            &ctx.http(),
-            user.id,
-            EditMember::new().enable_communication(),
+    let name = name.trim().to_lowercase();
+
+    let Some(found) = use_tag(ctx.data(), &guild_id.to_string(), &name) else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content(format!("❌ Тег **{}** не знайдено.", name))
+                .ephemeral(true),
         )
         .await?;
-    ctx.say(format!("🔊 Користувача <@{}> розмучено.", user.id))
-        .await?;
+        return Ok(());
+    };
+
+    let server = ctx.guild().map(|g| g.name.clone()).unwrap_or_default();
+    let vars = TagVars {
+        user_id: ctx.author().id.get(),
+        user_name: &ctx.author().name,
+        server: &server,
+        channel_id: ctx.channel_id().get(),
+        args: args.as_deref().unwrap_or(""),
+        uses: found.uses,
+    };
+    let (content, embed) = render_tag(&found, &vars);
+    let mut reply = poise::CreateReply::default().allowed_mentions(tag_allowed_mentions(&vars));
+    if let Some(c) = content {
+        reply = reply.content(c);
+    }
+    if let Some(e) = embed {
+        reply = reply.embed(e);
+    }
+    ctx.send(reply).await?;
     Ok(())
 }
 
-/// [ADMIN] Відправити оголошення в канал
-#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
-async fn admin_announce(ctx: Context<'_>, channel: ChannelId, text: String) -> Result<(), Error> {
-    channel.say(&ctx.http(), text).await?;
-    ctx.send(
-        poise::CreateReply::default()
-            .content("✅ Оголошення надіслано.")
-            .ephemeral(true),
+/// 🏷️ Керування тегами сервера
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands(
+        "tags_create",
+        "tags_edit",
+        "tags_delete",
+        "tags_list",
+        "tags_prefix",
+        "tags_permission"
     )
-    .await?;
+)]
+async fn tags(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
-/// Deletes messages while respecting Discord's rules: bulk delete only accepts
-/// 2–100 messages newer than 14 days. Older messages — and lone messages — are
-/// removed one by one so the command never errors out on those edge cases.
-async fn delete_messages_safely(
-    http: &serenity::Http,
-    channel_id: serenity::ChannelId,
-    messages: &[serenity::Message],
-) -> usize {
-    let cutoff = Utc::now().timestamp() - 14 * 24 * 60 * 60;
-    let mut recent: Vec<serenity::MessageId> = Vec::new();
-    let mut old: Vec<serenity::MessageId> = Vec::new();
-    for m in messages {
-        if m.timestamp.unix_timestamp() >= cutoff {
-            recent.push(m.id);
+/// Створити новий тег
+#[poise::command(slash_command, rename = "create")]
+async fn tags_create(
+    ctx: Context<'_>,
+    #[description = "Назва (без пробілів)"] name: String,
+    #[description = "Текст; плейсхолдери: {user} {user.name} {server} {channel} {args} {uses}"]
+    content: String,
+    #[description = "Відповідати embed-ом"] embed: Option<bool>,
+    #[description = "Заголовок embed-у"] title: Option<String>,
+    #[description = "Колір embed-у, напр. #2ECC71"] color: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
+
+    if !can_create_tags(ctx).await {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ У вас немає прав створювати теги.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    let Some(name) = normalize_tag_name(&name) else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Назва тегу має бути без пробілів і не довше 32 символів.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let created = {
+        let mut all = safe_lock(&ctx.data().tags);
+        let guild = all.entry(guild_id).or_default();
+        if guild.tags.contains_key(&name) {
+            false
         } else {
-            old.push(m.id);
+            guild.tags.insert(
+                name.clone(),
+                Tag {
+                    name: name.clone(),
+                    content,
+                    embed: embed.unwrap_or(false),
+                    title,
+                    color: color.as_deref().and_then(parse_hex_color),
+                    created_by: ctx.author().id.to_string(),
+                    created_at: Utc::now().timestamp(),
+                    uses: 0,
+                },
+            );
+            save_json(TAGS_FILE, &*all);
+            true
         }
-    }
+    };
 
-    let mut deleted = 0;
-    for chunk in recent.chunks(100) {
-        match chunk {
-            [] => {}
-            // Bulk delete requires at least 2 messages; delete a lone one directly.
-            [single] => {
-                if channel_id.delete_message(http, *single).await.is_ok() {
-                    deleted += 1;
-                }
+    let msg = if created {
+        format!("✅ Тег **{}** створено! Виклик: `/tag {}`", name, name)
+    } else {
+        format!("❌ Тег **{}** вже існує. Використай `/tags edit`.", name)
+    };
+    ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Змінити існуючий тег
+#[poise::command(slash_command, rename = "edit")]
+async fn tags_edit(
+    ctx: Context<'_>,
+    #[description = "Назва тегу"]
+    #[autocomplete = "autocomplete_tag"]
+    name: String,
+    #[description = "Новий текст"] content: Option<String>,
+    #[description = "Відповідати embed-ом"] embed: Option<bool>,
+    #[description = "Заголовок embed-у"] title: Option<String>,
+    #[description = "Колір embed-у, напр. #2ECC71"] color: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
+    let name = name.trim().to_lowercase();
+    let is_manager = can_create_tags(ctx).await;
+    let author_id = ctx.author().id.to_string();
+
+    let result = {
+        let mut all = safe_lock(&ctx.data().tags);
+        match all.get_mut(&guild_id).and_then(|g| g.tags.get_mut(&name)) {
+            None => Err("❌ Тег не знайдено."),
+            Some(t) if !is_manager && t.created_by != author_id => {
+                Err("❌ Змінювати тег може лише його автор або адміністратор.")
             }
-            many => {
-                if channel_id.delete_messages(http, many).await.is_ok() {
-                    deleted += many.len();
+            Some(t) => {
+                if let Some(c) = content {
+                    t.content = c;
                 }
+                if let Some(e) = embed {
+                    t.embed = e;
+                }
+                if let Some(tt) = title {
+                    t.title = Some(tt);
+                }
+                if let Some(c) = color.as_deref().and_then(parse_hex_color) {
+                    t.color = Some(c);
+                }
+                save_json(TAGS_FILE, &*all);
+                Ok(())
             }
         }
-    }
-    for id in old {
-        if channel_id.delete_message(http, id).await.is_ok() {
-            deleted += 1;
-        }
-    }
-    deleted
-}
+    };
 
-/// [ADMIN] Видалити повідомлення
-#[poise::command(slash_command, default_member_permissions = "MANAGE_MESSAGES")]
-async fn purge(ctx: Context<'_>, amount: u64) -> Result<(), Error> {
-    ctx.defer_ephemeral().await?;
-    let count = amount.clamp(1, 100);
-    let messages = ctx
-        .channel_id()
-        .messages(&ctx.http(), GetMessages::new().limit(count as u8))
+    let msg = match result {
+        Ok(()) => format!("✅ Тег **{}** оновлено.", name),
+        Err(e) => e.to_string(),
+    };
+    ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
         .await?;
+    Ok(())
+}
 
-    let deleted = delete_messages_safely(ctx.http(), ctx.channel_id(), &messages).await;
-    ctx.say(format!("🧹 Адмін видалив {} повідомлень.", deleted))
+/// Видалити тег
+#[poise::command(slash_command, rename = "delete")]
+async fn tags_delete(
+    ctx: Context<'_>,
+    #[description = "Назва тегу"]
+    #[autocomplete = "autocomplete_tag"]
+    name: String,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
+    let name = name.trim().to_lowercase();
+    let is_manager = can_create_tags(ctx).await;
+    let author_id = ctx.author().id.to_string();
+
+    let result = {
+        let mut all = safe_lock(&ctx.data().tags);
+        match all.get_mut(&guild_id) {
+            Some(g) => match g.tags.get(&name) {
+                None => Err("❌ Тег не знайдено."),
+                Some(t) if !is_manager && t.created_by != author_id => {
+                    Err("❌ Видаляти тег може лише його автор або адміністратор.")
+                }
+                Some(_) => {
+                    g.tags.remove(&name);
+                    save_json(TAGS_FILE, &*all);
+                    Ok(())
+                }
+            },
+            None => Err("❌ Тег не знайдено."),
+        }
+    };
+
+    let msg = match result {
+        Ok(()) => format!("🗑️ Тег **{}** видалено.", name),
+        Err(e) => e.to_string(),
+    };
+    ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
         .await?;
     Ok(())
 }
 
-/// [ADMIN] Видалити повідомлення бота
-#[poise::command(slash_command, default_member_permissions = "MANAGE_MESSAGES")]
-async fn clean(ctx: Context<'_>) -> Result<(), Error> {
-    ctx.defer_ephemeral().await?;
-    let messages = ctx
-        .channel_id()
-        .messages(&ctx.http(), GetMessages::new().limit(100))
+/// Список тегів сервера
+#[poise::command(slash_command, rename = "list")]
+async fn tags_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
+
+    let (mut list, prefix) = {
+        let all = safe_lock(&ctx.data().tags);
+        match all.get(&guild_id) {
+            Some(g) => (
+                g.tags
+                    .values()
+                    .map(|t| (t.name.clone(), t.uses))
+                    .collect::<Vec<_>>(),
+                g.prefix.clone(),
+            ),
+            None => (Vec::new(), None),
+        }
+    };
+
+    if list.is_empty() {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("📋 На сервері ще немає тегів.")
+                .ephemeral(true),
+        )
         .await?;
-    let bot_id = ctx.framework().bot_id;
-    let to_delete: Vec<serenity::Message> = messages
-        .into_iter()
-        .filter(|m| m.author.id == bot_id)
+        return Ok(());
+    }
+
+    list.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let description: String = list
+        .iter()
+        .take(50)
+        .map(|(name, uses)| format!("• `{}` — {} викл.\n", name, uses))
         .collect();
 
-    let deleted = delete_messages_safely(ctx.http(), ctx.channel_id(), &to_delete).await;
-    ctx.say(format!("🧹 Видалено {} моїх повідомлень.", deleted))
+    let footer = match prefix {
+        Some(p) => format!("Всього: {} | Префікс: {}", list.len(), p),
+        None => format!("Всього: {}", list.len()),
+    };
+    let embed = CreateEmbed::new()
+        .title("🏷️ Теги сервера")
+        .description(description)
+        .color(0x3498DB)
+        .footer(CreateEmbedFooter::new(footer));
+
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
         .await?;
     Ok(())
 }
 
-/// 📊 Створити голосування
-#[poise::command(slash_command)]
-async fn poll(ctx: Context<'_>, question: String) -> Result<(), Error> {
-    let embed = CreateEmbed::new()
-        .title("📊 Голосування")
-        .description(format!("**{}**", question))
-        .colour(0xF1C40F)
-        .footer(CreateEmbedFooter::new(format!(
-            "Автор: {}",
-            ctx.author().name
-        )));
+/// [ADMIN] Встановити префікс для виклику тегів у чаті
+#[poise::command(
+    slash_command,
+    rename = "prefix",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn tags_prefix(
+    ctx: Context<'_>,
+    #[description = "Префікс, напр. ! (порожньо — вимкнути)"] prefix: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
+    let prefix = prefix
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
 
-    let msg = ctx.send(poise::CreateReply::default().embed(embed)).await?;
-    let m = msg.message().await?;
-    m.react(&ctx.http(), '👍').await?;
-    m.react(&ctx.http(), '👎').await?;
+    {
+        let mut all = safe_lock(&ctx.data().tags);
+        all.entry(guild_id).or_default().prefix = prefix.clone();
+        save_json(TAGS_FILE, &*all);
+    }
+
+    let msg = match prefix {
+        Some(p) => format!("✅ Теги тепер можна викликати як `{}назва`.", p),
+        None => "✅ Виклик тегів через префікс вимкнено.".to_string(),
+    };
+    ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
+        .await?;
     Ok(())
 }
 
-/// 🖼️ Показати аватар користувача
-#[poise::command(slash_command)]
-async fn avatar(ctx: Context<'_>, user: Option<serenity::User>) -> Result<(), Error> {
-    let u = user.as_ref().unwrap_or_else(|| ctx.author());
-    ctx.send(
-        poise::CreateReply::default().embed(
-            CreateEmbed::new()
-                .title(u.name.clone())
-                .image(u.face())
-                .colour(0x99AAB5),
-        ),
-    )
-    .await?;
+/// [ADMIN] Роль, якій дозволено створювати теги
+#[poise::command(
+    slash_command,
+    rename = "permission",
+    required_permissions = "ADMINISTRATOR"
+)]
+async fn tags_permission(
+    ctx: Context<'_>,
+    #[description = "Роль (порожньо — лише адміністратори)"] role: Option<serenity::Role>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
+
+    {
+        let mut all = safe_lock(&ctx.data().tags);
+        all.entry(guild_id).or_default().creator_role = role.as_ref().map(|r| r.id.to_string());
+        save_json(TAGS_FILE, &*all);
+    }
+
+    let msg = match role {
+        Some(r) => format!("✅ Теги можуть створювати учасники з роллю **{}**.", r.name),
+        None => "✅ Теги можуть створювати лише адміністратори.".to_string(),
+    };
+    ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
+        .await?;
     Ok(())
 }
 
-/// 📊 Переглянути профіль і статистику
-#[poise::command(slash_command)]
-async fn rank(ctx: Context<'_>, user: Option<serenity::User>) -> Result<(), Error> {
-    let target = user.as_ref().unwrap_or_else(|| ctx.author());
-    let (level, xp, minutes, chips) = {
-        let users = safe_lock(&ctx.data().users);
-        match users.get(&target.id.to_string()) {
-            Some(p) => (p.level, p.xp, p.minutes, p.chips),
-            None => (0, 0, 0, 100),
+// --- АВТО-ВІДПОВІДІ ---
+
+/// Checks a message against a trigger. Exact/contains patterns are stored
+/// lowercased; regex patterns are compiled at most once and kept in `cache`,
+/// the same way `banned_regex_cache` works for banned words.
+fn responder_matches(
+    r: &AutoResponder,
+    content: &str,
+    content_lower: &str,
+    cache: &mut HashMap<String, Regex>,
+) -> bool {
+    match r.match_type {
+        TriggerMatch::Exact => content_lower.trim() == r.pattern,
+        TriggerMatch::Contains => content_lower.contains(&r.pattern),
+        TriggerMatch::Regex => {
+            if !cache.contains_key(&r.pattern) {
+                match Regex::new(&r.pattern) {
+                    Ok(re) => {
+                        cache.insert(r.pattern.clone(), re);
+                    }
+                    Err(_) => return false,
+                }
+            }
+            cache
+                .get(&r.pattern)
+                .map(|re| re.is_match(content))
+                .unwrap_or(false)
         }
-    };
-    let needed = get_xp_needed(level);
-    let pct = ((xp as f64 / needed as f64) * 10.0) as usize;
-    let bar = format!(
-        "{}{}",
-        "🟩".repeat(pct.min(10)),
-        "⬜".repeat(10 - pct.min(10))
-    );
-    let role_name = get_role_for_level(level).unwrap_or("Немає");
+    }
+}
 
-    ctx.send(
-        poise::CreateReply::default().embed(
-            CreateEmbed::new()
-                .title(format!("Картка {}", target.name))
-                .thumbnail(target.face())
-                .fields(vec![
-                    ("Звання", role_name, false),
-                    ("Рівень", &level.to_string(), true),
-                    ("Гривні", &format!("🪙 {}", chips), true),
-                    ("XP", &format!("{}/{}", xp, needed), true),
-                    (
-                        "В голосі",
-                        &format!("{} год {} хв", minutes / 60, minutes % 60),
-                        true,
-                    ),
-                    ("Прогрес", &bar, false),
-                ])
-                .colour(0x006400),
-        ),
+/// Whether `key` is off cooldown at `now`.
+fn cooldown_ready(cooldowns: &HashMap<String, i64>, key: &str, now: i64) -> bool {
+    cooldowns.get(key).is_none_or(|until| now >= *until)
+}
+
+/// Starts cooldowns (key, seconds) at `now` and drops the ones that have
+/// run out, so the map never outgrows the currently active cooldowns.
+fn start_cooldowns(cooldowns: &mut HashMap<String, i64>, keys: Vec<(String, i64)>, now: i64) {
+    cooldowns.retain(|_, until| *until > now);
+    for (key, secs) in keys.into_iter().filter(|(_, secs)| *secs > 0) {
+        cooldowns.insert(key, now + secs);
+    }
+}
+
+/// Finds the first trigger that matches the message and is off cooldown, and
+/// records the firing. Everything happens under the locks so two quick
+/// messages can't both slip past a cooldown.
+fn find_autoresponder(data: &Data, msg: &serenity::Message) -> Option<AutoResponder> {
+    let guild_id = msg.guild_id?.to_string();
+    let channel_id = msg.channel_id.to_string();
+    let user_id = msg.author.id.to_string();
+    let content_lower = msg.content.to_lowercase();
+    let now = Utc::now().timestamp();
+
+    let responders = safe_lock(&data.autoresponders);
+    let mut cache = safe_lock(&data.responder_regex_cache);
+    let mut cooldowns = safe_lock(&data.responder_cooldowns);
+
+    for r in responders.iter().filter(|r| r.guild_id == guild_id) {
+        if !r.channels.is_empty() && !r.channels.contains(&channel_id) {
+            continue;
+        }
+        if !responder_matches(r, &msg.content, &content_lower, &mut cache) {
+            continue;
+        }
+        let trigger_key = format!("{}:{}", r.guild_id, r.id);
+        let user_key = format!("{}:{}", trigger_key, user_id);
+        if !cooldown_ready(&cooldowns, &trigger_key, now)
+            || !cooldown_ready(&cooldowns, &user_key, now)
+        {
+            continue;
+        }
+        start_cooldowns(
+            &mut cooldowns,
+            vec![
+                (trigger_key, r.cooldown_secs),
+                (user_key, r.user_cooldown_secs),
+            ],
+            now,
+        );
+        return Some(r.clone());
+    }
+    None
+}
+
+/// 🤖 [ADMIN] Авто-відповіді на повідомлення
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR",
+    subcommands(
+        "autoresponder_add",
+        "autoresponder_remove",
+        "autoresponder_list",
+        "autoresponder_scope"
     )
-    .await?;
+)]
+async fn autoresponder(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
-/// 🎁 Отримати щоденну винагороду
-#[poise::command(slash_command)]
-async fn daily(ctx: Context<'_>) -> Result<(), Error> {
-    let user_id = ctx.author().id.to_string();
-    let now = Utc::now().timestamp();
-
-    let result = {
-        let mut users = safe_lock(&ctx.data().users);
-        let profile = users
-            .entry(user_id.clone())
-            .or_insert(create_default_profile());
-
-        if now - profile.last_daily < 86400 {
-            let wait = 86400 - (now - profile.last_daily);
-            Err(wait)
-        } else {
-            let bonus = rand::thread_rng().gen_range(50..150);
-            profile.chips = profile.chips.saturating_add(bonus);
-            profile.last_daily = now;
-            save_json(USERS_FILE, &*users);
-            Ok(bonus)
-        }
-    };
+/// Додати авто-відповідь
+#[poise::command(slash_command, rename = "add")]
+#[allow(clippy::too_many_arguments)]
+async fn autoresponder_add(
+    ctx: Context<'_>,
+    #[description = "Текст або регулярний вираз"] pattern: String,
+    #[description = "Як зіставляти"] match_type: TriggerMatch,
+    #[description = "Відповідь (плейсхолдери як у тегах)"] response: Option<String>,
+    #[description = "Реакція, напр. 👍"] reaction: Option<String>,
+    #[description = "Лише в цьому каналі"] channel: Option<serenity::ChannelId>,
+    #[description = "Кулдаун тригера, сек"] cooldown: Option<i64>,
+    #[description = "Кулдаун для користувача, сек"] user_cooldown: Option<i64>,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
 
-    match result {
-        Err(wait) => {
+    if response.is_none() && reaction.is_none() {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("❌ Вкажи відповідь або реакцію.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    if let Some(r) = &reaction {
+        if r.parse::<serenity::ReactionType>().is_err() {
             ctx.send(
                 poise::CreateReply::default()
-                    .content(format!(
-                        "⏳ Чекай **{} год {} хв**.",
-                        wait / 3600,
-                        (wait % 3600) / 60
-                    ))
+                    .content("❌ Невірна реакція.")
                     .ephemeral(true),
             )
             .await?;
-        }
-        Ok(bonus) => {
-            ctx.say(format!(
-                "🎁 Ти отримав **{} гривень** 💰! Приходь завтра.",
-                bonus
-            ))
-            .await?;
+            return Ok(());
         }
     }
+
+    let pattern = match match_type {
+        TriggerMatch::Regex => {
+            if let Err(e) = Regex::new(&pattern) {
+                ctx.send(
+                    poise::CreateReply::default()
+                        .content(format!("❌ Невірний регулярний вираз: {}", e))
+                        .ephemeral(true),
+                )
+                .await?;
+                return Ok(());
+            }
+            pattern
+        }
+        _ => pattern.trim().to_lowercase(),
+    };
+
+    let id = {
+        let mut responders = safe_lock(&ctx.data().autoresponders);
+        let id = responders
+            .iter()
+            .filter(|r| r.guild_id == guild_id)
+            .map(|r| r.id)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        responders.push(AutoResponder {
+            id,
+            guild_id,
+            pattern,
+            match_type,
+            response,
+            reaction,
+            channels: channel.map(|c| vec![c.to_string()]).unwrap_or_default(),
+            cooldown_secs: cooldown.unwrap_or(0).max(0),
+            user_cooldown_secs: user_cooldown.unwrap_or(0).max(0),
+        });
+        save_json(AUTORESPONDERS_FILE, &*responders);
+        id
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!("✅ Авто-відповідь **#{}** додано.", id))
+            .ephemeral(true),
+    )
+    .await?;
     Ok(())
 }
 
-/// 🎰 Спробуй удачу в казино
-#[poise::command(slash_command)]
-async fn casino(ctx: Context<'_>, amount: u64) -> Result<(), Error> {
-    let user_id = ctx.author().id.to_string();
-
-    let calc_result = {
-        let mut users = safe_lock(&ctx.data().users);
-        let profile = users.entry(user_id).or_insert(create_default_profile());
+/// Видалити авто-відповідь
+#[poise::command(slash_command, rename = "remove")]
+async fn autoresponder_remove(
+    ctx: Context<'_>,
+    #[description = "Номер авто-відповіді"] id: u32,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
 
-        if profile.chips < amount || amount == 0 {
-            None
-        } else if rand::thread_rng().gen_bool(0.45) {
-            profile.chips = profile.chips.saturating_add(amount);
-            Some((format!("🎰 Виграв **{} гривень**! 🤑", amount), true))
-        } else {
-            profile.chips = profile.chips.saturating_sub(amount);
-            Some((format!("🎰 Програв **{} гривень**. 📉", amount), false))
+    let removed = {
+        let mut responders = safe_lock(&ctx.data().autoresponders);
+        let before = responders.len();
+        responders.retain(|r| !(r.guild_id == guild_id && r.id == id));
+        let removed = responders.len() != before;
+        if removed {
+            save_json(AUTORESPONDERS_FILE, &*responders);
         }
+        removed
     };
 
-    match calc_result {
-        None => {
-            ctx.send(
-                poise::CreateReply::default()
-                    .content("❌ Недостатньо гривень або ставка 0.")
-                    .ephemeral(true),
-            )
-            .await?;
-        }
-        Some((msg, _)) => {
-            {
-                let users = safe_lock(&ctx.data().users);
-                save_json(USERS_FILE, &*users);
-            }
-            ctx.say(msg).await?;
+    let msg = if removed {
+        format!("🗑️ Авто-відповідь **#{}** видалено.", id)
+    } else {
+        format!("❌ Авто-відповідь **#{}** не знайдено.", id)
+    };
+    ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Увімкнути/вимкнути авто-відповідь у каналі
+#[poise::command(slash_command, rename = "scope")]
+async fn autoresponder_scope(
+    ctx: Context<'_>,
+    #[description = "Номер авто-відповіді"] id: u32,
+    #[description = "Канал для додавання/прибирання"] channel: serenity::ChannelId,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
+    let channel_str = channel.to_string();
+
+    let result = {
+        let mut responders = safe_lock(&ctx.data().autoresponders);
+        let result = responders
+            .iter_mut()
+            .find(|r| r.guild_id == guild_id && r.id == id)
+            .map(|r| {
+                if r.channels.contains(&channel_str) {
+                    r.channels.retain(|c| c != &channel_str);
+                    false
+                } else {
+                    r.channels.push(channel_str.clone());
+                    true
+                }
+            });
+        if result.is_some() {
+            save_json(AUTORESPONDERS_FILE, &*responders);
         }
-    }
+        result
+    };
+
+    let msg = match result {
+        Some(true) => format!(
+            "✅ Авто-відповідь **#{}** тепер працює в <#{}>.",
+            id, channel
+        ),
+        Some(false) => format!("✅ <#{}> прибрано з авто-відповіді **#{}**.", channel, id),
+        None => format!("❌ Авто-відповідь **#{}** не знайдено.", id),
+    };
+    ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
+        .await?;
     Ok(())
 }
 
-/// 🃏 Зіграй у блекджек
-#[poise::command(slash_command)]
-async fn blackjack(ctx: Context<'_>, bet: u64) -> Result<(), Error> {
-    let uid_str = ctx.author().id.to_string();
+/// Список авто-відповідей
+#[poise::command(slash_command, rename = "list")]
+async fn autoresponder_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
 
-    let can_play = {
-        let users = safe_lock(&ctx.data().users);
-        let p = users.get(&uid_str);
-        p.is_some() && p.unwrap().chips >= bet && bet > 0
+    let list: Vec<AutoResponder> = {
+        let responders = safe_lock(&ctx.data().autoresponders);
+        responders
+            .iter()
+            .filter(|r| r.guild_id == guild_id)
+            .cloned()
+            .collect()
     };
 
-    if !can_play {
+    if list.is_empty() {
         ctx.send(
             poise::CreateReply::default()
-                .content("❌ Недостатньо гривень.")
+                .content("📋 Авто-відповідей немає.")
                 .ephemeral(true),
         )
         .await?;
         return Ok(());
     }
 
-    let mut deck: Vec<u8> = vec![2, 3, 4, 5, 6, 7, 8, 9, 10, 10, 10, 10, 11]
-        .iter()
-        .cycle()
-        .take(52)
-        .cloned()
-        .collect();
-    use rand::seq::SliceRandom;
-    deck.shuffle(&mut rand::thread_rng());
-    let (mut player, mut dealer) = (
-        vec![deck.pop().unwrap_or(10), deck.pop().unwrap_or(10)],
-        vec![deck.pop().unwrap_or(10), deck.pop().unwrap_or(10)],
-    );
-    fn calc(h: &[u8]) -> u8 {
-        let mut s: u16 = h.iter().map(|&x| x as u16).sum();
-        let mut a = h.iter().filter(|&&x| x == 11).count();
-        while s > 21 && a > 0 {
-            s -= 10;
-            a -= 1;
-        }
-        s as u8
-    }
-    let uuid = ctx.id();
-    let (hit, stand) = (format!("{}h", uuid), format!("{}s", uuid));
-    let make_embed = |p: &Vec<u8>, d: &Vec<u8>, hide: bool, t: &str, c: u32| {
-        let dv = if hide {
-            format!("[{}, ?]", d[0])
+    let mut description = String::new();
+    for r in list.iter().take(25) {
+        let kind = match r.match_type {
+            TriggerMatch::Exact => "точний",
+            TriggerMatch::Contains => "містить",
+            TriggerMatch::Regex => "regex",
+        };
+        let scope = if r.channels.is_empty() {
+            "усі канали".to_string()
         } else {
-            format!("{:?} ({})", d, calc(d))
+            r.channels
+                .iter()
+                .map(|c| format!("<#{}>", c))
+                .collect::<Vec<_>>()
+                .join(", ")
         };
-        CreateEmbed::new()
-            .title(t)
-            .colour(c)
-            .field(format!("Твоя ({})", calc(p)), format!("{:?}", p), true)
-            .field("Дилер", dv, true)
-            .footer(CreateEmbedFooter::new(format!("Ставка: {} гривень", bet)))
-    };
-    let btns = vec![CreateActionRow::Buttons(vec![
-        CreateButton::new(&hit)
-            .label("Ще")
-            .style(ButtonStyle::Success),
-        CreateButton::new(&stand)
-            .label("Все")
-            .style(ButtonStyle::Primary),
-    ])];
-    let msg = ctx
-        .send(
-            poise::CreateReply::default()
-                .embed(make_embed(&player, &dealer, true, "🃏 Блекджек", 0x3498DB))
-                .components(btns),
-        )
+        let action = match (&r.response, &r.reaction) {
+            (Some(_), Some(e)) => format!("відповідь + {}", e),
+            (Some(_), None) => "відповідь".to_string(),
+            (None, Some(e)) => format!("реакція {}", e),
+            (None, None) => "—".to_string(),
+        };
+        description.push_str(&format!(
+            "**#{}** `{}` ({}) → {}\n└ {} | кулдаун {}с / {}с на користувача\n",
+            r.id, r.pattern, kind, action, scope, r.cooldown_secs, r.user_cooldown_secs
+        ));
+    }
+
+    let embed = CreateEmbed::new()
+        .title("🤖 Авто-відповіді")
+        .description(description)
+        .color(0x3498DB)
+        .footer(CreateEmbedFooter::new(format!("Всього: {}", list.len())));
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
         .await?;
-    let mut ended = false;
-    let mut res = 0;
+    Ok(())
+}
 
-    while let Some(m) = msg
-        .message()
-        .await?
-        .await_component_interaction(&ctx.serenity_context().shard)
-        .timeout(Duration::from_secs(60))
-        .await
-    {
-        if m.user.id != ctx.author().id {
-            m.defer(&ctx.http()).await?;
+// --- ЗАПЛАНОВАНІ ОГОЛОШЕННЯ ---
+
+/// A parsed 5-field cron expression (`minute hour day month weekday`) with
+/// support for `*`, lists, ranges and `/step`. Weekdays count from Sunday = 0
+/// (7 is also Sunday). Evaluated in Kyiv local time.
+struct CronSpec {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days: Vec<bool>,
+    months: Vec<bool>,
+    weekdays: Vec<bool>,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+fn parse_cron_field(field: &str, min: u32, max: u32) -> Option<Vec<bool>> {
+    let mut set = vec![false; max as usize + 1];
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => (r, s.parse::<u32>().ok().filter(|s| *s > 0)?),
+            None => (part, 1),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (a.parse().ok()?, b.parse().ok()?)
+        } else {
+            let v = range.parse().ok()?;
+            // "5/15" означає "з 5 до кінця з кроком 15"
+            (v, if step > 1 { max } else { v })
+        };
+        if lo < min || hi > max || lo > hi {
+            return None;
+        }
+        for v in (lo..=hi).step_by(step as usize) {
+            set[v as usize] = true;
+        }
+    }
+    Some(set)
+}
+
+fn parse_cron(expr: &str) -> Option<CronSpec> {
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    let [minute, hour, day, month, weekday] = fields[..] else {
+        return None;
+    };
+    let mut weekdays = parse_cron_field(weekday, 0, 7)?;
+    if weekdays[7] {
+        weekdays[0] = true;
+    }
+    weekdays.truncate(7);
+    Some(CronSpec {
+        minutes: parse_cron_field(minute, 0, 59)?,
+        hours: parse_cron_field(hour, 0, 23)?,
+        days: parse_cron_field(day, 1, 31)?,
+        months: parse_cron_field(month, 1, 12)?,
+        weekdays,
+        any_day: day == "*",
+        any_weekday: weekday == "*",
+    })
+}
+
+/// Next Kyiv-local minute matching `spec`, strictly after `after` (unix secs).
+fn cron_next_after(spec: &CronSpec, after: i64) -> Option<i64> {
+    let start = Kyiv.timestamp_opt(after, 0).single()?.naive_local();
+    let mut t = start.with_second(0)?.with_nanosecond(0)? + chrono::Duration::minutes(1);
+    let limit = t + chrono::Duration::days(366 * 5);
+
+    while t < limit {
+        let day_ok = spec.days[t.day() as usize];
+        let weekday_ok = spec.weekdays[t.weekday().num_days_from_sunday() as usize];
+        // Як у класичному cron: якщо обмежено і день, і день тижня — достатньо одного.
+        let date_ok = match (spec.any_day, spec.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday_ok,
+            (false, true) => day_ok,
+            (false, false) => day_ok || weekday_ok,
+        };
+        if !spec.months[t.month() as usize] || !date_ok {
+            t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
             continue;
         }
-        if m.data.custom_id == hit {
-            player.push(deck.pop().unwrap_or(10));
-            if calc(&player) > 21 {
-                ended = true;
-                res = -1;
-            }
-        } else if m.data.custom_id == stand {
-            ended = true;
-            while calc(&dealer) < 17 {
-                if let Some(card) = deck.pop() {
-                    dealer.push(card);
-                } else {
-                    break;
+        if !spec.hours[t.hour() as usize] {
+            t = t.date().and_hms_opt(t.hour(), 0, 0)? + chrono::Duration::hours(1);
+            continue;
+        }
+        if spec.minutes[t.minute() as usize] {
+            // Під час переходу на літній час частини годин не існує — пропускаємо.
+            if let Some(dt) = Kyiv.from_local_datetime(&t).earliest() {
+                if dt.timestamp() > after {
+                    return Some(dt.timestamp());
                 }
             }
-            let (ps, ds) = (calc(&player), calc(&dealer));
-            if ds > 21 || ps > ds {
-                res = 1;
-            } else if ps < ds {
-                res = -1;
-            }
         }
-        let (t, c) = if !ended {
-            ("🃏 Блекджек", 0x3498DB)
-        } else {
-            match res {
-                1 => ("🎉 Перемога!", 0x2ECC71),
-                -1 => ("📉 Програш", 0xE74C3C),
-                _ => ("🤝 Нічия", 0xF1C40F),
+        t += chrono::Duration::minutes(1);
+    }
+    None
+}
+
+/// Next run strictly after `after`, or `None` for one-off schedules.
+fn next_occurrence(recurrence: &Recurrence, after: i64) -> Option<i64> {
+    let expr = match recurrence {
+        Recurrence::Once => return None,
+        Recurrence::Daily { hour, minute } => format!("{} {} * * *", minute, hour),
+        Recurrence::Weekly {
+            weekdays,
+            hour,
+            minute,
+        } => {
+            // У нас 0 = понеділок, у cron 0 = неділя.
+            let days: Vec<String> = weekdays.iter().map(|d| ((d + 1) % 7).to_string()).collect();
+            format!("{} {} * * {}", minute, hour, days.join(","))
+        }
+        Recurrence::Cron { expr } => expr.clone(),
+    };
+    cron_next_after(&parse_cron(&expr)?, after)
+}
+
+/// Parses `HH:MM`.
+fn parse_time_of_day(s: &str) -> Option<(u32, u32)> {
+    let (h, m) = s.trim().split_once(':')?;
+    let (h, m) = (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?);
+    (h < 24 && m < 60).then_some((h, m))
+}
+
+/// Parses a Kyiv-local date/time into a unix timestamp. Accepts `HH:MM`
+/// (next such time), `DD.MM HH:MM`, `DD.MM.YYYY HH:MM` and `YYYY-MM-DD HH:MM`.
+fn parse_kyiv_datetime(input: &str, now: i64) -> Option<i64> {
+    let input = input.trim();
+    let today = Kyiv.timestamp_opt(now, 0).single()?.date_naive();
+
+    let (date, time) = match input.split_once(char::is_whitespace) {
+        Some((d, t)) => {
+            let d = d.trim();
+            let date = if let Ok(date) = NaiveDate::parse_from_str(d, "%Y-%m-%d") {
+                date
+            } else if let Ok(date) = NaiveDate::parse_from_str(d, "%d.%m.%Y") {
+                date
+            } else {
+                let (day, month) = d.split_once('.')?;
+                let date =
+                    NaiveDate::from_ymd_opt(today.year(), month.parse().ok()?, day.parse().ok()?)?;
+                // Дата без року, що вже минула, — наступного року.
+                if date < today {
+                    NaiveDate::from_ymd_opt(today.year() + 1, date.month(), date.day())?
+                } else {
+                    date
+                }
+            };
+            (Some(date), t)
+        }
+        None => (None, input),
+    };
+
+    let (hour, minute) = parse_time_of_day(time)?;
+    let at = |d: NaiveDate| {
+        Kyiv.from_local_datetime(&d.and_hms_opt(hour, minute, 0)?)
+            .earliest()
+            .map(|dt| dt.timestamp())
+    };
+    match date {
+        Some(d) => at(d),
+        None => {
+            let ts = at(today)?;
+            if ts > now {
+                Some(ts)
+            } else {
+                at(today.succ_opt()?)
             }
-        };
-        m.create_response(
-            &ctx.http(),
-            serenity::CreateInteractionResponse::UpdateMessage(
-                serenity::CreateInteractionResponseMessage::new()
-                    .embed(make_embed(&player, &dealer, !ended && res == 0, t, c))
-                    .components(if ended {
-                        vec![]
-                    } else {
-                        vec![CreateActionRow::Buttons(vec![
-                            CreateButton::new(&hit)
-                                .label("Ще")
-                                .style(ButtonStyle::Success),
-                            CreateButton::new(&stand)
-                                .label("Все")
-                                .style(ButtonStyle::Primary),
-                        ])]
-                    }),
-            ),
-        )
-        .await?;
-        if ended {
-            break;
         }
     }
-    if ended && res != 0 {
-        let mut users = safe_lock(&ctx.data().users);
-        let p = users.entry(uid_str).or_insert(create_default_profile());
-        if res == 1 {
-            p.chips = p.chips.saturating_add(bet);
-        } else {
-            p.chips = p.chips.saturating_sub(bet);
+}
+
+/// Parses weekdays like `пн,ср,пт`, `mon,wed` or `1,3,5` (1 = Monday)
+/// into our 0 = Monday indices.
+fn parse_weekdays(input: &str) -> Option<Vec<u32>> {
+    let mut days = Vec::new();
+    for part in input.split(',') {
+        let d = match part.trim().to_lowercase().as_str() {
+            "пн" | "mon" | "1" => 0,
+            "вт" | "tue" | "2" => 1,
+            "ср" | "wed" | "3" => 2,
+            "чт" | "thu" | "4" => 3,
+            "пт" | "fri" | "5" => 4,
+            "сб" | "sat" | "6" => 5,
+            "нд" | "sun" | "7" => 6,
+            _ => return None,
+        };
+        if !days.contains(&d) {
+            days.push(d);
         }
-        save_json(USERS_FILE, &*users);
     }
-    Ok(())
+    days.sort();
+    (!days.is_empty()).then_some(days)
 }
 
-/// 🛒 Магазин бустерів XP
-#[poise::command(slash_command, rename = "shop")]
-async fn shop(ctx: Context<'_>) -> Result<(), Error> {
-    let user_id = ctx.author().id.to_string();
-    let (chips, x2_until, x5_until) = {
-        let users = safe_lock(&ctx.data().users);
-        match users.get(&user_id) {
-            Some(p) => (p.chips, p.xp_booster_x2_until, p.xp_booster_x5_until),
-            None => (100, 0, 0),
+fn describe_recurrence(recurrence: &Recurrence) -> String {
+    const NAMES: [&str; 7] = ["пн", "вт", "ср", "чт", "пт", "сб", "нд"];
+    match recurrence {
+        Recurrence::Once => "один раз".to_string(),
+        Recurrence::Daily { hour, minute } => format!("щодня о {:02}:{:02}", hour, minute),
+        Recurrence::Weekly {
+            weekdays,
+            hour,
+            minute,
+        } => {
+            let days: Vec<&str> = weekdays
+                .iter()
+                .filter_map(|d| NAMES.get(*d as usize).copied())
+                .collect();
+            format!("{} о {:02}:{:02}", days.join(", "), hour, minute)
         }
-    };
+        Recurrence::Cron { expr } => format!("cron `{}`", expr),
+    }
+}
 
-    let now = Utc::now().timestamp();
-    let mut active_booster = "Немає активних бустерів".to_string();
+fn embed_from_template(t: &EmbedTemplate) -> CreateEmbed {
+    let mut embed = CreateEmbed::new().color(t.color.unwrap_or(0x3498DB));
+    if let Some(title) = &t.title {
+        embed = embed.title(title);
+    }
+    if let Some(description) = &t.description {
+        embed = embed.description(description);
+    }
+    if let Some(url) = &t.url {
+        embed = embed.url(url);
+    }
+    if let Some(image) = &t.image {
+        embed = embed.image(image);
+    }
+    if let Some(thumbnail) = &t.thumbnail {
+        embed = embed.thumbnail(thumbnail);
+    }
+    if let Some(footer) = &t.footer {
+        embed = embed.footer(CreateEmbedFooter::new(footer));
+    }
+    embed.fields(
+        t.fields
+            .iter()
+            .map(|f| (f.name.clone(), f.value.clone(), f.inline)),
+    )
+}
 
-    if now < x5_until {
-        let hours_left = (x5_until - now) / 3600;
-        active_booster = format!("🚀 **x5 XP бустер** (залишилось {} год)", hours_left);
-    } else if now < x2_until {
-        let hours_left = (x2_until - now) / 3600;
-        active_booster = format!("⚡ **x2 XP бустер** (залишилось {} год)", hours_left);
+fn build_announcement(content: Option<&str>, embed: Option<&EmbedTemplate>) -> CreateMessage {
+    let mut msg = CreateMessage::new();
+    if let Some(c) = content {
+        msg = msg.content(c);
+    }
+    if let Some(e) = embed {
+        msg = msg.embed(embed_from_template(e));
     }
+    msg
+}
 
-    let embed = CreateEmbed::new()
-        .title("🛒 Магазин бустерів XP")
-        .description(format!("**Твої гривні:** 💰 {}\n**Активний бустер:** {}", chips, active_booster))
-        .color(0xF1C40F)
-        .field("⚡ x2 XP Бустер", 
-            "**Ціна:** 💰 2000 гривень\n**Тривалість:** 24 години\n**Ефект:** Подвоює отримання XP\n\nВикористовуй `/buy_booster x2`", 
-            false)
-        .field("🚀 x5 XP Бустер", 
-            "**Ціна:** 💰 5000 гривень\n**Тривалість:** 24 години\n**Ефект:** Збільшує отримання XP в 5 разів!\n\nВикористовуй `/buy_booster x5`", 
-            false)
-        .footer(CreateEmbedFooter::new("💡 Бустери допоможуть швидше прокачатися!"));
+/// Sends every schedule whose time has come and advances it. Runs from
+/// `background_tasks`; `next_run` is persisted, so schedules survive restarts.
+async fn fire_due_schedules(ctx: &serenity::Context, data: &Data) {
+    let now = Utc::now().timestamp();
 
-    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    let due: Vec<ScheduledAnnouncement> = {
+        let mut schedules = safe_lock(&data.schedules);
+        let mut due = Vec::new();
+        let mut finished = Vec::new();
+        let mut changed = false;
+
+        for s in schedules.iter_mut() {
+            if s.paused || s.next_run > now {
+                continue;
+            }
+            let missed = now - s.next_run > SCHEDULE_GRACE_SECS;
+            if missed && !s.catch_up {
+                info!("⏭️ Пропущено оголошення #{} (бот був офлайн)", s.id);
+            } else {
+                due.push(s.clone());
+                s.last_run = Some(now);
+            }
+            // Наступний запуск рахуємо від «зараз», щоб після довгого офлайну
+            // не надсилати одне й те саме багато разів.
+            match next_occurrence(&s.recurrence, now) {
+                Some(next) => s.next_run = next,
+                None => finished.push(s.id),
+            }
+            changed = true;
+        }
+
+        // Одноразові оголошення після запуску більше не потрібні.
+        schedules.retain(|s| !finished.contains(&s.id));
+        if changed {
+            save_json(SCHEDULES_FILE, &*schedules);
+        }
+        due
+    };
+
+    for s in due {
+        let Ok(channel_num) = s.channel_id.parse::<u64>() else {
+            continue;
+        };
+        let msg = build_announcement(s.content.as_deref(), s.embed.as_ref());
+        match ChannelId::new(channel_num)
+            .send_message(&ctx.http, msg)
+            .await
+        {
+            Ok(_) => info!("📢 Надіслано заплановане оголошення #{}", s.id),
+            Err(e) => warn!("⚠️ Не вдалося надіслати оголошення #{}: {}", s.id, e),
+        }
+    }
+}
+
+/// 🗓️ [ADMIN] Заплановані оголошення
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR",
+    subcommands(
+        "schedule_create",
+        "schedule_list",
+        "schedule_pause",
+        "schedule_delete"
+    )
+)]
+async fn schedule(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
-/// 💳 Купити бустер XP
-#[poise::command(slash_command, rename = "buy_booster")]
-async fn buy_booster(ctx: Context<'_>, booster_type: String) -> Result<(), Error> {
-    let user_id = ctx.author().id.to_string();
+/// Запланувати оголошення (час за Києвом)
+#[poise::command(slash_command, rename = "create")]
+#[allow(clippy::too_many_arguments)]
+async fn schedule_create(
+    ctx: Context<'_>,
+    #[description = "Канал для оголошення"] channel: ChannelId,
+    #[description = "Як часто"] recurrence: RecurrenceKind,
+    #[description = "Текст оголошення"] text: String,
+    #[description = "Час: HH:MM, DD.MM HH:MM або YYYY-MM-DD HH:MM"] time: Option<String>,
+    #[description = "Дні тижня для щотижневих: пн,ср,пт"] weekdays: Option<String>,
+    #[description = "Cron: хв год день міс день_тижня, напр. 0 9 * * 1-5"] cron: Option<String>,
+    #[description = "Надіслати як embed"] embed: Option<bool>,
+    #[description = "Заголовок embed-у"] title: Option<String>,
+    #[description = "Колір embed-у, напр. #2ECC71"] color: Option<String>,
+    #[description = "Надсилати пропущене, поки бот був офлайн"] catch_up: Option<bool>,
+    #[description = "Embed у JSON (з /embed export); текст стане підписом"] embed_json: Option<
+        String,
+    >,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
+    let now = Utc::now().timestamp();
 
-    let (price, multiplier, duration) = match booster_type.to_lowercase().as_str() {
-        "x2" => (2000, 2, 86400),
-        "x5" => (5000, 5, 86400),
-        _ => {
+    let imported = match embed_json
+        .as_deref()
+        .map(serde_json::from_str::<EmbedTemplate>)
+    {
+        Some(Ok(t)) => Some(t),
+        Some(Err(e)) => {
             ctx.send(
                 poise::CreateReply::default()
-                    .content("❌ Невірний тип бустера! Використовуй `x2` або `x5`.")
+                    .content(format!("❌ Невірний JSON embed-у: {}", e))
                     .ephemeral(true),
             )
             .await?;
             return Ok(());
-        }
-    };
-
-    let result = {
-        let mut users = safe_lock(&ctx.data().users);
-        let profile = users
-            .entry(user_id.clone())
-            .or_insert(create_default_profile());
-
-        if profile.chips < price {
-            Err(format!(
-                "❌ Недостатньо гривень! Потрібно 💰 {}, а у тебе {}",
-                price, profile.chips
-            ))
-        } else {
-            let now = Utc::now().timestamp();
-            profile.chips -= price;
-            let remaining_chips = profile.chips;
+        }
+        None => None,
+    };
 
-            if multiplier == 2 {
-                profile.xp_booster_x2_until = now + duration;
-            } else {
-                profile.xp_booster_x5_until = now + duration;
+    let parsed: Result<(Recurrence, i64), &str> = match recurrence {
+        RecurrenceKind::Once => match time.as_deref().and_then(|t| parse_kyiv_datetime(t, now)) {
+            Some(at) if at > now => Ok((Recurrence::Once, at)),
+            Some(_) => Err("❌ Цей час уже минув."),
+            None => Err("❌ Вкажи час: HH:MM, DD.MM HH:MM або YYYY-MM-DD HH:MM."),
+        },
+        RecurrenceKind::Daily => match time.as_deref().and_then(parse_time_of_day) {
+            Some((hour, minute)) => {
+                let rec = Recurrence::Daily { hour, minute };
+                next_occurrence(&rec, now)
+                    .map(|next| (rec, next))
+                    .ok_or("❌ Не вдалося обчислити час.")
+            }
+            None => Err("❌ Для щоденних вкажи час у форматі HH:MM."),
+        },
+        RecurrenceKind::Weekly => {
+            match (
+                time.as_deref().and_then(parse_time_of_day),
+                weekdays.as_deref().and_then(parse_weekdays),
+            ) {
+                (Some((hour, minute)), Some(weekdays)) => {
+                    let rec = Recurrence::Weekly {
+                        weekdays,
+                        hour,
+                        minute,
+                    };
+                    next_occurrence(&rec, now)
+                        .map(|next| (rec, next))
+                        .ok_or("❌ Не вдалося обчислити час.")
+                }
+                _ => Err("❌ Для щотижневих вкажи час (HH:MM) і дні (пн,ср,пт)."),
             }
-
-            save_json(USERS_FILE, &*users);
-            Ok(format!(
-                "✅ Ти купив **x{} XP бустер** на 24 години!\n💰 Витрачено {} гривень. Залишок: {}",
-                multiplier, price, remaining_chips
-            ))
         }
+        RecurrenceKind::Cron => match cron.as_deref().filter(|c| parse_cron(c).is_some()) {
+            Some(expr) => {
+                let rec = Recurrence::Cron {
+                    expr: expr.trim().to_string(),
+                };
+                next_occurrence(&rec, now)
+                    .map(|next| (rec, next))
+                    .ok_or("❌ Cron-вираз ніколи не спрацює.")
+            }
+            None => Err("❌ Невірний cron-вираз. Приклад: `0 9 * * 1-5`."),
+        },
     };
 
-    match result {
-        Ok(msg) => {
-            ctx.say(msg).await?;
-        }
+    let (recurrence, next_run) = match parsed {
+        Ok(v) => v,
         Err(msg) => {
             ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
                 .await?;
+            return Ok(());
         }
-    }
+    };
 
-    Ok(())
-}
+    let (content, embed) = if let Some(template) = imported {
+        (Some(text), Some(template))
+    } else if embed.unwrap_or(false) || title.is_some() {
+        (
+            None,
+            Some(EmbedTemplate {
+                title,
+                description: Some(text),
+                color: color.as_deref().and_then(parse_hex_color),
+                ..Default::default()
+            }),
+        )
+    } else {
+        (Some(text), None)
+    };
 
-/// 🎂 Встановити свій день народження
-#[poise::command(slash_command)]
-async fn set_birthday(ctx: Context<'_>, day: u32, month: u32) -> Result<(), Error> {
-    if NaiveDate::from_ymd_opt(2000, month, day).is_none() {
-        ctx.say("❌ Дата не існує.").await?;
-        return Ok(());
-    }
-    let d = format!("{:02}.{:02}", day, month);
-    {
-        let mut b = safe_lock(&ctx.data().birthdays);
-        b.insert(ctx.author().id.to_string(), d.clone());
-        save_json(BIRTHDAY_FILE, &*b);
-    }
-    ctx.say(format!("✅ ДН встановлено: {}", d)).await?;
+    let id = {
+        let mut schedules = safe_lock(&ctx.data().schedules);
+        let id = schedules.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+        schedules.push(ScheduledAnnouncement {
+            id,
+            guild_id,
+            channel_id: channel.to_string(),
+            created_by: ctx.author().id.to_string(),
+            content,
+            embed,
+            recurrence: recurrence.clone(),
+            next_run,
+            paused: false,
+            catch_up: catch_up.unwrap_or(false),
+            last_run: None,
+        });
+        save_json(SCHEDULES_FILE, &*schedules);
+        id
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!(
+                "✅ Оголошення **#{}** заплановано в <#{}> ({}).\nНаступний запуск: <t:{}:F>",
+                id,
+                channel,
+                describe_recurrence(&recurrence),
+                next_run
+            ))
+            .ephemeral(true),
+    )
+    .await?;
     Ok(())
 }
 
-/// 📅 Переглянути список днів народження
-#[poise::command(slash_command)]
-async fn birthdays(ctx: Context<'_>) -> Result<(), Error> {
-    let birthdays_data = {
-        let b = safe_lock(&ctx.data().birthdays);
-        b.clone()
+/// Список запланованих оголошень
+#[poise::command(slash_command, rename = "list")]
+async fn schedule_list(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
+
+    let list: Vec<ScheduledAnnouncement> = {
+        let schedules = safe_lock(&ctx.data().schedules);
+        schedules
+            .iter()
+            .filter(|s| s.guild_id == guild_id)
+            .cloned()
+            .collect()
     };
 
-    if birthdays_data.is_empty() {
-        ctx.say("📅 Поки що немає збережених днів народження.")
-            .await?;
+    if list.is_empty() {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("📋 Запланованих оголошень немає.")
+                .ephemeral(true),
+        )
+        .await?;
         return Ok(());
     }
 
-    // Сортуємо дні народження по даті (місяць.день)
-    let mut sorted: Vec<_> = birthdays_data.iter().collect();
-    sorted.sort_by(|a, b| {
-        let parse_date = |s: &str| -> (u32, u32) {
-            let parts: Vec<&str> = s.split('.').collect();
-            if parts.len() == 2 {
-                let day = parts[0].parse::<u32>().unwrap_or(0);
-                let month = parts[1].parse::<u32>().unwrap_or(0);
-                (month, day)
-            } else {
-                (0, 0)
-            }
-        };
-
-        let (month_a, day_a) = parse_date(a.1);
-        let (month_b, day_b) = parse_date(b.1);
-
-        month_a.cmp(&month_b).then(day_a.cmp(&day_b))
-    });
-
     let mut description = String::new();
-    let months = [
-        "Січ", "Лют", "Бер", "Кві", "Тра", "Чер", "Лип", "Сер", "Вер", "Жов", "Лис", "Гру",
-    ];
-    let total_count = sorted.len();
-
-    for (user_id, date) in &sorted {
-        let parts: Vec<&str> = date.split('.').collect();
-        if parts.len() == 2 {
-            let day = parts[0];
-            let month_num = parts[1].parse::<usize>().unwrap_or(1);
-            let month_name = if month_num > 0 {
-                months.get(month_num - 1).unwrap_or(&"???")
-            } else {
-                &"???"
-            };
-            description.push_str(&format!("🎂 **{} {}** — <@{}>\n", day, month_name, user_id));
-        }
+    for s in list.iter().take(25) {
+        let preview: String = s
+            .content
+            .as_deref()
+            .or_else(|| {
+                s.embed
+                    .as_ref()
+                    .and_then(|e| e.title.as_deref().or(e.description.as_deref()))
+            })
+            .unwrap_or("")
+            .chars()
+            .take(60)
+            .collect();
+        let state = if s.paused {
+            "⏸️ на паузі".to_string()
+        } else {
+            format!("⏰ <t:{}:R>", s.next_run)
+        };
+        description.push_str(&format!(
+            "**#{}** <#{}> — {}\n└ {} | {}\n",
+            s.id,
+            s.channel_id,
+            describe_recurrence(&s.recurrence),
+            state,
+            preview
+        ));
     }
 
     let embed = CreateEmbed::new()
-        .title("📅 Календар днів народження")
+        .title("🗓️ Заплановані оголошення")
         .description(description)
-        .color(0xFF69B4)
-        .footer(CreateEmbedFooter::new(format!(
-            "Всього іменинників: {}",
-            total_count
-        )));
-
-    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+        .color(0x3498DB)
+        .footer(CreateEmbedFooter::new("Час — за Києвом"));
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
     Ok(())
 }
 
-/// [ADMIN] Додати день народження користувачу
-#[poise::command(
-    slash_command,
-    default_member_permissions = "ADMINISTRATOR",
-    rename = "admin_add_birthday"
-)]
-async fn admin_add_birthday(
+/// Поставити на паузу або відновити оголошення
+#[poise::command(slash_command, rename = "pause")]
+async fn schedule_pause(
     ctx: Context<'_>,
-    user: serenity::User,
-    day: u32,
-    month: u32,
+    #[description = "Номер оголошення"] id: u32,
 ) -> Result<(), Error> {
-    if NaiveDate::from_ymd_opt(2000, month, day).is_none() {
-        ctx.say("❌ Дата не існує.").await?;
-        return Ok(());
-    }
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
+    let now = Utc::now().timestamp();
 
-    let date = format!("{:02}.{:02}", day, month);
-    {
-        let mut b = safe_lock(&ctx.data().birthdays);
-        b.insert(user.id.to_string(), date.clone());
-        save_json(BIRTHDAY_FILE, &*b);
-    }
+    let result = {
+        let mut schedules = safe_lock(&ctx.data().schedules);
+        let result = schedules
+            .iter_mut()
+            .find(|s| s.guild_id == guild_id && s.id == id)
+            .map(|s| {
+                s.paused = !s.paused;
+                // Після паузи не надсилаємо все, що накопичилось, — рахуємо від «зараз».
+                if !s.paused && s.next_run <= now {
+                    if let Some(next) = next_occurrence(&s.recurrence, now) {
+                        s.next_run = next;
+                    }
+                }
+                (s.paused, s.next_run)
+            });
+        if result.is_some() {
+            save_json(SCHEDULES_FILE, &*schedules);
+        }
+        result
+    };
 
-    ctx.say(format!(
-        "✅ Адмін встановив ДН **{}** для користувача <@{}>",
-        date, user.id
-    ))
-    .await?;
+    let msg = match result {
+        Some((true, _)) => format!("⏸️ Оголошення **#{}** на паузі.", id),
+        Some((false, next)) => format!(
+            "▶️ Оголошення **#{}** відновлено. Наступний запуск: <t:{}:F>",
+            id, next
+        ),
+        None => format!("❌ Оголошення **#{}** не знайдено.", id),
+    };
+    ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
+        .await?;
     Ok(())
 }
 
-/// [ADMIN] Видалити день народження користувача
-#[poise::command(
-    slash_command,
-    default_member_permissions = "ADMINISTRATOR",
-    rename = "admin_remove_birthday"
-)]
-async fn admin_remove_birthday(ctx: Context<'_>, user: serenity::User) -> Result<(), Error> {
+/// Видалити заплановане оголошення
+#[poise::command(slash_command, rename = "delete")]
+async fn schedule_delete(
+    ctx: Context<'_>,
+    #[description = "Номер оголошення"] id: u32,
+) -> Result<(), Error> {
+    let guild_id = ctx.guild_id().ok_or("Not in a guild")?.to_string();
+
     let removed = {
-        let mut b = safe_lock(&ctx.data().birthdays);
-        let result = b.remove(&user.id.to_string());
-        if result.is_some() {
-            save_json(BIRTHDAY_FILE, &*b);
+        let mut schedules = safe_lock(&ctx.data().schedules);
+        let before = schedules.len();
+        schedules.retain(|s| !(s.guild_id == guild_id && s.id == id));
+        let removed = schedules.len() != before;
+        if removed {
+            save_json(SCHEDULES_FILE, &*schedules);
         }
-        result.is_some()
+        removed
     };
 
-    if removed {
-        ctx.say(format!(
-            "✅ День народження користувача <@{}> видалено!",
-            user.id
-        ))
-        .await?;
+    let msg = if removed {
+        format!("🗑️ Оголошення **#{}** видалено.", id)
     } else {
-        ctx.say(format!(
-            "❌ У користувача <@{}> немає збереженого дня народження.",
-            user.id
-        ))
+        format!("❌ Оголошення **#{}** не знайдено.", id)
+    };
+    ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
         .await?;
-    }
     Ok(())
 }
 
-// --- СИСТЕМА ТІКЕТІВ ---
+// --- КОНСТРУКТОР EMBED-ІВ ---
+
+#[derive(Debug, poise::Modal)]
+#[name = "Текст embed-у"]
+struct EmbedTextModal {
+    #[name = "Заголовок"]
+    #[max_length = 256]
+    title: Option<String>,
+    #[name = "Опис"]
+    #[paragraph]
+    #[max_length = 4000]
+    description: Option<String>,
+    #[name = "Футер"]
+    #[max_length = 2048]
+    footer: Option<String>,
+    #[name = "Колір (#RRGGBB)"]
+    #[max_length = 7]
+    color: Option<String>,
+}
 
-// --- СИСТЕМА ІДЕЙ ---
+#[derive(Debug, poise::Modal)]
+#[name = "Поля і зображення"]
+struct EmbedMediaModal {
+    #[name = "Поля: назва | значення | inline"]
+    #[placeholder = "Правила | Не спамити\nЧас | 18:00 | inline"]
+    #[paragraph]
+    fields: Option<String>,
+    #[name = "URL зображення"]
+    image: Option<String>,
+    #[name = "URL мініатюри"]
+    thumbnail: Option<String>,
+}
 
-/// 💡 [ADMIN] Встановити канал для ідей
-#[poise::command(
-    slash_command,
-    default_member_permissions = "ADMINISTRATOR",
-    rename = "suggest"
-)]
-async fn setup_suggestions_channel(ctx: Context<'_>) -> Result<(), Error> {
-    let channel_id = ctx.channel_id().to_string();
+/// Where a finished embed goes: a new message or an edit of an existing one.
+enum EmbedTarget {
+    Send {
+        channel: ChannelId,
+        ping_role: Option<serenity::RoleId>,
+    },
+    Edit {
+        channel: ChannelId,
+        message: serenity::MessageId,
+    },
+}
+
+/// Parses fields written one per line as `name | value` or `name | value | inline`.
+fn parse_embed_fields(text: &str) -> Vec<EmbedFieldTemplate> {
+    text.lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '|').map(str::trim);
+            let name = parts.next().filter(|n| !n.is_empty())?;
+            let value = parts.next().filter(|v| !v.is_empty())?;
+            let inline = parts
+                .next()
+                .map(|f| f.eq_ignore_ascii_case("inline"))
+                .unwrap_or(false);
+            Some(EmbedFieldTemplate {
+                name: name.to_string(),
+                value: value.to_string(),
+                inline,
+            })
+        })
+        .take(25)
+        .collect()
+}
+
+fn format_embed_fields(fields: &[EmbedFieldTemplate]) -> String {
+    fields
+        .iter()
+        .map(|f| {
+            if f.inline {
+                format!("{} | {} | inline", f.name, f.value)
+            } else {
+                format!("{} | {}", f.name, f.value)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn template_from_embed(e: &serenity::Embed) -> EmbedTemplate {
+    EmbedTemplate {
+        title: e.title.clone(),
+        description: e.description.clone(),
+        color: e.colour.map(|c| c.0),
+        url: e.url.clone(),
+        image: e.image.as_ref().map(|i| i.url.clone()),
+        thumbnail: e.thumbnail.as_ref().map(|t| t.url.clone()),
+        footer: e.footer.as_ref().map(|f| f.text.clone()),
+        fields: e
+            .fields
+            .iter()
+            .map(|f| EmbedFieldTemplate {
+                name: f.name.clone(),
+                value: f.value.clone(),
+                inline: f.inline,
+            })
+            .collect(),
+    }
+}
+
+fn template_is_empty(t: &EmbedTemplate) -> bool {
+    t.title.is_none()
+        && t.description.is_none()
+        && t.image.is_none()
+        && t.thumbnail.is_none()
+        && t.fields.is_empty()
+}
+
+/// Parses a message link (`https://discord.com/channels/<guild>/<channel>/<message>`).
+fn parse_message_link(link: &str) -> Option<(serenity::GuildId, ChannelId, serenity::MessageId)> {
+    let rest = link.trim().split("/channels/").nth(1)?;
+    let parts: Vec<&str> = rest.split('/').collect();
+    let [guild, channel, message] = parts[..] else {
+        return None;
+    };
+    Some((
+        serenity::GuildId::new(guild.parse().ok().filter(|v| *v > 0)?),
+        ChannelId::new(channel.parse().ok().filter(|v| *v > 0)?),
+        serenity::MessageId::new(message.parse().ok().filter(|v| *v > 0)?),
+    ))
+}
+
+/// Fetches a bot message by link and returns its content and first embed.
+/// Only messages from the caller's own server are accepted.
+async fn fetch_bot_embed(
+    ctx: Context<'_>,
+    link: &str,
+) -> Result<(ChannelId, serenity::Message, EmbedTemplate), &'static str> {
+    let (guild_id, channel, message_id) =
+        parse_message_link(link).ok_or("❌ Невірне посилання на повідомлення.")?;
+    let other_server = "❌ Повідомлення має бути з цього сервера.";
+    if ctx.guild_id() != Some(guild_id) {
+        return Err(other_server);
+    }
+    let channel_guild = channel
+        .to_channel(ctx)
+        .await
+        .ok()
+        .and_then(|c| c.guild())
+        .map(|c| c.guild_id);
+    if channel_guild != Some(guild_id) {
+        return Err(other_server);
+    }
+    let message = channel
+        .message(ctx.http(), message_id)
+        .await
+        .map_err(|_| "❌ Не вдалося знайти повідомлення.")?;
+    if message.author.id != ctx.framework().bot_id {
+        return Err("❌ Можна працювати лише з повідомленнями бота.");
+    }
+    let template = message
+        .embeds
+        .first()
+        .map(template_from_embed)
+        .unwrap_or_default();
+    Ok((channel, message, template))
+}
+
+fn embed_builder_buttons(prefix: &str) -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{}text", prefix))
+            .label("Текст")
+            .style(ButtonStyle::Secondary)
+            .emoji('📝'),
+        CreateButton::new(format!("{}media", prefix))
+            .label("Поля і зображення")
+            .style(ButtonStyle::Secondary)
+            .emoji('🖼'),
+        CreateButton::new(format!("{}json", prefix))
+            .label("JSON")
+            .style(ButtonStyle::Secondary)
+            .emoji('📤'),
+        CreateButton::new(format!("{}send", prefix))
+            .label("Надіслати")
+            .style(ButtonStyle::Success)
+            .emoji('✅'),
+        CreateButton::new(format!("{}cancel", prefix))
+            .label("Скасувати")
+            .style(ButtonStyle::Danger)
+            .emoji('✖'),
+    ])]
+}
 
+fn embed_preview(template: &EmbedTemplate) -> CreateEmbed {
+    if template_is_empty(template) {
+        embed_from_template(template).description("*(порожньо — натисни «Текст»)*")
+    } else {
+        embed_from_template(template)
+    }
+}
+
+/// Shows an ephemeral live preview with buttons to edit the embed through
+/// modals, export it as JSON, and finally send or apply it.
+async fn run_embed_builder(
+    ctx: Context<'_>,
+    mut template: EmbedTemplate,
+    content: Option<String>,
+    target: EmbedTarget,
+) -> Result<(), Error> {
+    let prefix = format!("embed_{}_", ctx.id());
+    let heading = match &target {
+        EmbedTarget::Send { channel, .. } => format!("👀 Попередній перегляд для <#{}>", channel),
+        EmbedTarget::Edit { .. } => "👀 Редагування оголошення".to_string(),
+    };
+
+    let reply = ctx
+        .send(
+            poise::CreateReply::default()
+                .content(&heading)
+                .embed(embed_preview(&template))
+                .components(embed_builder_buttons(&prefix))
+                .ephemeral(true),
+        )
+        .await?;
+
+    while let Some(mci) = serenity::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .timeout(Duration::from_secs(900))
+        .filter({
+            let prefix = prefix.clone();
+            move |mci| mci.data.custom_id.starts_with(&prefix)
+        })
+        .await
     {
-        let mut channels = safe_lock(&ctx.data().suggestions_channels);
-        if !channels.contains(&channel_id) {
-            channels.push(channel_id.clone());
-            save_json(SUGGESTIONS_CHANNELS_FILE, &*channels);
+        let action = mci.data.custom_id.trim_start_matches(&prefix).to_string();
+        match action.as_str() {
+            "text" => {
+                let defaults = EmbedTextModal {
+                    title: template.title.clone(),
+                    description: template.description.clone(),
+                    footer: template.footer.clone(),
+                    color: template.color.map(|c| format!("#{:06X}", c)),
+                };
+                let Some(m) = poise::execute_modal_on_component_interaction(
+                    ctx,
+                    mci,
+                    Some(defaults),
+                    Some(Duration::from_secs(600)),
+                )
+                .await?
+                else {
+                    continue;
+                };
+                template.title = m.title;
+                template.description = m.description;
+                template.footer = m.footer;
+                template.color = m.color.as_deref().and_then(parse_hex_color);
+            }
+            "media" => {
+                let defaults = EmbedMediaModal {
+                    fields: Some(format_embed_fields(&template.fields)).filter(|f| !f.is_empty()),
+                    image: template.image.clone(),
+                    thumbnail: template.thumbnail.clone(),
+                };
+                let Some(m) = poise::execute_modal_on_component_interaction(
+                    ctx,
+                    mci,
+                    Some(defaults),
+                    Some(Duration::from_secs(600)),
+                )
+                .await?
+                else {
+                    continue;
+                };
+                template.fields = m
+                    .fields
+                    .as_deref()
+                    .map(parse_embed_fields)
+                    .unwrap_or_default();
+                template.image = m.image;
+                template.thumbnail = m.thumbnail;
+            }
+            "json" => {
+                let json = serde_json::to_string_pretty(&template)?;
+                mci.create_response(
+                    ctx.http(),
+                    serenity::CreateInteractionResponse::Message(
+                        serenity::CreateInteractionResponseMessage::new()
+                            .content(
+                                "📤 Шаблон embed-у (можна імпортувати через `/embed create json:`)",
+                            )
+                            .add_file(CreateAttachment::bytes(json.into_bytes(), "embed.json"))
+                            .ephemeral(true),
+                    ),
+                )
+                .await?;
+                continue;
+            }
+            "send" => {
+                if template_is_empty(&template) {
+                    mci.create_response(
+                        ctx.http(),
+                        serenity::CreateInteractionResponse::Message(
+                            serenity::CreateInteractionResponseMessage::new()
+                                .content("❌ Embed порожній.")
+                                .ephemeral(true),
+                        ),
+                    )
+                    .await?;
+                    continue;
+                }
+                let result = match &target {
+                    EmbedTarget::Send { channel, ping_role } => {
+                        let mut msg = CreateMessage::new().embed(embed_from_template(&template));
+                        if let Some(role) = ping_role {
+                            msg = msg.content(format!("<@&{}>", role)).allowed_mentions(
+                                serenity::CreateAllowedMentions::new().roles(vec![*role]),
+                            );
+                        }
+                        channel.send_message(ctx.http(), msg).await.map(|_| ())
+                    }
+                    EmbedTarget::Edit { channel, message } => {
+                        let mut edit =
+                            serenity::EditMessage::new().embed(embed_from_template(&template));
+                        if let Some(c) = &content {
+                            edit = edit.content(c);
+                        }
+                        channel
+                            .edit_message(ctx.http(), *message, edit)
+                            .await
+                            .map(|_| ())
+                    }
+                };
+                let text = match result {
+                    Ok(()) => "✅ Готово!".to_string(),
+                    Err(e) => format!("❌ Не вдалося надіслати: {}", e),
+                };
+                mci.create_response(
+                    ctx.http(),
+                    serenity::CreateInteractionResponse::UpdateMessage(
+                        serenity::CreateInteractionResponseMessage::new()
+                            .content(text)
+                            .components(vec![]),
+                    ),
+                )
+                .await?;
+                return Ok(());
+            }
+            _ => {
+                mci.create_response(
+                    ctx.http(),
+                    serenity::CreateInteractionResponse::UpdateMessage(
+                        serenity::CreateInteractionResponseMessage::new()
+                            .content("✖ Скасовано.")
+                            .embeds(vec![])
+                            .components(vec![]),
+                    ),
+                )
+                .await?;
+                return Ok(());
+            }
         }
-    }
 
-    ctx.send(poise::CreateReply::default()
-        .content("✅ Цей канал тепер використовується для ідей!\nУсі повідомлення будуть автоматично перетворюватися на ідеї.")
-        .ephemeral(true)).await?;
+        // Модальне вікно вже підтверджене — оновлюємо перегляд.
+        reply
+            .edit(
+                ctx,
+                poise::CreateReply::default()
+                    .content(&heading)
+                    .embed(embed_preview(&template))
+                    .components(embed_builder_buttons(&prefix)),
+            )
+            .await?;
+    }
 
+    reply
+        .edit(
+            ctx,
+            poise::CreateReply::default()
+                .content("⌛ Час редагування вичерпано.")
+                .components(vec![]),
+        )
+        .await?;
     Ok(())
 }
 
-/// 🚫 [ADMIN] Відключити канал для ідей
+/// 🖼️ [ADMIN] Конструктор embed-оголошень
 #[poise::command(
     slash_command,
+    guild_only,
+    rename = "embed",
     default_member_permissions = "ADMINISTRATOR",
-    rename = "unsuggest"
+    subcommands("embed_create", "embed_edit", "embed_export")
 )]
-async fn remove_suggestions_channel(ctx: Context<'_>) -> Result<(), Error> {
-    let channel_id = ctx.channel_id().to_string();
+async fn embed_builder(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
 
-    let removed = {
-        let mut channels = safe_lock(&ctx.data().suggestions_channels);
-        let initial_len = channels.len();
-        channels.retain(|c| c != &channel_id);
-        let removed = initial_len != channels.len();
-        if removed {
-            save_json(SUGGESTIONS_CHANNELS_FILE, &*channels);
+/// Створити embed з переглядом перед надсиланням
+#[poise::command(slash_command, rename = "create")]
+async fn embed_create(
+    ctx: Context<'_>,
+    #[description = "Канал для оголошення"] channel: ChannelId,
+    #[description = "Роль, яку згадати"] ping_role: Option<serenity::Role>,
+    #[description = "Шаблон у JSON"] json: Option<String>,
+    #[description = "Файл із шаблоном JSON"] json_file: Option<serenity::Attachment>,
+) -> Result<(), Error> {
+    let raw = match (json, json_file) {
+        (Some(j), _) => Some(j),
+        (None, Some(file)) => Some(String::from_utf8(file.download().await?)?),
+        (None, None) => None,
+    };
+    let template = match raw.as_deref().map(serde_json::from_str::<EmbedTemplate>) {
+        Some(Ok(t)) => t,
+        Some(Err(e)) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content(format!("❌ Невірний JSON embed-у: {}", e))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
         }
-        removed
+        None => EmbedTemplate::default(),
     };
 
-    if removed {
-        ctx.send(
-            poise::CreateReply::default()
-                .content("✅ Цей канал більше не використовується для ідей.")
-                .ephemeral(true),
-        )
-        .await?;
-    } else {
-        ctx.send(
-            poise::CreateReply::default()
-                .content("❌ Цей канал не був налаштований для ідей.")
-                .ephemeral(true),
-        )
-        .await?;
-    }
-
-    Ok(())
+    run_embed_builder(
+        ctx,
+        template,
+        None,
+        EmbedTarget::Send {
+            channel,
+            ping_role: ping_role.map(|r| r.id),
+        },
+    )
+    .await
 }
 
-// --- АВТОМАТИЧНЕ УПРАВЛІННЯ РОЛЯМИ ---
-
-/// 🎭 [ADMIN] Налаштувати авто-ролі для нових користувачів
-#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
-async fn setup_autorole(ctx: Context<'_>, role: serenity::Role) -> Result<(), Error> {
-    let guild_id = ctx.guild_id().ok_or("Not in a guild")?;
-
-    let auto_role = AutoRole {
-        guild_id: guild_id.to_string(),
-        role_id: role.id.to_string(),
+/// Змінити вже надіслане оголошення бота
+#[poise::command(slash_command, rename = "edit")]
+async fn embed_edit(
+    ctx: Context<'_>,
+    #[description = "Посилання на повідомлення бота"] message_link: String,
+) -> Result<(), Error> {
+    let (channel, message, template) = match fetch_bot_embed(ctx, &message_link).await {
+        Ok(v) => v,
+        Err(msg) => {
+            ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
     };
+    let content = Some(message.content.clone()).filter(|c| !c.is_empty());
+
+    run_embed_builder(
+        ctx,
+        template,
+        content,
+        EmbedTarget::Edit {
+            channel,
+            message: message.id,
+        },
+    )
+    .await
+}
 
-    {
-        let mut roles = safe_lock(&ctx.data().auto_roles);
-        // Видаляємо стару роль для цього серверу
-        roles.retain(|r| r.guild_id != guild_id.to_string());
-        // Додаємо нову
-        roles.push(auto_role);
-        save_json(AUTO_ROLES_FILE, &*roles);
-    }
+/// Експортувати embed повідомлення бота в JSON
+#[poise::command(slash_command, rename = "export")]
+async fn embed_export(
+    ctx: Context<'_>,
+    #[description = "Посилання на повідомлення бота"] message_link: String,
+) -> Result<(), Error> {
+    let template = match fetch_bot_embed(ctx, &message_link).await {
+        Ok((_, _, t)) if !template_is_empty(&t) => t,
+        Ok(_) => {
+            ctx.send(
+                poise::CreateReply::default()
+                    .content("❌ У повідомленні немає embed-у.")
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+        Err(msg) => {
+            ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
 
-    ctx.say(format!(
-        "✅ Авто-роль встановлено: **{}**\nНові користувачі автоматично отримають цю роль!",
-        role.name
-    ))
+    let json = serde_json::to_string_pretty(&template)?;
+    ctx.send(
+        poise::CreateReply::default()
+            .content("📤 Шаблон embed-у:")
+            .attachment(CreateAttachment::bytes(json.into_bytes(), "embed.json"))
+            .ephemeral(true),
+    )
     .await?;
     Ok(())
 }
 
-/// 🗑️ [ADMIN] Відключити авто-ролі
-#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
-async fn remove_autorole(ctx: Context<'_>) -> Result<(), Error> {
-    let guild_id = ctx.guild_id().ok_or("Not in a guild")?;
-
-    {
-        let mut roles = safe_lock(&ctx.data().auto_roles);
-        roles.retain(|r| r.guild_id != guild_id.to_string());
-        save_json(AUTO_ROLES_FILE, &*roles);
+// --- НАГАДУВАННЯ ---
+
+/// Parses a relative duration like `10m`, `1h30m`, `2д`, `1w` into seconds.
+/// Units: s/с, m/м/хв, h/г/год, d/д, w/т/тиж.
+fn parse_duration(input: &str) -> Option<i64> {
+    let input = input.trim().to_lowercase();
+    let mut total: i64 = 0;
+    let mut number = String::new();
+    let mut unit = String::new();
+    let mut parts: Vec<(i64, String)> = Vec::new();
+
+    for c in input.chars().filter(|c| !c.is_whitespace()) {
+        if c.is_ascii_digit() {
+            if !unit.is_empty() {
+                parts.push((number.parse().ok()?, std::mem::take(&mut unit)));
+                number.clear();
+            }
+            number.push(c);
+        } else {
+            if number.is_empty() {
+                return None;
+            }
+            unit.push(c);
+        }
     }
-
-    ctx.say("✅ Авто-ролі відключено для цього сервера.")
-        .await?;
-    Ok(())
+    if number.is_empty() || unit.is_empty() {
+        return None;
+    }
+    parts.push((number.parse().ok()?, unit));
+
+    for (n, unit) in parts {
+        let secs = match unit.as_str() {
+            "s" | "с" | "сек" => 1,
+            "m" | "min" | "м" | "хв" => 60,
+            "h" | "г" | "год" => 3600,
+            "d" | "д" | "дн" => 86400,
+            "w" | "т" | "тиж" => 604800,
+            _ => return None,
+        };
+        total = total.checked_add(n.checked_mul(secs)?)?;
+    }
+    (total > 0).then_some(total)
 }
 
-// --- АВТОМАТИЧНА МОДЕРАЦІЯ ---
+/// Delivers every reminder that is due and reschedules recurring ones.
+/// The next run is computed from the previous due time (not from "now"), so
+/// recurring reminders never drift.
+async fn deliver_due_reminders(ctx: &serenity::Context, data: &Data) {
+    let now = Utc::now().timestamp();
 
-/// 🚫 [ADMIN] Додати заборонене слово
-#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
-async fn add_banned_word(ctx: Context<'_>, word: String) -> Result<(), Error> {
-    {
-        let mut words = safe_lock(&ctx.data().banned_words);
-        let word_lower = word.to_lowercase();
-        if !words.contains(&word_lower) {
-            words.push(word_lower);
-            save_json(BANNED_WORDS_FILE, &*words);
+    let due: Vec<Reminder> = {
+        let mut reminders = safe_lock(&data.reminders);
+        let due: Vec<Reminder> = reminders.iter().filter(|r| r.due <= now).cloned().collect();
+        if due.is_empty() {
+            return;
         }
-    }
+        reminders.retain_mut(|r| {
+            if r.due > now {
+                return true;
+            }
+            let Some(rec) = &r.recurrence else {
+                return false;
+            };
+            // Після довгого офлайну не надсилаємо кожне пропущене повторення.
+            let next = next_occurrence(rec, r.due)
+                .filter(|n| *n > now)
+                .or_else(|| next_occurrence(rec, now));
+            match next {
+                Some(n) => {
+                    r.due = n;
+                    true
+                }
+                None => false,
+            }
+        });
+        save_json(REMINDERS_FILE, &*reminders);
+        due
+    };
 
-    ctx.send(
-        poise::CreateReply::default()
-            .content("✅ Слово додано до чорного списку!".to_string())
-            .ephemeral(true),
-    )
-    .await?;
-    Ok(())
+    for r in due {
+        let Ok(user_num) = r.user_id.parse::<u64>() else {
+            continue;
+        };
+        let user_id = serenity::UserId::new(user_num);
+        let late = if now - r.due > 300 {
+            format!("\n*(із запізненням — мало бути <t:{}:F>)*", r.due)
+        } else {
+            String::new()
+        };
+
+        let in_channel = if r.dm {
+            false
+        } else {
+            match r.channel_id.parse::<u64>() {
+                // Текст нагадування пише користувач: пінгуємо лише його самого
+                Ok(c) => ChannelId::new(c)
+                    .send_message(
+                        &ctx.http,
+                        CreateMessage::new()
+                            .content(format!(
+                                "⏰ <@{}>, нагадування: {}{}",
+                                r.user_id, r.text, late
+                            ))
+                            .allowed_mentions(
+                                serenity::CreateAllowedMentions::new().users(vec![user_id]),
+                            ),
+                    )
+                    .await
+                    .is_ok(),
+                Err(_) => false,
+            }
+        };
+        // Якщо в канал не вийшло (або обрано DM) — пишемо в особисті.
+        if !in_channel {
+            if let Ok(dm) = user_id.create_dm_channel(&ctx.http).await {
+                if let Err(e) = dm
+                    .say(&ctx.http, format!("⏰ Нагадування: {}{}", r.text, late))
+                    .await
+                {
+                    warn!("⚠️ Не вдалося доставити нагадування #{}: {}", r.id, e);
+                }
+            }
+        }
+    }
 }
 
-/// 📋 [ADMIN] Список заборонених слів
-#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
-async fn list_banned_words(ctx: Context<'_>) -> Result<(), Error> {
-    let words = {
-        let w = safe_lock(&ctx.data().banned_words);
-        w.clone()
+/// Seconds until the earliest reminder is due (capped to an hour so the
+/// scheduler also wakes up periodically).
+fn next_reminder_delay(data: &Data) -> Duration {
+    let now = Utc::now().timestamp();
+    let next = {
+        let reminders = safe_lock(&data.reminders);
+        reminders.iter().map(|r| r.due).min()
     };
+    let secs = next.map(|due| (due - now).clamp(0, 3600)).unwrap_or(3600);
+    Duration::from_secs(secs as u64)
+}
 
-    if words.is_empty() {
+/// ⏰ Створити нагадування
+#[poise::command(slash_command)]
+async fn remind(
+    ctx: Context<'_>,
+    #[description = "Коли: 10m, 2h30m, 1d або HH:MM, DD.MM HH:MM (за Києвом)"] when: String,
+    #[description = "Про що нагадати"] text: String,
+    #[description = "Надіслати в особисті"] dm: Option<bool>,
+    #[description = "Повторювати"] repeat: Option<RecurrenceKind>,
+    #[description = "Cron для повторення: хв год день міс день_тижня"] cron: Option<String>,
+) -> Result<(), Error> {
+    let now = Utc::now().timestamp();
+    let user_id = ctx.author().id.to_string();
+
+    let due = parse_duration(&when)
+        .map(|secs| now + secs)
+        .or_else(|| parse_kyiv_datetime(&when, now))
+        .filter(|due| *due > now);
+    let Some(due) = due else {
         ctx.send(
             poise::CreateReply::default()
-                .content("📋 Чорний список порожній.")
+                .content(
+                    "❌ Не зрозумів час. Приклади: `10m`, `2h30m`, `1d`, `18:00`, `25.12 09:00`.",
+                )
                 .ephemeral(true),
         )
         .await?;
         return Ok(());
-    }
+    };
 
-    let list = words.join(", ");
-    ctx.send(
-        poise::CreateReply::default()
-            .content(format!("🚫 **Заборонені слова:**\n{}", list))
-            .ephemeral(true),
-    )
-    .await?;
-    Ok(())
-}
+    let local = Kyiv.timestamp_opt(due, 0).single().ok_or("Invalid time")?;
+    let recurrence = match repeat {
+        None | Some(RecurrenceKind::Once) => None,
+        Some(RecurrenceKind::Daily) => Some(Recurrence::Daily {
+            hour: local.hour(),
+            minute: local.minute(),
+        }),
+        Some(RecurrenceKind::Weekly) => Some(Recurrence::Weekly {
+            weekdays: vec![local.weekday().num_days_from_monday()],
+            hour: local.hour(),
+            minute: local.minute(),
+        }),
+        Some(RecurrenceKind::Cron) => match cron.as_deref().filter(|c| parse_cron(c).is_some()) {
+            Some(expr) => Some(Recurrence::Cron {
+                expr: expr.trim().to_string(),
+            }),
+            None => {
+                ctx.send(
+                    poise::CreateReply::default()
+                        .content("❌ Невірний cron-вираз. Приклад: `0 9 * * 1-5`.")
+                        .ephemeral(true),
+                )
+                .await?;
+                return Ok(());
+            }
+        },
+    };
 
-/// 🗑️ [ADMIN] Видалити заборонене слово
-#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
-async fn remove_banned_word(ctx: Context<'_>, word: String) -> Result<(), Error> {
-    let removed = {
-        let mut words = safe_lock(&ctx.data().banned_words);
-        let word_lower = word.to_lowercase();
-        let len_before = words.len();
-        words.retain(|w| w != &word_lower);
-        let removed = words.len() < len_before;
-        if removed {
-            save_json(BANNED_WORDS_FILE, &*words);
+    let created = {
+        let mut reminders = safe_lock(&ctx.data().reminders);
+        if reminders.iter().filter(|r| r.user_id == user_id).count() >= MAX_REMINDERS_PER_USER {
+            None
+        } else {
+            let id = reminders.iter().map(|r| r.id).max().unwrap_or(0) + 1;
+            reminders.push(Reminder {
+                id,
+                user_id,
+                channel_id: ctx.channel_id().to_string(),
+                text,
+                dm: dm.unwrap_or(false) || ctx.guild_id().is_none(),
+                due,
+                created_at: now,
+                recurrence: recurrence.clone(),
+            });
+            save_json(REMINDERS_FILE, &*reminders);
+            Some(id)
         }
-        removed
     };
-
-    if removed {
-        ctx.send(
-            poise::CreateReply::default()
-                .content("✅ Слово видалено з чорного списку!")
-                .ephemeral(true),
-        )
-        .await?;
-    } else {
-        ctx.send(
-            poise::CreateReply::default()
-                .content("❌ Слово не знайдено в списку.")
-                .ephemeral(true),
-        )
+    ctx.data().reminders_wakeup.notify_one();
+
+    let msg = match created {
+        Some(id) => {
+            let repeat = recurrence
+                .as_ref()
+                .map(|r| format!("\n🔁 Повтор: {}", describe_recurrence(r)))
+                .unwrap_or_default();
+            format!(
+                "✅ Нагадування **#{}** на <t:{}:F> (<t:{}:R>).{}",
+                id, due, due, repeat
+            )
+        }
+        None => format!(
+            "❌ Забагато нагадувань (максимум {}).",
+            MAX_REMINDERS_PER_USER
+        ),
+    };
+    ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
         .await?;
-    }
     Ok(())
 }
 
-// --- АВТОМАТИЧНЕ ОЧИЩЕННЯ НЕАКТИВНИХ РОЛЕЙ ---
-
-/// 🧹 [ADMIN] Видалити ролі з неактивних користувачів
-#[poise::command(slash_command, default_member_permissions = "ADMINISTRATOR")]
-async fn cleanup_inactive(ctx: Context<'_>, days: u64) -> Result<(), Error> {
-    ctx.defer().await?;
-
-    let guild_id = ctx.guild_id().ok_or("Not in a guild")?;
-    let threshold = Utc::now().timestamp() - (days as i64 * 86400);
-
-    let inactive_users: Vec<String> = {
-        let users = safe_lock(&ctx.data().users);
-        users
+/// ⏰ Мої нагадування
+#[poise::command(slash_command, subcommands("reminders_list", "reminders_cancel"))]
+async fn reminders(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Список моїх нагадувань
+#[poise::command(slash_command, rename = "list")]
+async fn reminders_list(ctx: Context<'_>) -> Result<(), Error> {
+    let user_id = ctx.author().id.to_string();
+    let mut list: Vec<Reminder> = {
+        let reminders = safe_lock(&ctx.data().reminders);
+        reminders
             .iter()
-            .filter(|(_, p)| p.last_msg_time != 0 && p.last_msg_time < threshold * 1000)
-            .map(|(id, _)| id.clone())
+            .filter(|r| r.user_id == user_id)
+            .cloned()
             .collect()
     };
 
-    // Fetch the guild's roles once up front instead of re-fetching them inside
-    // the per-user / per-role loop (which hammered the API and risked rate
-    // limits on large servers).
-    let all_roles = guild_id.roles(&ctx.http()).await?;
-    let config = get_roles_config();
-    let level_role_ids: Vec<serenity::RoleId> = config
-        .iter()
-        .filter_map(|(_, role_name, _)| {
-            all_roles
-                .values()
-                .find(|r| r.name == *role_name)
-                .map(|r| r.id)
-        })
-        .collect();
+    if list.is_empty() {
+        ctx.send(
+            poise::CreateReply::default()
+                .content("📋 У тебе немає нагадувань.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
 
-    let mut removed_count = 0;
+    list.sort_by_key(|r| r.due);
+    let mut description = String::new();
+    for r in &list {
+        let text: String = r.text.chars().take(80).collect();
+        let repeat = r
+            .recurrence
+            .as_ref()
+            .map(|rec| format!(" 🔁 {}", describe_recurrence(rec)))
+            .unwrap_or_default();
+        let place = if r.dm {
+            "в особисті".to_string()
+        } else {
+            format!("в <#{}>", r.channel_id)
+        };
+        description.push_str(&format!(
+            "**#{}** <t:{}:R> {}{}\n└ {}\n",
+            r.id, r.due, place, repeat, text
+        ));
+    }
 
-    for user_id_str in inactive_users {
-        if let Ok(user_id_num) = user_id_str.parse::<u64>() {
-            let user_id = serenity::UserId::new(user_id_num);
-            if let Ok(member) = guild_id.member(&ctx.http(), user_id).await {
-                for role_id in &level_role_ids {
-                    if member.roles.contains(role_id) {
-                        let _ = member.remove_role(&ctx.http(), *role_id).await;
-                        removed_count += 1;
-                    }
-                }
-            }
+    let embed = CreateEmbed::new()
+        .title("⏰ Мої нагадування")
+        .description(description)
+        .color(0x3498DB)
+        .footer(CreateEmbedFooter::new(
+            "Скасувати: /reminders cancel <номер>",
+        ));
+    ctx.send(poise::CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Скасувати нагадування
+#[poise::command(slash_command, rename = "cancel")]
+async fn reminders_cancel(
+    ctx: Context<'_>,
+    #[description = "Номер нагадування"] id: u32,
+) -> Result<(), Error> {
+    let user_id = ctx.author().id.to_string();
+    let removed = {
+        let mut reminders = safe_lock(&ctx.data().reminders);
+        let before = reminders.len();
+        reminders.retain(|r| !(r.id == id && r.user_id == user_id));
+        let removed = reminders.len() != before;
+        if removed {
+            save_json(REMINDERS_FILE, &*reminders);
         }
-    }
+        removed
+    };
 
-    ctx.say(format!(
-        "🧹 Очищено ролі з {} неактивних користувачів (неактивні > {} днів).",
-        removed_count, days
-    ))
-    .await?;
+    let msg = if removed {
+        format!("🗑️ Нагадування **#{}** скасовано.", id)
+    } else {
+        format!("❌ Нагадування **#{}** не знайдено.", id)
+    };
+    ctx.send(poise::CreateReply::default().content(msg).ephemeral(true))
+        .await?;
     Ok(())
 }
 
@@ -1768,6 +11385,32 @@ async fn event_handler(
     _framework: poise::FrameworkContext<'_, Data, Error>,
     data: &Data,
 ) -> Result<(), Error> {
+    // Видалені повідомлення та канали з ідеями
+    if let serenity::FullEvent::MessageDelete {
+        deleted_message_id, ..
+    } = event
+    {
+        archive_suggestion(data, &deleted_message_id.to_string());
+    }
+    if let serenity::FullEvent::MessageDeleteBulk {
+        multiple_deleted_messages_ids,
+        ..
+    } = event
+    {
+        for id in multiple_deleted_messages_ids {
+            archive_suggestion(data, &id.to_string());
+        }
+    }
+    if let serenity::FullEvent::ChannelDelete { channel, .. } = event {
+        let removed = forget_suggestion_channel(data, &channel.id.to_string());
+        if removed > 0 {
+            info!(
+                "💡 Канал {} видалено, прибрано ідей: {}",
+                channel.id, removed
+            );
+        }
+    }
+
     // Обробка нових учасників (авто-роль + привітання)
     if let serenity::FullEvent::GuildMemberAddition { new_member } = event {
         let guild_id = new_member.guild_id;
@@ -1819,6 +11462,43 @@ async fn event_handler(
             return Ok(());
         }
 
+        // Особисті повідомлення боту йдуть у модмейл
+        if new_message.guild_id.is_none() && handle_modmail_dm(ctx, data, new_message).await? {
+            return Ok(());
+        }
+
+        // Відповіді персоналу в треді модмейлу пересилаються користувачу
+        if let Some(thread) = find_modmail_thread_by_channel(data, new_message.channel_id) {
+            // Повідомлення з "//" — внутрішні нотатки персоналу
+            if new_message.content.starts_with("//") {
+                return Ok(());
+            }
+            let anonymous = {
+                let configs = safe_lock(&data.modmail_config);
+                configs
+                    .iter()
+                    .find(|c| c.guild_id == thread.guild_id)
+                    .map(|c| c.anonymous)
+                    .unwrap_or(false)
+            };
+            let relayed = relay_modmail_reply(
+                ctx,
+                data,
+                &thread,
+                &new_message.author,
+                &new_message.content,
+                &new_message.attachments,
+                anonymous,
+            )
+            .await;
+            let reaction = if relayed.is_ok() { '✅' } else { '❌' };
+            let _ = new_message.react(&ctx.http, reaction).await;
+            if let Err(e) = relayed {
+                warn!("⚠️ Не вдалося переслати відповідь модмейлу: {}", e);
+            }
+            return Ok(());
+        }
+
         // Перевірка на заборонені слова
         let msg_lower = new_message.content.to_lowercase();
         let contains_banned = {
@@ -1892,7 +11572,72 @@ async fn event_handler(
             return Ok(());
         }
 
-        // Кастомні команди видалено за запитом
+        // Виклик тегів через префікс (напр. "!правила")
+        if let Some(guild_id) = new_message.guild_id {
+            let invoked = {
+                let all = safe_lock(&data.tags);
+                all.get(&guild_id.to_string())
+                    .and_then(|g| g.prefix.clone())
+                    .and_then(|p| new_message.content.strip_prefix(&p).map(str::to_string))
+            };
+            if let Some(rest) = invoked {
+                let (name, args) = rest.split_once(char::is_whitespace).unwrap_or((&rest, ""));
+                let name = name.to_lowercase();
+                if let Some(found) = use_tag(data, &guild_id.to_string(), &name) {
+                    let server = guild_id
+                        .to_guild_cached(&ctx.cache)
+                        .map(|g| g.name.clone())
+                        .unwrap_or_default();
+                    let vars = TagVars {
+                        user_id: new_message.author.id.get(),
+                        user_name: &new_message.author.name,
+                        server: &server,
+                        channel_id: new_message.channel_id.get(),
+                        args: args.trim(),
+                        uses: found.uses,
+                    };
+                    let (content, embed) = render_tag(&found, &vars);
+                    let mut reply =
+                        CreateMessage::new().allowed_mentions(tag_allowed_mentions(&vars));
+                    if let Some(c) = content {
+                        reply = reply.content(c);
+                    }
+                    if let Some(e) = embed {
+                        reply = reply.embed(e);
+                    }
+                    let _ = new_message.channel_id.send_message(&ctx.http, reply).await;
+                    return Ok(());
+                }
+            }
+        }
+
+        // Авто-відповіді за тригерами
+        if let Some(responder) = find_autoresponder(data, new_message) {
+            if let Some(emoji) = responder
+                .reaction
+                .as_deref()
+                .and_then(|e| e.parse::<serenity::ReactionType>().ok())
+            {
+                let _ = new_message.react(&ctx.http, emoji).await;
+            }
+            if let Some(text) = &responder.response {
+                let server = new_message
+                    .guild_id
+                    .and_then(|g| g.to_guild_cached(&ctx.cache).map(|g| g.name.clone()))
+                    .unwrap_or_default();
+                let vars = TagVars {
+                    user_id: new_message.author.id.get(),
+                    user_name: &new_message.author.name,
+                    server: &server,
+                    channel_id: new_message.channel_id.get(),
+                    args: "",
+                    uses: 0,
+                };
+                let _ = new_message
+                    .reply(&ctx.http, render_tag_template(text, &vars))
+                    .await;
+            }
+        }
 
         // Обробка повідомлень у каналах ідей
         let channel_id = new_message.channel_id.to_string();
@@ -1939,33 +11684,7 @@ async fn event_handler(
 
                         if let Some(suggestion) = updated {
                             // Оновлюємо embed
-                            let total = suggestion.votes_for + suggestion.votes_against;
-                            let percent = if total > 0 {
-                                (suggestion.votes_for as f64 / total as f64 * 100.0) as u32
-                            } else {
-                                0
-                            };
-
-                            let updated_embed = CreateEmbed::new()
-                                .title(format!("💡 Ідея користувача @{}", suggestion.author_name))
-                                .description(format!("**Ідея**\n{}", suggestion.content))
-                                .color(0xF1C40F)
-                                .field(
-                                    format!(
-                                        "За: {} | Проти: {} | Процентів за: {}%",
-                                        suggestion.votes_for, suggestion.votes_against, percent
-                                    ),
-                                    "",
-                                    false,
-                                )
-                                .field(
-                                    "Статус",
-                                    "📊 | Чекаємо на відгук спільноти! Все у ваших руках",
-                                    false,
-                                )
-                                .footer(CreateEmbedFooter::new(
-                                    "Хочете додати свою ідею? Просто напишіть її прямо сюди",
-                                ));
+                            let updated_embed = suggestion_embed(data, &suggestion);
 
                             let _ = replied_msg
                                 .channel_id
@@ -2002,58 +11721,51 @@ async fn event_handler(
                 let author_name = new_message.author.name.clone();
                 let content = new_message.content.clone();
                 let timestamp = Utc::now().timestamp();
+                let anonymous = safe_lock(&data.suggestions_config)
+                    .get(&channel_id)
+                    .is_some_and(|c| c.anonymous);
+
+                // Завантажуємо вкладення до видалення оригіналу, щоб
+                // перезалити їх разом з ідеєю
+                let mut files = download_attachments(&ctx.http, &new_message.attachments).await;
+                for (i, file) in files.iter_mut().enumerate() {
+                    file.filename = idea_attachment_name(i, &file.filename);
+                }
 
                 // Видаляємо оригінальне повідомлення
                 let _ = new_message.delete(&ctx.http).await;
 
-                // Створюємо embed з ідеєю
-                let embed = CreateEmbed::new()
-                    .title(format!("💡 Ідея користувача @{}", author_name))
-                    .description(format!("**Ідея**\n{}", content))
-                    .color(0xF1C40F)
-                    .field("За: 0 | Проти: 0 | Процентів за: 0%", "", false)
-                    .field(
-                        "Статус",
-                        "📊 | Чекаємо на відгук спільноти! Все у ваших руках",
-                        false,
-                    )
-                    .footer(CreateEmbedFooter::new(
-                        "Хочете додати свою ідею? Просто напишіть її прямо сюди",
-                    ));
+                let mut suggestion = SuggestionData {
+                    channel_id: channel_id.clone(),
+                    author_id: author_id.clone(),
+                    author_name: author_name.clone(),
+                    content: content.clone(),
+                    status: SuggestionStatus::Pending,
+                    timestamp,
+                    anonymous,
+                    attachments: files
+                        .iter()
+                        .map(|f| IdeaAttachment {
+                            filename: f.filename.clone(),
+                            url: String::new(),
+                        })
+                        .collect(),
+                    ..Default::default()
+                };
 
-                // Створюємо кнопки
-                let buttons = vec![
-                    CreateActionRow::Buttons(vec![
-                        CreateButton::new(format!("idea_like_{}", timestamp))
-                            .label("Класнючка")
-                            .style(ButtonStyle::Success)
-                            .emoji('👍'),
-                        CreateButton::new(format!("idea_dislike_{}", timestamp))
-                            .label("Жах")
-                            .style(ButtonStyle::Danger)
-                            .emoji('👎'),
-                    ]),
-                    CreateActionRow::Buttons(vec![
-                        CreateButton::new(format!("idea_approve_{}", timestamp))
-                            .label("Прийняти ідею")
-                            .style(ButtonStyle::Primary)
-                            .emoji('✅'),
-                        CreateButton::new(format!("idea_reject_{}", timestamp))
-                            .label("Відхилити ідею")
-                            .style(ButtonStyle::Danger)
-                            .emoji('❌'),
-                        CreateButton::new(format!("idea_edit_{}", timestamp))
-                            .label("Змінити")
-                            .style(ButtonStyle::Secondary)
-                            .emoji('✏'),
-                    ]),
-                ];
+                // Створюємо embed з ідеєю та кнопки
+                let theme = suggestion_theme(data, &channel_id);
+                let embed = render_suggestion_embed(&suggestion, &theme);
+                let buttons = suggestion_buttons(&theme, timestamp);
 
                 let msg = new_message
                     .channel_id
                     .send_message(
                         &ctx.http,
-                        CreateMessage::new().embed(embed).components(buttons),
+                        CreateMessage::new()
+                            .embed(embed)
+                            .components(buttons)
+                            .add_files(files),
                     )
                     .await;
 
@@ -2083,27 +11795,22 @@ async fn event_handler(
                         info!("✅ Створено тред для обговорення ідеї: {}", thread.id);
 
                         // Відправляємо привітальне повідомлення в тред
+                        let author_line = if anonymous {
+                            "Автор: анонім".to_string()
+                        } else {
+                            format!("Автор: <@{}>", author_id)
+                        };
                         let _ = thread.send_message(&ctx.http,
                                                     CreateMessage::new()
-                                                        .content(format!("💬 **Тут можна обговорити цю ідею!**\n\nАвтор: <@{}>\n\nПишіть свої думки, пропозиції та питання!", author_id))
+                                                        .content(format!("💬 **Тут можна обговорити цю ідею!**\n\n{}\n\nПишіть свої думки, пропозиції та питання!", author_line))
                         ).await;
                     } else {
                         warn!("⚠️ Не вдалося створити тред для ідеї");
                     }
 
                     // Зберігаємо дані про ідею
-                    let suggestion = SuggestionData {
-                        message_id: sent_msg.id.to_string(),
-                        channel_id: channel_id.clone(),
-                        author_id: author_id.clone(),
-                        author_name: author_name.clone(),
-                        content: content.clone(),
-                        status: "pending".to_string(),
-                        votes_for: 0,
-                        votes_against: 0,
-                        voted_users: Vec::new(),
-                        timestamp,
-                    };
+                    suggestion.message_id = sent_msg.id.to_string();
+                    suggestion.attachments = idea_attachments_from(&sent_msg);
 
                     let mut suggestions = safe_lock(&data.suggestions_data);
                     suggestions.insert(sent_msg.id.to_string(), suggestion);
@@ -2118,6 +11825,12 @@ async fn event_handler(
         let now_millis = Utc::now().timestamp_millis();
 
         let mut punish_spam = false;
+        let boosts = guild_xp_boosts(data, new_message.guild_id);
+        let member_roles: Vec<String> = new_message
+            .member
+            .as_ref()
+            .map(|m| m.roles.iter().map(|r| r.to_string()).collect())
+            .unwrap_or_default();
 
         {
             let mut users = safe_lock(&data.users);
@@ -2144,9 +11857,9 @@ async fn event_handler(
                 p.spam_counter = 0;
                 punish_spam = true;
             } else {
-                let multiplier = get_xp_multiplier(p);
-                p.xp =
-                    p.xp.saturating_add(MSG_XP_AMOUNT.saturating_mul(multiplier));
+                let multiplier =
+                    xp_multiplier(p, boosts.as_ref(), &member_roles, now_millis / 1000).total;
+                p.xp = p.xp.saturating_add(boosted_xp(MSG_XP_AMOUNT, multiplier));
             }
 
             lvl = try_levelup(p);
@@ -2251,41 +11964,7 @@ async fn event_handler(
 
                 if let Some(suggestion) = updated {
                     // Оновлюємо повідомлення
-                    let total = suggestion.votes_for + suggestion.votes_against;
-                    let percent = if total > 0 {
-                        (suggestion.votes_for as f64 / total as f64 * 100.0) as u32
-                    } else {
-                        0
-                    };
-
-                    let status_text = match suggestion.status.as_str() {
-                        "approved" => "✅ | Крута ідея, інтегруєм!",
-                        "rejected" => "❌ | До одного місця такі ідеї!",
-                        _ => "📊 | Чекаємо на відгук спільноти! Все у ваших руках",
-                    };
-
-                    let color = match suggestion.status.as_str() {
-                        "approved" => 0x2ECC71,
-                        "rejected" => 0xE74C3C,
-                        _ => 0xF1C40F,
-                    };
-
-                    let updated_embed = CreateEmbed::new()
-                        .title(format!("💡 Ідея користувача @{}", suggestion.author_name))
-                        .description(format!("**Ідея**\n{}", suggestion.content))
-                        .color(color)
-                        .field(
-                            format!(
-                                "За: {} | Проти: {} | Процентів за: {}%",
-                                suggestion.votes_for, suggestion.votes_against, percent
-                            ),
-                            "",
-                            false,
-                        )
-                        .field("Статус", status_text, false)
-                        .footer(CreateEmbedFooter::new(
-                            "Хочете додати свою ідею? Просто напишіть її прямо сюди",
-                        ));
+                    let updated_embed = suggestion_embed(data, &suggestion);
 
                     let channel_num: u64 = match suggestion.channel_id.parse() {
                         Ok(v) if v > 0 => v,
@@ -2343,16 +12022,47 @@ async fn event_handler(
         if let Some(interaction) = interaction.as_message_component() {
             let custom_id = &interaction.data.custom_id;
 
+            if custom_id == "giveaway_enter" {
+                return handle_giveaway_interaction(ctx, data, interaction).await;
+            }
+
+            if custom_id == "duel_accept" || custom_id == "duel_decline" {
+                return handle_duel_interaction(ctx, data, interaction).await;
+            }
+
+            if custom_id.starts_with("role_renew:") {
+                return handle_role_renew_interaction(ctx, data, interaction).await;
+            }
+
+            // Кнопки голосувань
+            if custom_id.starts_with("poll_") {
+                return handle_poll_interaction(ctx, data, interaction).await;
+            }
+
             // Обробка кнопок ідей
             if custom_id.starts_with("idea_") {
                 let msg_id = interaction.message.id.to_string();
 
                 let suggestion_data = {
                     let suggestions = safe_lock(&data.suggestions_data);
-                    suggestions.get(&msg_id).cloned()
+                    suggestions.get(&msg_id).filter(|s| !s.archived).cloned()
+                };
+
+                let Some(suggestion) = suggestion_data else {
+                    interaction
+                        .create_response(
+                            &ctx.http,
+                            serenity::CreateInteractionResponse::Message(
+                                serenity::CreateInteractionResponseMessage::new()
+                                    .content("❌ Ця ідея більше не відстежується.")
+                                    .ephemeral(true),
+                            ),
+                        )
+                        .await?;
+                    return Ok(());
                 };
 
-                if let Some(mut suggestion) = suggestion_data {
+                {
                     let user_id = interaction.user.id.to_string();
                     let is_author = user_id == suggestion.author_id;
                     let is_admin = if let Some(member) = &interaction.member {
@@ -2392,7 +12102,8 @@ async fn event_handler(
                         false
                     };
 
-                    if custom_id.starts_with("idea_like_") {
+                    if custom_id.starts_with("idea_like_") || custom_id.starts_with("idea_dislike_")
+                    {
                         // Перевірка: чи це автор ідеї?
                         if is_author {
                             interaction.create_response(&ctx.http, serenity::CreateInteractionResponse::Message(
@@ -2403,179 +12114,48 @@ async fn event_handler(
                             return Ok(());
                         }
 
-                        // Атомарно перевіряємо дубль і записуємо голос під одним
-                        // локом, щоб одночасні натискання не загубили голос.
-                        let vote_key = format!("{}:like", user_id);
-                        let vote_key_dislike = format!("{}:dislike", user_id);
-                        let outcome = {
-                            let mut suggestions = safe_lock(&data.suggestions_data);
-                            match suggestions.get_mut(&msg_id) {
-                                None => VoteOutcome::Gone,
-                                Some(s) => {
-                                    if s.voted_users.contains(&vote_key) {
-                                        VoteOutcome::AlreadyVotedSame
-                                    } else if s.voted_users.contains(&vote_key_dislike) {
-                                        VoteOutcome::AlreadyVotedOther
-                                    } else {
-                                        s.votes_for += 1;
-                                        s.voted_users.push(vote_key);
-                                        let snap = s.clone();
-                                        save_json(SUGGESTIONS_DATA_FILE, &*suggestions);
-                                        VoteOutcome::Recorded(snap)
-                                    }
-                                }
-                            }
-                        };
-
-                        let suggestion = match outcome {
-                            VoteOutcome::AlreadyVotedSame => {
-                                interaction.create_response(&ctx.http, serenity::CreateInteractionResponse::Message(
-                                    serenity::CreateInteractionResponseMessage::new()
-                                        .content("❌ Ви вже проголосували \"Класнючка\" за цю ідею!")
-                                        .ephemeral(true)
-                                )).await?;
-                                return Ok(());
-                            }
-                            VoteOutcome::AlreadyVotedOther => {
-                                interaction.create_response(&ctx.http, serenity::CreateInteractionResponse::Message(
-                                    serenity::CreateInteractionResponseMessage::new()
-                                        .content("❌ Ви вже проголосували \"Жах\" за цю ідею! Неможливо змінити голос.")
-                                        .ephemeral(true)
-                                )).await?;
-                                return Ok(());
-                            }
-                            VoteOutcome::Gone => return Ok(()),
-                            VoteOutcome::Recorded(s) => s,
-                        };
+                        if !suggestion.status.is_open() {
+                            interaction
+                                .create_response(
+                                    &ctx.http,
+                                    serenity::CreateInteractionResponse::Message(
+                                        serenity::CreateInteractionResponseMessage::new()
+                                            .content("❌ Голосування за цю ідею закрито.")
+                                            .ephemeral(true),
+                                    ),
+                                )
+                                .await?;
+                            return Ok(());
+                        }
 
-                        // Оновлюємо embed
-                        let total = suggestion.votes_for + suggestion.votes_against;
-                        let percent = if total > 0 {
-                            (suggestion.votes_for as f64 / total as f64 * 100.0) as u32
+                        let vote = if custom_id.starts_with("idea_like_") {
+                            IdeaVote::Like
                         } else {
-                            0
+                            IdeaVote::Dislike
                         };
 
-                        let updated_embed = CreateEmbed::new()
-                            .title(format!("💡 Ідея користувача @{}", suggestion.author_name))
-                            .description(format!("**Ідея**\n{}", suggestion.content))
-                            .color(0xF1C40F)
-                            .field(
-                                format!(
-                                    "За: {} | Проти: {} | Процентів за: {}%",
-                                    suggestion.votes_for, suggestion.votes_against, percent
-                                ),
-                                "",
-                                false,
-                            )
-                            .field(
-                                "Статус",
-                                "📊 | Чекаємо на відгук спільноти! Все у ваших руках",
-                                false,
-                            )
-                            .footer(CreateEmbedFooter::new(
-                                "Хочете додати свою ідею? Просто напишіть її прямо сюди",
-                            ));
-
-                        interaction
-                            .create_response(
-                                &ctx.http,
-                                serenity::CreateInteractionResponse::UpdateMessage(
-                                    serenity::CreateInteractionResponseMessage::new()
-                                        .embed(updated_embed),
-                                ),
-                            )
-                            .await?;
-                    } else if custom_id.starts_with("idea_dislike_") {
-                        // Перевірка: чи це автор ідеї?
-                        if is_author {
-                            interaction.create_response(&ctx.http, serenity::CreateInteractionResponse::Message(
-                                serenity::CreateInteractionResponseMessage::new()
-                                    .content("❌ Ви не можете голосувати за свою власну ідею! Ви можете тільки змінити її.")
-                                    .ephemeral(true)
-                            )).await?;
-                            return Ok(());
-                        }
-
-                        // Атомарно перевіряємо дубль і записуємо голос під одним
-                        // локом, щоб одночасні натискання не загубили голос.
-                        let vote_key = format!("{}:dislike", user_id);
-                        let vote_key_like = format!("{}:like", user_id);
+                        // Атомарно змінюємо голос під одним локом, щоб одночасні
+                        // натискання не загубили голос.
                         let outcome = {
                             let mut suggestions = safe_lock(&data.suggestions_data);
                             match suggestions.get_mut(&msg_id) {
                                 None => VoteOutcome::Gone,
                                 Some(s) => {
-                                    if s.voted_users.contains(&vote_key) {
-                                        VoteOutcome::AlreadyVotedSame
-                                    } else if s.voted_users.contains(&vote_key_like) {
-                                        VoteOutcome::AlreadyVotedOther
-                                    } else {
-                                        s.votes_against += 1;
-                                        s.voted_users.push(vote_key);
-                                        let snap = s.clone();
-                                        save_json(SUGGESTIONS_DATA_FILE, &*suggestions);
-                                        VoteOutcome::Recorded(snap)
-                                    }
+                                    let change = s.apply_vote(&user_id, vote);
+                                    let snap = Box::new(s.clone());
+                                    save_json(SUGGESTIONS_DATA_FILE, &*suggestions);
+                                    VoteOutcome::Recorded(snap, change)
                                 }
                             }
                         };
 
-                        let suggestion = match outcome {
-                            VoteOutcome::AlreadyVotedSame => {
-                                interaction
-                                    .create_response(
-                                        &ctx.http,
-                                        serenity::CreateInteractionResponse::Message(
-                                            serenity::CreateInteractionResponseMessage::new()
-                                                .content(
-                                                    "❌ Ви вже проголосували \"Жах\" за цю ідею!",
-                                                )
-                                                .ephemeral(true),
-                                        ),
-                                    )
-                                    .await?;
-                                return Ok(());
-                            }
-                            VoteOutcome::AlreadyVotedOther => {
-                                interaction.create_response(&ctx.http, serenity::CreateInteractionResponse::Message(
-                                    serenity::CreateInteractionResponseMessage::new()
-                                        .content("❌ Ви вже проголосували \"Класнючка\" за цю ідею! Неможливо змінити голос.")
-                                        .ephemeral(true)
-                                )).await?;
-                                return Ok(());
-                            }
+                        let (suggestion, change) = match outcome {
                             VoteOutcome::Gone => return Ok(()),
-                            VoteOutcome::Recorded(s) => s,
+                            VoteOutcome::Recorded(s, change) => (*s, change),
                         };
 
-                        let total = suggestion.votes_for + suggestion.votes_against;
-                        let percent = if total > 0 {
-                            (suggestion.votes_for as f64 / total as f64 * 100.0) as u32
-                        } else {
-                            0
-                        };
-
-                        let updated_embed = CreateEmbed::new()
-                            .title(format!("💡 Ідея користувача @{}", suggestion.author_name))
-                            .description(format!("**Ідея**\n{}", suggestion.content))
-                            .color(0xF1C40F)
-                            .field(
-                                format!(
-                                    "За: {} | Проти: {} | Процентів за: {}%",
-                                    suggestion.votes_for, suggestion.votes_against, percent
-                                ),
-                                "",
-                                false,
-                            )
-                            .field(
-                                "Статус",
-                                "📊 | Чекаємо на відгук спільноти! Все у ваших руках",
-                                false,
-                            )
-                            .footer(CreateEmbedFooter::new(
-                                "Хочете додати свою ідею? Просто напишіть її прямо сюди",
-                            ));
+                        // Оновлюємо embed
+                        let updated_embed = suggestion_embed(data, &suggestion);
 
                         interaction
                             .create_response(
@@ -2586,90 +12166,62 @@ async fn event_handler(
                                 ),
                             )
                             .await?;
-                    } else if custom_id.starts_with("idea_approve_") && is_admin {
-                        suggestion.status = "approved".to_string();
 
-                        let total = suggestion.votes_for + suggestion.votes_against;
-                        let percent = if total > 0 {
-                            (suggestion.votes_for as f64 / total as f64 * 100.0) as u32
-                        } else {
-                            0
+                        let theme = suggestion_theme(data, &suggestion.channel_id);
+                        let label = match vote {
+                            IdeaVote::Like => theme.like_label(),
+                            IdeaVote::Dislike => theme.dislike_label(),
                         };
-
-                        let updated_embed = CreateEmbed::new()
-                            .title(format!("💡 Ідея користувача @{}", suggestion.author_name))
-                            .description(format!("**Ідея**\n{}", suggestion.content))
-                            .color(0x2ECC71)
-                            .field(
-                                format!(
-                                    "За: {} | Проти: {} | Процентів за: {}%",
-                                    suggestion.votes_for, suggestion.votes_against, percent
-                                ),
-                                "",
-                                false,
-                            )
-                            .field("Статус", "✅ | Крута ідея, інтегруєм!", false)
-                            .footer(CreateEmbedFooter::new("Ідея прийнята і буде реалізована!"));
-
-                        interaction
-                            .create_response(
-                                &ctx.http,
-                                serenity::CreateInteractionResponse::UpdateMessage(
-                                    serenity::CreateInteractionResponseMessage::new()
-                                        .embed(updated_embed)
-                                        .components(vec![]), // Видаляємо кнопки
-                                ),
-                            )
-                            .await?;
-
-                        // Видаляємо ідею з JSON після прийняття
-                        {
-                            let mut suggestions = safe_lock(&data.suggestions_data);
-                            suggestions.remove(&msg_id);
-                            save_json(SUGGESTIONS_DATA_FILE, &*suggestions);
+                        let note = match change {
+                            VoteChange::Added => None,
+                            VoteChange::Switched => {
+                                Some(format!("🔄 Голос змінено на \"{}\".", label))
+                            }
+                            VoteChange::Retracted => Some("↩️ Голос знято.".to_string()),
+                        };
+                        if let Some(note) = note {
+                            let _ = interaction
+                                .create_followup(
+                                    &ctx.http,
+                                    serenity::CreateInteractionResponseFollowup::new()
+                                        .content(note)
+                                        .ephemeral(true),
+                                )
+                                .await;
                         }
-                    } else if custom_id.starts_with("idea_reject_") && is_admin {
-                        suggestion.status = "rejected".to_string();
-
-                        let total = suggestion.votes_for + suggestion.votes_against;
-                        let percent = if total > 0 {
-                            (suggestion.votes_for as f64 / total as f64 * 100.0) as u32
+                    } else if (custom_id.starts_with("idea_approve_")
+                        || custom_id.starts_with("idea_reject_"))
+                        && is_admin
+                    {
+                        let status = if custom_id.starts_with("idea_approve_") {
+                            SuggestionStatus::Approved
                         } else {
-                            0
+                            SuggestionStatus::Rejected
                         };
-
-                        let updated_embed = CreateEmbed::new()
-                            .title(format!("💡 Ідея користувача @{}", suggestion.author_name))
-                            .description(format!("**Ідея**\n{}", suggestion.content))
-                            .color(0xE74C3C)
-                            .field(
-                                format!(
-                                    "За: {} | Проти: {} | Процентів за: {}%",
-                                    suggestion.votes_for, suggestion.votes_against, percent
-                                ),
-                                "",
-                                false,
-                            )
-                            .field("Статус", "❌ | До одного місця такі ідеї!", false)
-                            .footer(CreateEmbedFooter::new("Ідея відхилена."));
-
                         interaction
                             .create_response(
                                 &ctx.http,
-                                serenity::CreateInteractionResponse::UpdateMessage(
+                                serenity::CreateInteractionResponse::Message(
                                     serenity::CreateInteractionResponseMessage::new()
-                                        .embed(updated_embed)
-                                        .components(vec![]), // Видаляємо кнопки
+                                        .content(format!(
+                                            "{}\nВідповідь можна додати через `/idea status`.",
+                                            status.label()
+                                        ))
+                                        .ephemeral(true),
                                 ),
                             )
                             .await?;
-
-                        // Видаляємо ідею з JSON після відхилення
-                        {
-                            let mut suggestions = safe_lock(&data.suggestions_data);
-                            suggestions.remove(&msg_id);
-                            save_json(SUGGESTIONS_DATA_FILE, &*suggestions);
-                        }
+                        let _ = change_suggestion_status(
+                            ctx,
+                            data,
+                            &msg_id,
+                            interaction.guild_id,
+                            status,
+                            &interaction.user,
+                            None,
+                            None,
+                        )
+                        .await;
                     } else if custom_id.starts_with("idea_edit_") && is_author {
                         // Інформуємо автора як змінити ідею
                         interaction.create_response(&ctx.http, serenity::CreateInteractionResponse::Message(
@@ -2701,9 +12253,18 @@ async fn background_tasks(ctx: serenity::Context, data: Arc<Data>) {
     let mut m_tick = time::interval(Duration::from_secs(60));
     let mut h_tick = time::interval(Duration::from_secs(3600));
     let mut d_tick = time::interval(Duration::from_secs(86400));
+    let mut s_tick = time::interval(Duration::from_secs(30));
 
     loop {
+        // Нагадування спрацьовують точно в свій час: спимо рівно до
+        // найближчого, а нове нагадування будить цикл через reminders_wakeup.
+        let reminder_sleep = time::sleep(next_reminder_delay(&data));
+
         tokio::select! {
+            _ = reminder_sleep => {
+                deliver_due_reminders(&ctx, &data).await;
+            }
+            _ = data.reminders_wakeup.notified() => {}
             _ = m_tick.tick() => {
                 let mut updates: Vec<(serenity::UserId, serenity::GuildId, u64)> = Vec::new();
                 let mut save = false;
@@ -2713,7 +12274,7 @@ async fn background_tasks(ctx: serenity::Context, data: Arc<Data>) {
                     // Collect active (non-bot) voice users straight from the
                     // cache — no per-user HTTP call, which previously risked
                     // rate limits with busy voice channels.
-                    let voice_users: Vec<serenity::UserId> = if let Some(guild) = g.to_guild_cached(&ctx.cache) {
+                    let voice_users: Vec<(serenity::UserId, Vec<String>)> = if let Some(guild) = g.to_guild_cached(&ctx.cache) {
                          guild.voice_states.iter()
                             .filter(|(_, s)| !s.self_deaf && !s.self_mute)
                             .filter(|(uid, _)| {
@@ -2721,17 +12282,24 @@ async fn background_tasks(ctx: serenity::Context, data: Arc<Data>) {
                                 // member isn't cached, assume a human.
                                 !guild.members.get(uid).map(|m| m.user.bot).unwrap_or(false)
                             })
-                            .map(|(_, s)| s.user_id)
+                            .map(|(_, s)| {
+                                let roles = guild.members.get(&s.user_id)
+                                    .map(|m| m.roles.iter().map(|r| r.to_string()).collect())
+                                    .unwrap_or_default();
+                                (s.user_id, roles)
+                            })
                             .collect()
                     } else {
                         continue;
                     };
+                    let boosts = guild_xp_boosts(&data, Some(g));
+                    let now = Utc::now().timestamp();
 
-                    for user_id in voice_users {
+                    for (user_id, roles) in voice_users {
                         let mut users = safe_lock(&data.users);
                         let p = users.entry(user_id.to_string()).or_insert(create_default_profile());
-                        let multiplier = get_xp_multiplier(p);
-                        p.xp = p.xp.saturating_add(VOICE_XP_AMOUNT.saturating_mul(multiplier));
+                        let multiplier = xp_multiplier(p, boosts.as_ref(), &roles, now).total;
+                        p.xp = p.xp.saturating_add(boosted_xp(VOICE_XP_AMOUNT, multiplier));
                         p.minutes = p.minutes.saturating_add(1);
 
                         if let Some(new_lvl) = try_levelup(p) {
@@ -2758,6 +12326,8 @@ async fn background_tasks(ctx: serenity::Context, data: Arc<Data>) {
                 }
             }
             _ = h_tick.tick() => {
+                accrue_bank_interest(&data);
+                post_economy_stats(&ctx, &data).await;
                 let now = Local::now();
                 if now.hour() == 9 {
                     let today = format!("{:02}.{:02}", now.day(), now.month());
@@ -2822,6 +12392,16 @@ async fn background_tasks(ctx: serenity::Context, data: Arc<Data>) {
                     }
                 }
             }
+            _ = s_tick.tick() => {
+                fire_due_schedules(&ctx, &data).await;
+                close_expired_polls(&ctx, &data).await;
+                finish_due_giveaways(&ctx, &data).await;
+                moderate_ideas(&ctx, &data).await;
+                expire_blackjack_games(&ctx, &data).await;
+                expire_duels(&ctx, &data).await;
+                remind_role_grants(&ctx, &data).await;
+                expire_role_grants(&ctx, &data).await;
+            }
             _ = d_tick.tick() => {
                  let admin = serenity::UserId::new(get_admin_id());
                  if let Ok(chan) = admin.create_dm_channel(&ctx.http).await {
@@ -2852,19 +12432,79 @@ async fn main() {
     // env-var panel), before anything reads them.
     load_env_file();
 
-    let users_data = Arc::new(Mutex::new(load_json(USERS_FILE)));
+    let mut users_loaded = load_json::<HashMap<String, UserProfile>>(USERS_FILE);
+    users_loaded
+        .values_mut()
+        .for_each(UserProfile::migrate_legacy_boosters);
+    let users_data = Arc::new(Mutex::new(users_loaded));
     let birthdays_data = Arc::new(Mutex::new(load_json(BIRTHDAY_FILE)));
     let auto_roles_data = Arc::new(Mutex::new(load_json(AUTO_ROLES_FILE)));
     let banned_words_data = Arc::new(Mutex::new(load_json(BANNED_WORDS_FILE)));
     let suggestions_channels_data = Arc::new(Mutex::new(load_json::<Vec<String>>(
         SUGGESTIONS_CHANNELS_FILE,
     )));
-    let suggestions_data_data = Arc::new(Mutex::new(load_json::<HashMap<String, SuggestionData>>(
-        SUGGESTIONS_DATA_FILE,
-    )));
+    let mut suggestions_loaded =
+        load_json::<HashMap<String, SuggestionData>>(SUGGESTIONS_DATA_FILE);
+    suggestions_loaded
+        .values_mut()
+        .for_each(SuggestionData::migrate_legacy_votes);
+    let suggestions_data_data = Arc::new(Mutex::new(suggestions_loaded));
     let banned_regex_cache_data: Arc<Mutex<HashMap<String, Regex>>> =
         Arc::new(Mutex::new(HashMap::new()));
+    let modmail_config_data = Arc::new(Mutex::new(load_json::<Vec<ModmailConfig>>(
+        MODMAIL_CONFIG_FILE,
+    )));
+    let modmail_threads_data = Arc::new(Mutex::new(load_json::<HashMap<String, ModmailThread>>(
+        MODMAIL_THREADS_FILE,
+    )));
 
+    let tags_data = Arc::new(Mutex::new(load_json::<HashMap<String, GuildTags>>(
+        TAGS_FILE,
+    )));
+    let autoresponders_data = Arc::new(Mutex::new(load_json::<Vec<AutoResponder>>(
+        AUTORESPONDERS_FILE,
+    )));
+    let responder_regex_cache_data = Arc::new(Mutex::new(HashMap::new()));
+    let responder_cooldowns_data = Arc::new(Mutex::new(HashMap::new()));
+    let schedules_data = Arc::new(Mutex::new(load_json::<Vec<ScheduledAnnouncement>>(
+        SCHEDULES_FILE,
+    )));
+    let reminders_data = Arc::new(Mutex::new(load_json::<Vec<Reminder>>(REMINDERS_FILE)));
+    let reminders_wakeup_data = Arc::new(tokio::sync::Notify::new());
+    let polls_data = Arc::new(Mutex::new(load_json::<HashMap<String, PollData>>(
+        POLLS_FILE,
+    )));
+    let giveaways_data = Arc::new(Mutex::new(load_json(GIVEAWAYS_FILE)));
+    let suggestions_config_data = Arc::new(Mutex::new(load_json(SUGGESTIONS_CONFIG_FILE)));
+    let blackjack_games_data = Arc::new(Mutex::new(load_json(BLACKJACK_FILE)));
+    let casino_config_data = Arc::new(Mutex::new(load_json::<CasinoConfig>(CASINO_CONFIG_FILE)));
+    let mut ledger_loaded = load_ledger();
+    let mut economy_loaded = load_json::<EconomyConfig>(ECONOMY_CONFIG_FILE);
+    if economy_loaded.ledger_opened_at == 0 {
+        let opened = open_ledger(
+            &safe_lock(&users_data),
+            &mut ledger_loaded,
+            &mut economy_loaded.ledger_opened_at,
+            Utc::now().timestamp(),
+        );
+        if opened > 0 {
+            info!("📒 Журнал гривень відкрито: {} початкових балансів", opened);
+            write_ledger_lines(
+                TRANSACTIONS_FILE,
+                &ledger_loaded[ledger_loaded.len() - opened..],
+            );
+        }
+        save_json(ECONOMY_CONFIG_FILE, &economy_loaded);
+    }
+    let transactions_data = Arc::new(Mutex::new(ledger_loaded));
+    let duels_data = Arc::new(Mutex::new(load_json::<HashMap<String, Duel>>(DUELS_FILE)));
+    let economy_config_data = Arc::new(Mutex::new(economy_loaded));
+    let transfer_flags_data = Arc::new(Mutex::new(load_json::<Vec<TransferFlag>>(
+        TRANSFER_FLAGS_FILE,
+    )));
+    let shop_data = Arc::new(Mutex::new(load_json(SHOP_FILE)));
+    let role_grants_data = Arc::new(Mutex::new(load_json(ROLE_GRANTS_FILE)));
+    let xp_boosts_data = Arc::new(Mutex::new(load_json(XP_BOOSTS_FILE)));
     let data = Data {
         users: users_data.clone(),
         birthdays: birthdays_data.clone(),
@@ -2873,6 +12513,27 @@ async fn main() {
         banned_regex_cache: banned_regex_cache_data.clone(),
         suggestions_channels: suggestions_channels_data.clone(),
         suggestions_data: suggestions_data_data.clone(),
+        modmail_config: modmail_config_data.clone(),
+        modmail_threads: modmail_threads_data.clone(),
+        tags: tags_data.clone(),
+        autoresponders: autoresponders_data.clone(),
+        responder_regex_cache: responder_regex_cache_data.clone(),
+        responder_cooldowns: responder_cooldowns_data.clone(),
+        schedules: schedules_data.clone(),
+        reminders: reminders_data.clone(),
+        reminders_wakeup: reminders_wakeup_data.clone(),
+        polls: polls_data.clone(),
+        giveaways: giveaways_data.clone(),
+        suggestions_config: suggestions_config_data.clone(),
+        blackjack_games: blackjack_games_data.clone(),
+        casino_config: casino_config_data.clone(),
+        transactions: transactions_data.clone(),
+        duels: duels_data.clone(),
+        economy_config: economy_config_data.clone(),
+        transfer_flags: transfer_flags_data.clone(),
+        shop: shop_data.clone(),
+        role_grants: role_grants_data.clone(),
+        xp_boosts: xp_boosts_data.clone(),
     };
 
     let framework = poise::Framework::builder()
@@ -2885,14 +12546,30 @@ async fn main() {
                 purge(),
                 clean(),
                 poll(),
+                giveaway(),
                 avatar(),
                 rank(),
                 daily(),
+                weekly(),
+                monthly(),
                 casino(),
                 blackjack(),
+                roulette(),
+                slots(),
+                dice(),
+                duel(),
+                balance(),
+                economy(),
+                pay(),
+                bank(),
+                games_config(),
                 shop(),
-                buy_booster(),
+                inventory(),
+                use_item(),
+                xp_boost(),
                 setup_suggestions_channel(),
+                idea(),
+                ideas(),
                 remove_suggestions_channel(),
                 set_birthday(),
                 birthdays(),
@@ -2911,6 +12588,14 @@ async fn main() {
                 list_banned_words(),
                 remove_banned_word(),
                 cleanup_inactive(),
+                modmail(),
+                tag(),
+                tags(),
+                autoresponder(),
+                schedule(),
+                embed_builder(),
+                remind(),
+                reminders(),
             ],
             event_handler: |ctx, event, framework, data| {
                 Box::pin(event_handler(ctx, event, framework, data))
@@ -2930,11 +12615,37 @@ async fn main() {
                     banned_regex_cache: banned_regex_cache_data.clone(),
                     suggestions_channels: suggestions_channels_data.clone(),
                     suggestions_data: suggestions_data_data.clone(),
+                    modmail_config: modmail_config_data.clone(),
+                    modmail_threads: modmail_threads_data.clone(),
+                    tags: tags_data.clone(),
+                    autoresponders: autoresponders_data.clone(),
+                    responder_regex_cache: responder_regex_cache_data.clone(),
+                    responder_cooldowns: responder_cooldowns_data.clone(),
+                    schedules: schedules_data.clone(),
+                    reminders: reminders_data.clone(),
+                    reminders_wakeup: reminders_wakeup_data.clone(),
+                    polls: polls_data.clone(),
+                    giveaways: giveaways_data.clone(),
+                    suggestions_config: suggestions_config_data.clone(),
+                    blackjack_games: blackjack_games_data.clone(),
+                    casino_config: casino_config_data.clone(),
+                    transactions: transactions_data.clone(),
+                    duels: duels_data.clone(),
+                    economy_config: economy_config_data.clone(),
+                    transfer_flags: transfer_flags_data.clone(),
+                    shop: shop_data.clone(),
+                    role_grants: role_grants_data.clone(),
+                    xp_boosts: xp_boosts_data.clone(),
                 });
                 // Spawn background tasks only once, even if Discord fires
                 // multiple Ready events (e.g. after a gateway reconnect).
                 static BG_STARTED: AtomicBool = AtomicBool::new(false);
                 if !BG_STARTED.swap(true, Ordering::SeqCst) {
+                    let ctx_reconcile = ctx_clone.clone();
+                    let data_reconcile = data_clone.clone();
+                    tokio::spawn(async move {
+                        reconcile_suggestions(&ctx_reconcile, &data_reconcile).await;
+                    });
                     tokio::spawn(async move {
                         // Supervisor: if the background loop ever panics, log it
                         // and restart it so voice XP / birthdays / backups keep
@@ -2986,6 +12697,57 @@ async fn main() {
                     "📝 Завантажено ідей: {}",
                     safe_lock(&data.suggestions_data).len()
                 );
+                info!(
+                    "📨 Відкритих розмов модмейлу: {}",
+                    safe_lock(&data.modmail_threads).len()
+                );
+                info!(
+                    "🤖 Завантажено авто-відповідей: {}",
+                    safe_lock(&data.autoresponders).len()
+                );
+                info!(
+                    "🗓️ Завантажено запланованих оголошень: {}",
+                    safe_lock(&data.schedules).len()
+                );
+                info!(
+                    "⏰ Завантажено нагадувань: {}",
+                    safe_lock(&data.reminders).len()
+                );
+                info!("📊 Активних голосувань: {}", safe_lock(&data.polls).len());
+                info!(
+                    "🃏 Незавершених ігор у блекджек: {}",
+                    safe_lock(&data.blackjack_games).len()
+                );
+                info!(
+                    "⚔️ Активних викликів на дуель: {}",
+                    safe_lock(&data.duels).len()
+                );
+                info!(
+                    "⏳ Тимчасових ролей з магазину: {}",
+                    safe_lock(&data.role_grants).len()
+                );
+                let drift = {
+                    let users = safe_lock(&data.users);
+                    let log = safe_lock(&data.transactions);
+                    ledger_mismatches(&users, &log).len()
+                };
+                if drift > 0 {
+                    warn!("⚠️ Баланси не збігаються з журналом гривень: {}", drift);
+                }
+                info!(
+                    "🎉 Активних розіграшів: {}",
+                    safe_lock(&data.giveaways)
+                        .values()
+                        .filter(|g| !g.ended)
+                        .count()
+                );
+                info!(
+                    "🏷️ Завантажено тегів: {}",
+                    safe_lock(&data.tags)
+                        .values()
+                        .map(|g| g.tags.len())
+                        .sum::<usize>()
+                );
                 Ok(data)
             })
         })
@@ -3042,7 +12804,7 @@ async fn main() {
 
 #[cfg(test)]
 mod tests {
-    use super::parse_env;
+    use super::*;
 
     #[test]
     fn parses_plain_key_values() {
@@ -3063,4 +12825,943 @@ mod tests {
         assert_eq!(pairs[0], ("DISCORD_TOKEN".to_string(), "tok".to_string()));
         assert_eq!(pairs[1], ("ADMIN_ID".to_string(), "99".to_string()));
     }
+
+    #[test]
+    fn modmail_transcript_marks_staff_and_anonymous_entries() {
+        let entry = |name: &str, content: &str, from_staff: bool, anonymous: bool| ModmailEntry {
+            author_id: "1".to_string(),
+            author_name: name.to_string(),
+            content: content.to_string(),
+            attachments: Vec::new(),
+            from_staff,
+            anonymous,
+            timestamp: 0,
+        };
+        let thread = ModmailThread {
+            user_id: "42".to_string(),
+            user_name: "petro".to_string(),
+            guild_id: "7".to_string(),
+            thread_id: "9".to_string(),
+            opened_at: 0,
+            transcript: vec![
+                entry("petro", "Привіт", false, false),
+                entry("mod", "Вітаю", true, false),
+                entry("mod", "Секрет", true, true),
+            ],
+        };
+        let text = format_modmail_transcript(&thread);
+        assert!(text.starts_with("Модмейл: petro (42)"));
+        assert!(text.contains("[Користувач] petro: Привіт"));
+        assert!(text.contains("[Персонал] mod: Вітаю"));
+        assert!(text.contains("[Персонал, анонімно] mod: Секрет"));
+    }
+
+    #[test]
+    fn tag_template_fills_placeholders() {
+        let vars = TagVars {
+            user_id: 5,
+            user_name: "olena",
+            server: "Село",
+            channel_id: 8,
+            args: "завтра",
+            uses: 3,
+        };
+        assert_eq!(
+            render_tag_template(
+                "{user} ({user.name}, {user.id}) у {server} {channel}: {args} #{uses}",
+                &vars
+            ),
+            "<@5> (olena, 5) у Село <#8>: завтра #3"
+        );
+        assert_eq!(parse_hex_color("#2ECC71"), Some(0x2ECC71));
+        assert_eq!(parse_hex_color("zzz"), None);
+        assert_eq!(normalize_tag_name(" Правила "), Some("правила".to_string()));
+        assert_eq!(normalize_tag_name("два слова"), None);
+    }
+
+    #[test]
+    fn autoresponder_matching_and_cooldowns() {
+        let responder = |pattern: &str, match_type| AutoResponder {
+            id: 1,
+            guild_id: "1".to_string(),
+            pattern: pattern.to_string(),
+            match_type,
+            response: None,
+            reaction: None,
+            channels: Vec::new(),
+            cooldown_secs: 0,
+            user_cooldown_secs: 0,
+        };
+        let mut cache = HashMap::new();
+        let msg = "Де ПРАВИЛА сервера?";
+        let lower = msg.to_lowercase();
+
+        assert!(responder_matches(
+            &responder("правила", TriggerMatch::Contains),
+            msg,
+            &lower,
+            &mut cache
+        ));
+        assert!(!responder_matches(
+            &responder("правила", TriggerMatch::Exact),
+            msg,
+            &lower,
+            &mut cache
+        ));
+        assert!(responder_matches(
+            &responder(r"(?i)^де\b", TriggerMatch::Regex),
+            msg,
+            &lower,
+            &mut cache
+        ));
+        assert_eq!(cache.len(), 1);
+
+        let mut cooldowns = HashMap::new();
+        assert!(cooldown_ready(&cooldowns, "k", 100));
+        start_cooldowns(
+            &mut cooldowns,
+            vec![("k".to_string(), 30), ("u".to_string(), 0)],
+            100,
+        );
+        assert!(!cooldown_ready(&cooldowns, "k", 120));
+        assert!(cooldown_ready(&cooldowns, "k", 130));
+        assert!(cooldown_ready(&cooldowns, "u", 101));
+        // Прострочені кулдауни прибираються при наступному спрацюванні
+        start_cooldowns(&mut cooldowns, vec![("x".to_string(), 10)], 130);
+        assert_eq!(cooldowns.len(), 1);
+    }
+
+    #[test]
+    fn cron_and_recurrence_next_runs_in_kyiv() {
+        // 2026-01-05 (понеділок) 08:00 за Києвом = 06:00 UTC.
+        let monday_8am = Kyiv
+            .with_ymd_and_hms(2026, 1, 5, 8, 0, 0)
+            .unwrap()
+            .timestamp();
+        let at = |y, m, d, h, min| {
+            Kyiv.with_ymd_and_hms(y, m, d, h, min, 0)
+                .unwrap()
+                .timestamp()
+        };
+
+        let weekdays_9am = parse_cron("0 9 * * 1-5").unwrap();
+        assert_eq!(
+            cron_next_after(&weekdays_9am, monday_8am),
+            Some(at(2026, 1, 5, 9, 0))
+        );
+        let every_15 = parse_cron("*/15 * * * *").unwrap();
+        assert_eq!(
+            cron_next_after(&every_15, monday_8am),
+            Some(at(2026, 1, 5, 8, 15))
+        );
+        assert!(parse_cron("61 * * * *").is_none());
+        assert!(parse_cron("0 9 * *").is_none());
+
+        let daily = Recurrence::Daily {
+            hour: 7,
+            minute: 30,
+        };
+        assert_eq!(
+            next_occurrence(&daily, monday_8am),
+            Some(at(2026, 1, 6, 7, 30))
+        );
+        let weekly = Recurrence::Weekly {
+            weekdays: parse_weekdays("пт,нд").unwrap(),
+            hour: 18,
+            minute: 0,
+        };
+        assert_eq!(
+            next_occurrence(&weekly, monday_8am),
+            Some(at(2026, 1, 9, 18, 0))
+        );
+        assert_eq!(next_occurrence(&Recurrence::Once, monday_8am), None);
+
+        assert_eq!(
+            parse_kyiv_datetime("09:00", monday_8am),
+            Some(at(2026, 1, 5, 9, 0))
+        );
+        assert_eq!(
+            parse_kyiv_datetime("07:00", monday_8am),
+            Some(at(2026, 1, 6, 7, 0))
+        );
+        assert_eq!(
+            parse_kyiv_datetime("2026-03-01 12:00", monday_8am),
+            Some(at(2026, 3, 1, 12, 0))
+        );
+        assert_eq!(
+            parse_kyiv_datetime("01.01 10:00", monday_8am),
+            Some(at(2027, 1, 1, 10, 0))
+        );
+    }
+
+    #[test]
+    fn embed_template_fields_links_and_json() {
+        let fields = parse_embed_fields("Правила | Не спамити\nЧас | 18:00 | inline\nбез значення");
+        assert_eq!(fields.len(), 2);
+        assert!(!fields[0].inline && fields[1].inline);
+        assert_eq!(parse_embed_fields(&format_embed_fields(&fields)), fields);
+
+        let template = EmbedTemplate {
+            title: Some("Збори".to_string()),
+            color: Some(0x2ECC71),
+            fields,
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&template).unwrap();
+        assert!(!json.contains("image"));
+        assert_eq!(
+            serde_json::from_str::<EmbedTemplate>(&json).unwrap(),
+            template
+        );
+
+        let (guild, channel, message) =
+            parse_message_link("https://discord.com/channels/1/22/333").unwrap();
+        assert_eq!((guild.get(), channel.get(), message.get()), (1, 22, 333));
+        assert!(parse_message_link("https://discord.com/channels/@me/22/333").is_none());
+        assert!(parse_message_link("https://discord.com/channels/1/22").is_none());
+    }
+
+    #[test]
+    fn parses_relative_durations() {
+        assert_eq!(parse_duration("10m"), Some(600));
+        assert_eq!(parse_duration("1h30m"), Some(5400));
+        assert_eq!(parse_duration("2 д"), Some(172800));
+        assert_eq!(parse_duration("1тиж"), Some(604800));
+        assert_eq!(parse_duration("15"), None);
+        assert_eq!(parse_duration("m5"), None);
+        assert_eq!(parse_duration("3y"), None);
+    }
+
+    #[test]
+    fn poll_votes_toggle_and_tally() {
+        let mut poll = PollData {
+            message_id: "1".to_string(),
+            channel_id: "2".to_string(),
+            author_id: "3".to_string(),
+            question: "Куди йдемо?".to_string(),
+            options: parse_poll_options("Ліс; Річка | Поле;;"),
+            multi: false,
+            anonymous: false,
+            ends_at: None,
+            required_role: None,
+            votes: HashMap::new(),
+            created_at: 0,
+        };
+        assert_eq!(poll.options.len(), 3);
+
+        assert!(apply_poll_vote(&mut poll, "a", 0));
+        assert!(apply_poll_vote(&mut poll, "a", 1)); // переносить голос
+        assert_eq!(poll.votes["a"], vec![1]);
+        assert!(!apply_poll_vote(&mut poll, "a", 1)); // знімає голос
+        assert!(poll.votes.is_empty());
+
+        poll.multi = true;
+        apply_poll_vote(&mut poll, "a", 0);
+        apply_poll_vote(&mut poll, "a", 2);
+        apply_poll_vote(&mut poll, "b", 0);
+        assert_eq!(poll_counts(&poll), vec![2, 0, 1]);
+        let lines = poll_results_lines(&poll);
+        assert!(lines.contains("▰▰▰▰▰▰▰▰▰▰ 100% (2)"));
+        assert!(lines.contains("▰▰▰▰▰▱▱▱▱▱ 50% (1)"));
+    }
+
+    #[test]
+    fn giveaway_winners_are_distinct_and_skip_previous() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let entrants: Vec<String> = (1..=5).map(|i| i.to_string()).collect();
+
+        let first = pick_giveaway_winners(&entrants, &[], 3, &mut rng);
+        assert_eq!(first.len(), 3);
+        assert!(first
+            .iter()
+            .all(|w| first.iter().filter(|x| *x == w).count() == 1));
+
+        let reroll = pick_giveaway_winners(&entrants, &first, 3, &mut rng);
+        assert_eq!(reroll.len(), 2);
+        assert!(reroll.iter().all(|w| !first.contains(w)));
+
+        assert!(pick_giveaway_winners(&[], &[], 1, &mut rng).is_empty());
+
+        // Кожне переобрання пропускає переможців усіх попередніх раундів
+        let mut giveaway = Giveaway {
+            message_id: "1".to_string(),
+            channel_id: "2".to_string(),
+            guild_id: "3".to_string(),
+            host_id: "4".to_string(),
+            prize: "Нітро".to_string(),
+            winners: 2,
+            ends_at: 0,
+            min_level: 0,
+            required_role: None,
+            entry_fee: 0,
+            entrants,
+            ended: false,
+            winner_ids: Vec::new(),
+            past_winners: Vec::new(),
+        };
+        giveaway.draw(false, &mut rng);
+        let round1 = giveaway.winner_ids.clone();
+        giveaway.draw(true, &mut rng);
+        let round2 = giveaway.winner_ids.clone();
+        giveaway.draw(true, &mut rng);
+        assert_eq!((round1.len(), round2.len()), (2, 2));
+        assert_eq!(giveaway.winner_ids.len(), 1);
+        assert!(giveaway
+            .winner_ids
+            .iter()
+            .all(|w| !round1.contains(w) && !round2.contains(w)));
+        assert_eq!(giveaway.past_winners.len(), 4);
+    }
+
+    #[test]
+    fn idea_votes_switch_retract_and_migrate() {
+        let mut idea = SuggestionData {
+            message_id: "1".to_string(),
+            channel_id: "2".to_string(),
+            author_id: "3".to_string(),
+            author_name: "автор".to_string(),
+            content: "Більше грядок".to_string(),
+            voted_users: vec!["10:like".to_string(), "11:dislike".to_string()],
+            ..Default::default()
+        };
+        idea.migrate_legacy_votes();
+        assert!(idea.voted_users.is_empty());
+        assert_eq!((idea.votes_for(), idea.votes_against()), (1, 1));
+
+        assert_eq!(
+            idea.apply_vote("10", IdeaVote::Dislike),
+            VoteChange::Switched
+        );
+        assert_eq!((idea.votes_for(), idea.votes_against()), (0, 2));
+        assert_eq!(
+            idea.apply_vote("11", IdeaVote::Dislike),
+            VoteChange::Retracted
+        );
+        assert_eq!(idea.apply_vote("12", IdeaVote::Like), VoteChange::Added);
+        assert_eq!((idea.votes_for(), idea.votes_against()), (1, 1));
+    }
+
+    #[test]
+    fn idea_status_changes_are_recorded() {
+        let mut idea = SuggestionData {
+            message_id: "1".to_string(),
+            channel_id: "2".to_string(),
+            author_id: "3".to_string(),
+            author_name: "автор".to_string(),
+            content: "Ставок біля клубу".to_string(),
+            ..Default::default()
+        };
+        assert!(idea.status.is_open());
+
+        let link = Some("https://discord.com/channels/1/2/5".to_string());
+        idea.set_status(
+            SuggestionStatus::Duplicate,
+            ("9", "модер"),
+            Some("Вже є".to_string()),
+            link.clone(),
+            100,
+        );
+        assert_eq!(idea.duplicate_of, link);
+        assert!(!idea.status.is_open());
+
+        idea.set_status(
+            SuggestionStatus::Considering,
+            ("9", "модер"),
+            None,
+            link,
+            200,
+        );
+        assert_eq!(idea.duplicate_of, None);
+        assert_eq!(idea.staff_response, None);
+        assert_eq!(idea.status_history.len(), 2);
+        assert_eq!(idea.status_history[0].response.as_deref(), Some("Вже є"));
+
+        let json = serde_json::to_string(&idea).unwrap();
+        assert!(json.contains("\"status\":\"considering\""));
+
+        // Анонімний автор, що відкликав ідею, прихований від інших учасників
+        idea.anonymous = true;
+        idea.set_status(SuggestionStatus::Withdrawn, ("3", "Олена"), None, None, 300);
+        let public = idea.history_lines("7", false);
+        assert!(public
+            .iter()
+            .all(|l| !l.contains("Олена") && !l.contains("<@3>")));
+        assert!(public[1].contains("модер"));
+        assert!(idea.history_lines("7", true)[3].contains("Олена"));
+        assert!(idea.history_lines("3", false)[3].contains("Олена"));
+    }
+
+    #[test]
+    fn ideas_sort_by_net_and_approval() {
+        let idea = |id: &str, likes: usize, dislikes: usize| {
+            let mut votes = HashMap::new();
+            for i in 0..likes {
+                votes.insert(format!("l{}", i), IdeaVote::Like);
+            }
+            for i in 0..dislikes {
+                votes.insert(format!("d{}", i), IdeaVote::Dislike);
+            }
+            SuggestionData {
+                message_id: id.to_string(),
+                channel_id: "1".to_string(),
+                author_id: "2".to_string(),
+                author_name: "автор".to_string(),
+                content: id.to_string(),
+                votes,
+                ..Default::default()
+            }
+        };
+        let mut list = vec![idea("a", 6, 4), idea("b", 3, 0), idea("c", 1, 5)];
+
+        sort_ideas(&mut list, IdeaSort::Net);
+        let order: Vec<&str> = list.iter().map(|s| s.message_id.as_str()).collect();
+        assert_eq!(order, ["b", "a", "c"]);
+        assert_eq!(list[2].net_votes(), -4);
+
+        sort_ideas(&mut list, IdeaSort::Approval);
+        assert_eq!(list[0].approval_percent(), 100);
+        assert_eq!(list[1].approval_percent(), 60);
+        assert_eq!(SuggestionStatus::Implemented.emoji(), "🚀");
+    }
+
+    #[test]
+    fn idea_rules_flag_close_and_remind() {
+        let now = 10 * 86400;
+        let mut idea = SuggestionData {
+            timestamp: now - 3600,
+            ..Default::default()
+        };
+        for i in 0..4 {
+            idea.apply_vote(&format!("l{}", i), IdeaVote::Like);
+        }
+        idea.apply_vote("d", IdeaVote::Dislike);
+
+        let mut cfg = SuggestionChannelConfig {
+            staff_channel: Some("5".to_string()),
+            popular_net_votes: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(evaluate_idea(&cfg, &idea, now), Some(IdeaAction::Flag));
+        idea.flagged = true;
+        assert_eq!(evaluate_idea(&cfg, &idea, now), None);
+        cfg.auto_popular = true;
+        assert_eq!(
+            evaluate_idea(&cfg, &idea, now),
+            Some(IdeaAction::MarkPopular)
+        );
+
+        let mut bad = SuggestionData {
+            timestamp: now - 7200,
+            ..Default::default()
+        };
+        bad.apply_vote("a", IdeaVote::Dislike);
+        bad.apply_vote("b", IdeaVote::Dislike);
+        let cfg = SuggestionChannelConfig {
+            staff_channel: Some("5".to_string()),
+            negative_percent: Some(20),
+            negative_after_hours: 3,
+            min_voters: 2,
+            stale_days: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(evaluate_idea(&cfg, &bad, now), None);
+        assert_eq!(
+            evaluate_idea(&cfg, &bad, now + 3600),
+            Some(IdeaAction::AutoClose)
+        );
+
+        let old = SuggestionData {
+            timestamp: now - 2 * 86400,
+            ..Default::default()
+        };
+        assert_eq!(
+            evaluate_idea(&cfg, &old, now),
+            Some(IdeaAction::StaleReminder)
+        );
+    }
+
+    #[test]
+    fn suggestion_theme_overrides_defaults() {
+        let mut theme = SuggestionTheme::default();
+        assert_eq!(theme.title_for(Some("Петро")), "💡 Ідея користувача @Петро");
+        assert_eq!(theme.title_for(None), "💡 Анонімна ідея");
+        assert_eq!(theme.color(SuggestionStatus::Rejected), 0xE74C3C);
+        assert_eq!(
+            (theme.like_label(), theme.dislike_label()),
+            ("Класнючка", "Жах")
+        );
+
+        theme.title = Some("Пропозиція від {author}".to_string());
+        theme.colors.insert(SuggestionStatus::Rejected, 0x123456);
+        theme.like_label = Some("За".to_string());
+        assert_eq!(theme.title_for(Some("Петро")), "Пропозиція від Петро");
+        assert_eq!(theme.title_for(None), "Пропозиція від Анонім");
+        assert_eq!(theme.color(SuggestionStatus::Rejected), 0x123456);
+        assert_eq!(theme.color(SuggestionStatus::Pending), 0xF1C40F);
+        assert_eq!(theme.like_label(), "За");
+
+        let json = serde_json::to_string(&theme).unwrap();
+        assert!(json.contains("\"rejected\":1193046"));
+        let back: SuggestionTheme = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, theme);
+    }
+
+    #[test]
+    fn deleted_channel_drops_only_its_ideas() {
+        let idea = |msg: &str, channel: &str| SuggestionData {
+            message_id: msg.to_string(),
+            channel_id: channel.to_string(),
+            ..Default::default()
+        };
+        let mut ideas: HashMap<String, SuggestionData> =
+            [idea("1", "a"), idea("2", "a"), idea("3", "b")]
+                .into_iter()
+                .map(|s| (s.message_id.clone(), s))
+                .collect();
+        assert_eq!(remove_channel_ideas(&mut ideas, "a"), 2);
+        assert_eq!(remove_channel_ideas(&mut ideas, "a"), 0);
+        assert!(ideas.contains_key("3"));
+
+        let cfg = SuggestionChannelConfig {
+            staff_channel: Some("5".to_string()),
+            stale_days: Some(1),
+            ..Default::default()
+        };
+        let mut old = idea("4", "b");
+        assert_eq!(
+            evaluate_idea(&cfg, &old, 3 * 86400),
+            Some(IdeaAction::StaleReminder)
+        );
+        old.archived = true;
+        assert_eq!(evaluate_idea(&cfg, &old, 3 * 86400), None);
+    }
+
+    #[test]
+    fn idea_attachments_get_stable_names() {
+        assert_eq!(idea_attachment_name(0, "Фото з дачі.JPG"), "idea_0.jpg");
+        assert_eq!(idea_attachment_name(2, "notes"), "idea_2");
+        assert_eq!(idea_attachment_name(1, "x.t?xt"), "idea_1.txt");
+        assert!(is_image_filename("idea_0.jpg"));
+        assert!(!is_image_filename("idea_1.txt"));
+
+        let idea = SuggestionData {
+            author_name: "Петро".to_string(),
+            anonymous: true,
+            ..Default::default()
+        };
+        assert_eq!(idea.public_author(), None);
+    }
+
+    #[test]
+    fn blackjack_titles_and_buttons_follow_the_round() {
+        use blackjack::{Card, Game, Rules, Shoe, Suit};
+        let deal = |ranks: &[u8]| {
+            let cards: Vec<Card> = ranks.iter().map(|r| Card::new(*r, Suit::Clubs)).collect();
+            Game::with_shoe(Rules::default(), 100, Shoe::stacked(&cards))
+        };
+
+        let natural = deal(&[1, 9, 13, 8]);
+        assert!(blackjack_buttons("x", &natural).is_empty());
+        assert_eq!(
+            blackjack_title(&natural).0,
+            "🂡 Блекджек! +150 гривень (3:2)"
+        );
+
+        let mut pair = deal(&[8, 10, 8, 7, 10, 10]);
+        assert_eq!(blackjack_buttons("x", &pair).len(), 1);
+        pair.apply(blackjack::Action::Split).unwrap();
+        pair.apply(blackjack::Action::Stand).unwrap();
+        pair.apply(blackjack::Action::Stand).unwrap();
+        assert_eq!(pair.total_staked(), 200);
+        assert_eq!(blackjack_title(&pair).0, "🎉 Перемога! +200 гривень");
+
+        let insurance = deal(&[10, 1, 9, 5]);
+        assert_eq!(blackjack_title(&insurance).0, "🃏 Блекджек");
+        assert!(insurance.can(blackjack::Action::Insurance));
+    }
+
+    #[test]
+    fn casino_config_fills_defaults_and_checks_limits() {
+        let config: CasinoConfig =
+            serde_json::from_str(r#"{"blackjack": {"decks": 2}, "max_bet": 500}"#).unwrap();
+        assert_eq!(config.blackjack.decks, 2);
+        assert_eq!(config.min_bet, 10);
+        assert_eq!(config.slots, games::default_slot_table());
+        assert!(check_bet(&config, 10).is_ok());
+        assert!(check_bet(&config, 500).is_ok());
+        assert!(check_bet(&config, 9).is_err());
+        assert!(check_bet(&config, 501).is_err());
+        assert_eq!(
+            game_result_line(100, 250, 900),
+            "🎉 Виграш: **+150** гривень\n💰 Баланс: 900"
+        );
+    }
+
+    #[test]
+    fn ledger_opens_once_and_flags_drift() {
+        let entry = |user: &str, delta: i64, reason: &str| Transaction {
+            user_id: user.to_string(),
+            delta,
+            reason: reason.to_string(),
+            reference: None,
+            at: 0,
+            bank: false,
+        };
+        let mut users = HashMap::new();
+        let mut rich = create_default_profile();
+        rich.chips = 500;
+        users.insert("1".to_string(), rich);
+        users.insert("2".to_string(), create_default_profile());
+
+        let mut log = vec![entry("1", -50, "casino")];
+        assert_eq!(
+            ledger_mismatches(&users, &log),
+            vec![("1".to_string(), "гаманець", 500, 50)]
+        );
+        // Перший запуск без профілів теж ставить позначку: пізніші
+        // розбіжності не поглинаються новими початковими балансами
+        let mut opened_at = 0;
+        assert_eq!(
+            open_ledger(&HashMap::new(), &mut log, &mut opened_at, 10),
+            0
+        );
+        assert_eq!(opened_at, 10);
+        assert_eq!(open_ledger(&users, &mut log, &mut opened_at, 20), 0);
+        assert_eq!(ledger_mismatches(&users, &log).len(), 1);
+
+        let mut opened_at = 0;
+        assert_eq!(open_ledger(&users, &mut log, &mut opened_at, 30), 2);
+        assert!(ledger_mismatches(&users, &log).is_empty());
+        assert_eq!(open_ledger(&users, &mut log, &mut opened_at, 40), 0);
+
+        // Журнал у файлі — JSON-рядки; пошкоджений хвіст пропускається
+        let mut text: String = log
+            .iter()
+            .map(|t| serde_json::to_string(t).unwrap() + "\n")
+            .collect();
+        text.push_str("{\"user_id\":\"1\",\"del");
+        assert_eq!(parse_ledger_lines(&text).len(), log.len());
+
+        log.push(entry("2", 30, "daily"));
+        assert_eq!(
+            ledger_mismatches(&users, &log),
+            vec![("2".to_string(), "гаманець", 100, 130)]
+        );
+        users.get_mut("2").unwrap().chips = 130;
+        assert!(ledger_mismatches(&users, &log).is_empty());
+
+        let (lines, pages) = ledger_page(&log, "2", 0);
+        assert_eq!(pages, 1);
+        assert!(lines[0].starts_with("`+30` Щоденна нагорода"));
+    }
+
+    #[test]
+    fn pay_caps_fees_and_circular_transfers() {
+        let sent = |from: &str, to: &str, amount: i64, at: i64| Transaction {
+            user_id: from.to_string(),
+            delta: -amount,
+            reason: "pay".to_string(),
+            reference: Some(to.to_string()),
+            at,
+            bank: false,
+        };
+        let log = vec![
+            sent("a", "b", 100, 10),
+            sent("b", "c", 50, 20),
+            sent("c", "a", 40, 30),
+            sent("a", "d", 70, 5),
+        ];
+        assert_eq!(pay_sent_since(&log, "a", 6), 100);
+        assert_eq!(pay_sent_since(&log, "a", 0), 170);
+
+        // c → a замикає коло a → b → c → a
+        assert_eq!(
+            transfer_cycle(&log, "c", "a", 0),
+            Some(vec![
+                "c".to_string(),
+                "a".to_string(),
+                "b".to_string(),
+                "c".to_string()
+            ])
+        );
+        assert_eq!(
+            transfer_cycle(&log, "b", "a", 0),
+            Some(vec!["b".to_string(), "a".to_string(), "b".to_string()])
+        );
+        assert_eq!(transfer_cycle(&log, "c", "a", 15), None);
+        assert_eq!(transfer_cycle(&log, "d", "e", 0), None);
+
+        assert_eq!(transfer_fee(1000, 2.5), 25);
+        assert_eq!(transfer_fee(10, 2.5), 1);
+        assert_eq!(transfer_fee(10, 0.0), 0);
+    }
+
+    #[test]
+    fn bank_interest_and_economy_stats() {
+        assert_eq!(bank_interest(5_000, 1.0, 10_000), 50);
+        assert_eq!(bank_interest(50_000, 1.0, 10_000), 100);
+        assert_eq!(bank_interest(99, 1.0, 10_000), 0);
+
+        // Порожній банк не накопичує відсотків: після зняття всього і
+        // нового внеску через 10 днів перше нарахування нічого не дає
+        let config = EconomyConfig::default();
+        let day = 86400;
+        let mut profile = create_default_profile();
+        profile.chips = 10_000;
+        assert!(apply_bank_move(&mut profile, 5_000, true, day).is_ok());
+        assert_eq!(accrue_profile_interest(&mut profile, &config, 2 * day), 50);
+        assert!(apply_bank_move(&mut profile, 5_050, false, 2 * day).is_ok());
+        assert_eq!(profile.bank_interest_at, 0);
+        assert!(apply_bank_move(&mut profile, 10_000, true, 12 * day).is_ok());
+        assert_eq!(
+            accrue_profile_interest(&mut profile, &config, 12 * day + 3600),
+            0
+        );
+        assert_eq!(
+            accrue_profile_interest(&mut profile, &config, 13 * day),
+            100
+        );
+        assert_eq!(apply_bank_move(&mut profile, 100, true, 13 * day), Err(50));
+
+        // Внесок посеред дня в банк з грошима: спершу виплачуються завершені
+        // дні, а нові гривні не отримують відсотків за повний день
+        let mut profile = create_default_profile();
+        profile.chips = 20_000;
+        assert!(apply_bank_move(&mut profile, 1_000, true, day).is_ok());
+        let settle = |p: &mut UserProfile, now: i64| accrue_profile_interest(p, &config, now);
+        assert_eq!(settle(&mut profile, 2 * day + 20 * 3600), 10);
+        assert!(apply_bank_move(&mut profile, 9_000, true, 2 * day + 20 * 3600).is_ok());
+        assert_eq!(settle(&mut profile, 3 * day), 0);
+        assert_eq!(settle(&mut profile, 3 * day + 20 * 3600), 100);
+
+        let entry = |user: &str, delta: i64, reason: &str, bank: bool| Transaction {
+            user_id: user.to_string(),
+            delta,
+            reason: reason.to_string(),
+            reference: None,
+            at: 100,
+            bank,
+        };
+        let log = vec![
+            entry("a", -300, "bank_deposit", false),
+            entry("a", 300, "bank_deposit", true),
+            entry("a", 3, "bank_interest", true),
+            entry("b", 120, "daily", false),
+            entry("b", -50, "casino", false),
+            entry("b", 500, "daily", false),
+        ];
+        let mut users = HashMap::new();
+        for (id, chips, bank) in [("a", 0, 303), ("b", 670, 0), ("c", 10, 0)] {
+            let mut p = create_default_profile();
+            p.chips = chips;
+            p.bank = bank;
+            users.insert(id.to_string(), p);
+        }
+        // Банк рахується окремо від гаманця
+        assert_eq!(
+            ledger_mismatches(&users, &log),
+            vec![
+                ("a".to_string(), "гаманець", 0, -200),
+                ("c".to_string(), "гаманець", 10, 100)
+            ]
+        );
+
+        let stats = economy_stats(&users, &log, 50);
+        assert_eq!((stats.wallets, stats.banks), (680, 303));
+        assert_eq!(stats.median, 303);
+        assert_eq!(stats.top[0], ("b".to_string(), 670));
+        assert_eq!(
+            stats.flows,
+            vec![
+                ("daily".to_string(), 620),
+                ("casino".to_string(), -50),
+                ("bank_interest".to_string(), 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn daily_streak_grace_and_multipliers() {
+        let grace = 12 * 3600;
+        let day = 86400;
+        assert_eq!(next_daily_streak(0, 0, day, grace), 1);
+        assert_eq!(next_daily_streak(4, day, 2 * day, grace), 5);
+        // Запізнення на 10 годин — серія триває, на 13 — обривається
+        assert_eq!(next_daily_streak(4, day, 2 * day + 10 * 3600, grace), 5);
+        assert_eq!(next_daily_streak(4, day, 2 * day + 13 * 3600, grace), 1);
+
+        let mut profile = create_default_profile();
+        profile.daily_streak = 7;
+        profile.last_daily = day;
+        assert_eq!(current_daily_streak(&profile, 2 * day, grace), 7);
+        assert_eq!(current_daily_streak(&profile, 3 * day, grace), 0);
+
+        let config = EconomyConfig::default();
+        assert_eq!(streak_bonus_percent(&config, 1), 0.0);
+        assert_eq!(streak_bonus_percent(&config, 4), 30.0);
+        assert_eq!(streak_bonus_percent(&config, 40), 100.0);
+        assert_eq!(reward_multiplier(&config, 5, false), 1.0);
+        assert_eq!(reward_multiplier(&config, 20, false), 1.1);
+        assert_eq!(reward_multiplier(&config, 0, true), 1.5);
+    }
+
+    #[test]
+    fn shop_purchase_inventory_and_legacy_boosters() {
+        let mut items = default_shop_items();
+        items[0].stock = Some(1);
+        items[0].min_level = 3;
+        assert_eq!(find_shop_item(&items, " X2 xp бустер"), Some(0));
+
+        let mut profile = create_default_profile();
+        profile.chips = 5000;
+        assert!(purchase_item(&mut items[0], &mut profile, "g").is_err());
+        profile.level = 3;
+        assert!(purchase_item(&mut items[0], &mut profile, "g").is_ok());
+        assert_eq!((profile.chips, items[0].stock), (3000, Some(0)));
+        // Товар закінчився, а на x5 не вистачає гривень
+        assert!(purchase_item(&mut items[0], &mut profile, "g").is_err());
+        assert!(purchase_item(&mut items[1], &mut profile, "g").is_err());
+        assert_eq!(profile.chips, 3000);
+
+        profile.add_to_inventory("g", &items[0]);
+        assert_eq!(profile.inventory[0].quantity, 2);
+        assert!(profile
+            .take_from_inventory("other", "x2 XP бустер")
+            .is_none());
+        assert!(profile.take_from_inventory("g", "x2 XP бустер").is_some());
+        assert!(profile.take_from_inventory("g", "x2 XP бустер").is_some());
+        assert!(profile.inventory.is_empty());
+
+        assert!(
+            build_shop_kind(ShopKindChoice::TempRole, Some("1".into()), None, None, None).is_err()
+        );
+        assert_eq!(
+            build_shop_kind(
+                ShopKindChoice::TempRole,
+                Some("1".into()),
+                None,
+                Some(604800),
+                None
+            ),
+            Ok(ShopItemKind::TempRole {
+                role_id: "1".to_string(),
+                duration_secs: 604800
+            })
+        );
+        let mut grants = Vec::new();
+        assert_eq!(
+            extend_role_grant(&mut grants, "g", "u", "1", "VIP", 100, 1000),
+            1100
+        );
+        assert_eq!(
+            extend_role_grant(&mut grants, "g", "u", "1", "VIP", 100, 1050),
+            1200
+        );
+        assert_eq!(grants.len(), 1);
+
+        let now = Utc::now().timestamp();
+        let mut legacy = create_default_profile();
+        legacy.xp_booster_x2_until = now + 3600;
+        legacy.xp_booster_x5_until = now - 3600;
+        legacy.migrate_legacy_boosters();
+        assert_eq!(legacy.xp_boosters.len(), 2);
+        assert_eq!(xp_multiplier(&legacy, None, &[], now).total, 2.0);
+    }
+
+    #[test]
+    fn xp_boosters_stack_and_extend() {
+        let now = 1_000_000;
+        let mut profile = create_default_profile();
+        assert_eq!(profile.add_xp_boost(2, 3600, now), now + 3600);
+        // Повторна покупка продовжує, а не перезапускає таймер
+        assert_eq!(profile.add_xp_boost(2, 3600, now + 600), now + 7200);
+        profile.add_xp_boost(3, 3600, now);
+        assert_eq!(profile.xp_boosters.len(), 2);
+
+        let mut settings = XpBoostSettings::default();
+        settings.role_multipliers.insert("vip".to_string(), 1.5);
+        settings.server_boosts.push(ServerBoost {
+            name: "Вихідні".to_string(),
+            multiplier: 2.0,
+            until: now + 60,
+            started_by: "1".to_string(),
+        });
+        let roles = vec!["vip".to_string()];
+
+        let additive = xp_multiplier(&profile, Some(&settings), &roles, now);
+        assert_eq!(additive.sources.len(), 4);
+        assert_eq!(additive.total, 5.5);
+        settings.stacking = BoostStacking::Highest;
+        assert_eq!(
+            xp_multiplier(&profile, Some(&settings), &[], now).total,
+            3.0
+        );
+        // Множення обмежене максимумом
+        settings.stacking = BoostStacking::Multiplicative;
+        assert_eq!(
+            xp_multiplier(&profile, Some(&settings), &roles, now).total,
+            10.0
+        );
+        // Після закінчення подій і бустерів лишається лише роль
+        let later = xp_multiplier(&profile, Some(&settings), &roles, now + 7200);
+        assert_eq!(later.total, 1.5);
+        assert_eq!(boosted_xp(MSG_XP_AMOUNT, later.total), 3);
+        assert_eq!(xp_multiplier(&profile, None, &[], now + 7200).total, 1.0);
+    }
+
+    #[test]
+    fn temp_role_reminder_and_renewal() {
+        let week = 7 * 86400;
+        let mut grants = Vec::new();
+        let expires = extend_role_grant(&mut grants, "g", "u", "42", "VIP колір", week, 0);
+        assert!(!role_grant_reminder_due(&grants[0], expires - 2 * 86400));
+        assert!(role_grant_reminder_due(&grants[0], expires - 3600));
+        assert!(!role_grant_reminder_due(&grants[0], expires));
+        // Для короткої ролі нагадування — в останню чверть терміну
+        let mut short = grants[0].clone();
+        short.duration_secs = 3600;
+        assert!(!role_grant_reminder_due(&short, short.expires_at - 1800));
+        assert!(role_grant_reminder_due(&short, short.expires_at - 900));
+
+        // Продовження скидає позначку нагадування
+        grants[0].reminded = true;
+        assert_eq!(
+            extend_role_grant(
+                &mut grants,
+                "g",
+                "u",
+                "42",
+                "VIP колір",
+                week,
+                expires - 3600
+            ),
+            expires + week
+        );
+        assert!(!grants[0].reminded);
+
+        let mut items = vec![ShopItem {
+            name: "VIP колір".to_string(),
+            description: String::new(),
+            price: 500,
+            kind: ShopItemKind::TempRole {
+                role_id: "42".to_string(),
+                duration_secs: week,
+            },
+            stock: None,
+            min_level: 0,
+        }];
+        assert_eq!(renewal_item(&items, &grants[0]), Some((0, week)));
+        items[0].kind = ShopItemKind::Role {
+            role_id: "42".to_string(),
+        };
+        assert_eq!(renewal_item(&items, &grants[0]), None);
+
+        let mut profile = create_default_profile();
+        profile.chips = 600;
+        assert!(charge_for_item(&mut items[0], &mut profile).is_ok());
+        assert!(charge_for_item(&mut items[0], &mut profile).is_err());
+        assert_eq!(profile.chips, 100);
+        assert!(profile.inventory.is_empty());
+    }
 }